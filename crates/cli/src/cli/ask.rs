@@ -31,7 +31,10 @@ pub async fn run(args: AskArgs, agent_id: &str) -> Result<()> {
     };
 
     let mut agent = Agent::new(agent_config, &config, memory).await?;
-    agent.extend_tools(crate::tools::create_cli_tools(&config)?);
+    agent.extend_tools(crate::tools::create_cli_tools(
+        &config,
+        agent.memory_manager(),
+    )?)?;
     agent.new_session().await?;
 
     let workspace_lock = WorkspaceLock::new()?;
@@ -126,7 +126,10 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
     };
 
     let mut agent = Agent::new(agent_config, &config, memory).await?;
-    agent.extend_tools(crate::tools::create_cli_tools(&config)?);
+    agent.extend_tools(crate::tools::create_cli_tools(
+        &config,
+        agent.memory_manager(),
+    )?)?;
     let workspace_lock = WorkspaceLock::new()?;
 
     // Determine session to use
@@ -441,7 +444,14 @@ pub async fn run(args: ChatArgs, agent_id: &str) -> Result<()> {
                             println!("\n[{}]", tc.name);
                         }
 
-                        if agent.requires_approval(&tc.name) {
+                        if agent.requires_approval(&tc.name, &tc.arguments) {
+                            if let Some(risk) =
+                                agent.approval_policy().bash_risk(&tc.name, &tc.arguments)
+                                && !risk.reasons.is_empty()
+                            {
+                                println!("Risk: {} ({})", risk.level, risk.reasons.join(", "));
+                            }
+
                             // Prompt for approval
                             print!("Execute {}? [y/N]: ", tc.name);
                             stdout.flush()?;
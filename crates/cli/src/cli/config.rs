@@ -79,7 +79,8 @@ fn get_config(key: &str) -> Result<()> {
 }
 
 fn set_config(key: &str, value: &str) -> Result<()> {
-    let mut config = Config::load()?;
+    // Edit the file as written so profile overrides aren't saved into the base
+    let mut config = Config::load_base()?;
     config.set_value(key, value)?;
     config.save()?;
     println!("Set {} = {}", key, value);
@@ -78,7 +78,7 @@ pub fn daemonize_and_run(agent_id: &str) -> Result<()> {
         fs::remove_file(&pid_file)?;
     }
 
-    let log_file = get_log_file(config.logging.retention_days)?;
+    let log_file = get_log_file(&config)?;
 
     // Print startup info before daemonizing
     println!(
@@ -463,12 +463,12 @@ fn get_pid_file() -> Result<PathBuf> {
     Ok(paths.pid_file())
 }
 
-fn get_log_file(retention_days: u32) -> Result<PathBuf> {
-    let paths = localgpt_core::paths::Paths::resolve()?;
-    let logs_dir = paths.logs_dir();
+fn get_log_file(config: &Config) -> Result<PathBuf> {
+    let logs_dir = config.paths.logs_dir();
     fs::create_dir_all(&logs_dir)?;
 
     // Prune old logs only if retention_days > 0
+    let retention_days = config.logging.retention_days;
     if retention_days > 0 {
         prune_old_logs(&logs_dir, retention_days as i64);
     }
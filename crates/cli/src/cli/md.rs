@@ -168,6 +168,7 @@ async fn show_audit(json_output: bool, filter: Option<String>) -> Result<()> {
 
     // Verify chain integrity (on full log, not filtered)
     let broken = security::verify_audit_chain(state_dir)?;
+    let gaps = security::verify_audit_sequence(state_dir)?;
 
     if json_output {
         let output = serde_json::to_string_pretty(&entries)?;
@@ -217,6 +218,14 @@ async fn show_audit(json_output: bool, filter: Option<String>) -> Result<()> {
         } else {
             println!("Chain integrity: BROKEN at {} position(s)", broken.len());
         }
+        if gaps.is_empty() {
+            println!("Sequence:        CONTIGUOUS");
+        } else {
+            println!(
+                "Sequence:        {} gap(s) — entries lost or duplicated",
+                gaps.len()
+            );
+        }
     }
 
     Ok(())
@@ -274,7 +283,18 @@ async fn show_status() -> Result<()> {
         } else {
             "CHAIN BROKEN"
         };
-        println!("  Audit Log:  {} entries, {}", entries.len(), chain_status);
+        let gaps = security::verify_audit_sequence(state_dir)?;
+        let seq_status = if gaps.is_empty() {
+            String::new()
+        } else {
+            format!(", {} SEQUENCE GAP(S)", gaps.len())
+        };
+        println!(
+            "  Audit Log:  {} entries, {}{}",
+            entries.len(),
+            chain_status,
+            seq_status
+        );
     }
 
     // Protected files
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
+use std::io::Write;
 
 use localgpt_core::config::Config;
 use localgpt_core::memory::MemoryManager;
@@ -106,10 +107,19 @@ async fn reindex_memory(memory: &MemoryManager, force: bool) -> Result<()> {
     // Generate embeddings if provider is configured
     if memory.has_embeddings() {
         println!("\nGenerating embeddings...");
-        let (processed, embedded) = memory.generate_embeddings(50).await?;
-        if processed > 0 {
-            println!("  Chunks processed: {}", processed);
-            println!("  Embeddings generated: {}", embedded);
+        let progress = memory
+            .generate_embeddings_with_progress(|p| {
+                print!("\r  Embedded {}/{} chunks", p.embedded, p.total);
+                let _ = std::io::stdout().flush();
+            })
+            .await?;
+        if progress.processed > 0 {
+            println!();
+            println!("  Chunks processed: {}", progress.processed);
+            println!(
+                "  Embeddings generated: {} ({} from cache)",
+                progress.embedded, progress.cache_hits
+            );
         } else {
             println!("  All chunks already have embeddings");
         }
@@ -39,6 +39,10 @@ pub struct Cli {
         env = "LOCALGPT_AGENT"
     )]
     pub agent: String,
+
+    /// Config profile to apply over the base config
+    #[arg(long, global = true, env = "LOCALGPT_CONFIG_PROFILE")]
+    pub config_profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 
+use localgpt_core::agent::truncate_at_char_boundary;
 use localgpt_core::config::Config;
 use localgpt_sandbox::{SandboxLevel, build_policy, detect_capabilities, run_sandboxed};
 
@@ -96,7 +97,7 @@ async fn run_test() -> Result<()> {
             println!(
                 "FAIL (exit={}, output={})",
                 code,
-                &output[..output.len().min(100)]
+                truncate_at_char_boundary(&output, 100)
             );
             failed += 1;
         }
@@ -232,7 +233,7 @@ async fn run_test() -> Result<()> {
             println!(
                 "FAIL (exit={}, output={})",
                 code,
-                &output[..output.len().min(100)]
+                truncate_at_char_boundary(&output, 100)
             );
             failed += 1;
         }
@@ -35,6 +35,7 @@ pub enum UiMessage {
 
 /// Message from worker to UI
 #[derive(Debug, Clone)]
+#[allow(dead_code)] // Tool call ids and warnings are not shown yet
 pub enum WorkerMessage {
     /// Agent is ready
     Ready {
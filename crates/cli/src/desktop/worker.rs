@@ -86,7 +86,10 @@ async fn worker_loop(
     };
 
     let mut agent = Agent::new(agent_config, &config, memory).await?;
-    agent.extend_tools(crate::tools::create_cli_tools(&config)?);
+    agent.extend_tools(crate::tools::create_cli_tools(
+        &config,
+        agent.memory_manager(),
+    )?)?;
     agent.new_session().await?;
 
     // Send ready message
@@ -105,7 +108,7 @@ async fn worker_loop(
     let _ = tx.send(WorkerMessage::Status(agent.session_status()));
 
     // Track tools requiring approval
-    let approval_tools: Vec<String> = agent.approval_required_tools().to_vec();
+    let approval_policy = agent.approval_policy().clone();
 
     // Main loop
     while let Ok(msg) = rx.recv() {
@@ -131,7 +134,7 @@ async fn worker_loop(
                                         arguments,
                                     } => {
                                         // Check if this tool requires approval
-                                        if approval_tools.contains(&name) {
+                                        if approval_policy.requires_approval(&name, &arguments) {
                                             // Collect for approval
                                             pending_tools.push(ToolCall {
                                                 id,
@@ -20,6 +20,13 @@ fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    // Config::load() reads the profile from the environment. Set it here,
+    // before any threads are spawned.
+    if let Some(ref profile) = cli.config_profile {
+        // SAFETY: still single-threaded at this point
+        unsafe { std::env::set_var(localgpt_core::config::PROFILE_ENV, profile) };
+    }
+
     // Handle Gen mode specially — Bevy must own the main thread (no tokio runtime here)
     #[cfg(feature = "gen")]
     if let Commands::Gen(args) = cli.command {
@@ -1,4 +1,4 @@
-//! CLI-only tools: bash, read_file, write_file, edit_file.
+//! CLI-only tools: bash, read_file, write_file, edit_file, hash_file.
 //!
 //! These tools are not included in `localgpt-core` because they have
 //! platform-specific dependencies (sandbox) and security implications
@@ -8,20 +8,24 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::{Value, json};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::debug;
 
 use localgpt_core::agent::providers::ToolSchema;
-use localgpt_core::agent::tools::Tool;
+use localgpt_core::agent::tools::{Tool, create_memory_write_tools};
+use localgpt_core::agent::truncate_at_char_boundary;
 use localgpt_core::config::Config;
+use localgpt_core::memory::MemoryManager;
 use localgpt_core::security;
 use localgpt_sandbox::{self, SandboxPolicy};
 
-/// Create just the CLI-specific dangerous tools (bash, read_file, write_file, edit_file).
+/// Create just the CLI-specific dangerous tools (bash, read_file, write_file, edit_file,
+/// memory_write, memory_reindex).
 ///
 /// Use with `agent.extend_tools()` after `Agent::new()` to add these to an
 /// agent that already has safe tools.
-pub fn create_cli_tools(config: &Config) -> Result<Vec<Box<dyn Tool>>> {
+pub fn create_cli_tools(config: &Config, memory: Arc<MemoryManager>) -> Result<Vec<Box<dyn Tool>>> {
     let workspace = config.workspace_path();
     let state_dir = config.paths.state_dir.clone();
 
@@ -47,39 +51,568 @@ pub fn create_cli_tools(config: &Config) -> Result<Vec<Box<dyn Tool>>> {
         None
     };
 
-    Ok(vec![
+    let denied_paths = DeniedPaths::new(
+        sandbox_policy.clone(),
+        state_dir.clone(),
+        config.security.generic_path_errors(),
+    );
+
+    let secret_scanner = security::SecretScanner::from_config(config)?;
+    let mutation_audit = MutationAudit::new(
+        state_dir.clone(),
+        config.security.audit_tool_mutations(),
+        secret_scanner.clone(),
+    );
+
+    let mut tools: Vec<Box<dyn Tool>> = vec![
         Box::new(BashTool::new(
-            config.tools.bash_timeout_ms,
+            BashToolOptions::from_config(config, sandbox_policy)?,
             state_dir.clone(),
-            sandbox_policy.clone(),
+            workspace.clone(),
+            denied_paths.clone(),
+            secret_scanner.clone(),
+            mutation_audit.clone(),
+        )),
+        Box::new(ReadFileTool::new(
+            denied_paths.clone(),
+            config.tools.read_file_max_bytes,
+        )),
+        Box::new(ReadGlobTool::new(denied_paths.clone())),
+        Box::new(HashFileTool::new(denied_paths.clone())),
+        Box::new(GrepTool::new(workspace.clone(), denied_paths.clone())),
+        Box::new(ListDirectoryTool::new(
+            workspace.clone(),
+            denied_paths.clone(),
         )),
-        Box::new(ReadFileTool::new(sandbox_policy.clone())),
         Box::new(WriteFileTool::new(
             state_dir.clone(),
-            sandbox_policy.clone(),
+            denied_paths.clone(),
+            mutation_audit.clone(),
+        )),
+        Box::new(EditFileTool::new(
+            state_dir.clone(),
+            denied_paths.clone(),
+            mutation_audit.clone(),
+        )),
+        Box::new(MoveFileTool::new(
+            state_dir.clone(),
+            denied_paths.clone(),
+            mutation_audit.clone(),
         )),
-        Box::new(EditFileTool::new(state_dir, sandbox_policy)),
-    ])
+        Box::new(DeleteFileTool::new(
+            state_dir,
+            denied_paths,
+            config.tools.hard_delete,
+            mutation_audit.always_on(),
+        )),
+    ];
+    tools.extend(create_memory_write_tools(memory));
+
+    Ok(tools)
+}
+
+/// Audit trail of what tools actually changed, complementing the
+/// `WriteBlocked` entries for what they were stopped from changing.
+///
+/// Off unless `security.audit_tool_mutations` (default: `strict_policy`).
+/// Details pass through the configured secret scanner, including any
+/// `security.secret_patterns`, before they are written.
+#[derive(Clone)]
+pub struct MutationAudit {
+    state_dir: PathBuf,
+    enabled: bool,
+    scanner: security::SecretScanner,
+}
+
+impl MutationAudit {
+    pub fn new(state_dir: PathBuf, enabled: bool, scanner: security::SecretScanner) -> Self {
+        Self {
+            state_dir,
+            enabled,
+            scanner,
+        }
+    }
+
+    /// The same audit trail, recording regardless of
+    /// `security.audit_tool_mutations`. Deletes are always audited.
+    pub fn always_on(&self) -> Self {
+        Self {
+            enabled: true,
+            ..self.clone()
+        }
+    }
+
+    pub fn record(&self, tool: &str, detail: &str) {
+        if !self.enabled {
+            return;
+        }
+        let detail = self.scanner.redact_secrets(detail);
+        if let Err(e) = security::append_audit_entry_with_detail(
+            &self.state_dir,
+            security::AuditAction::ToolMutation,
+            "",
+            &format!("tool:{}", tool),
+            Some(&detail),
+        ) {
+            tracing::warn!("Failed to audit {} call: {}", tool, e);
+        }
+    }
+}
+
+/// Sandbox credential-directory check shared by the file tools.
+///
+/// Every denial is written to the audit log with the resolved path. With
+/// `generic_errors`, the error returned to the model is just "Path not
+/// permitted", so it can't probe which denied paths exist or what they
+/// resolve to.
+#[derive(Clone)]
+pub struct DeniedPaths {
+    policy: Option<SandboxPolicy>,
+    state_dir: PathBuf,
+    generic_errors: bool,
+}
+
+impl DeniedPaths {
+    pub fn new(policy: Option<SandboxPolicy>, state_dir: PathBuf, generic_errors: bool) -> Self {
+        Self {
+            policy,
+            state_dir,
+            generic_errors,
+        }
+    }
+
+    pub fn is_denied(&self, path: &Path) -> bool {
+        self.policy
+            .as_ref()
+            .is_some_and(|policy| localgpt_sandbox::policy::is_path_denied(path, policy))
+    }
+
+    /// Fail if `path` is denied. `action` completes "Cannot <action> denied
+    /// directory", e.g. "read file in".
+    pub fn check(&self, tool: &str, action: &str, path: &Path) -> Result<()> {
+        if !self.is_denied(path) {
+            return Ok(());
+        }
+        self.refuse(
+            tool,
+            path,
+            format!(
+                "Cannot {} denied directory: {}. This path is blocked by sandbox policy.",
+                action,
+                path.display()
+            ),
+        )
+    }
+
+    /// [`check`](Self::check), and also fail if a denied directory lies
+    /// under `path`, for tools that move or delete whole directories.
+    pub fn check_with_contents(&self, tool: &str, action: &str, path: &Path) -> Result<()> {
+        self.check(tool, action, path)?;
+        let contains_denied = self
+            .policy
+            .as_ref()
+            .is_some_and(|policy| localgpt_sandbox::policy::contains_denied_path(path, policy));
+        if !contains_denied {
+            return Ok(());
+        }
+        self.refuse(
+            tool,
+            path,
+            format!(
+                "Cannot run {} on {}: it contains a directory blocked by sandbox policy.",
+                tool,
+                path.display()
+            ),
+        )
+    }
+
+    /// Audit a denial of `path` and fail with `message`, or with a generic
+    /// error when `generic_errors` is set.
+    fn refuse(&self, tool: &str, path: &Path, message: String) -> Result<()> {
+        let detail = format!(
+            "Denied path: {}",
+            localgpt_sandbox::policy::resolve_path(path)
+                .unwrap_or_else(|_| path.to_path_buf())
+                .display()
+        );
+        let _ = security::append_audit_entry_with_detail(
+            &self.state_dir,
+            security::AuditAction::PathDenied,
+            "",
+            &format!("tool:{}", tool),
+            Some(&detail),
+        );
+
+        if self.generic_errors {
+            anyhow::bail!("Path not permitted");
+        }
+        Err(anyhow::anyhow!(message))
+    }
+}
+
+/// Environment variable pointing bash commands at the session scratch directory.
+pub const SESSION_TMP_ENV: &str = "LOCALGPT_TMP";
+
+/// Per-session scratch directory under `<state_dir>/tmp/`.
+///
+/// Exported to bash as `$LOCALGPT_TMP` so scratch files the agent creates
+/// don't litter the user's tree. Removed when dropped.
+pub struct SessionTempDir {
+    path: PathBuf,
+}
+
+impl SessionTempDir {
+    pub fn create(state_dir: &Path) -> Result<Self> {
+        let name = format!(
+            "session-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.3f")
+        );
+        let path = state_dir.join("tmp").join(name);
+        fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SessionTempDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            debug!(
+                "Failed to remove session temp dir {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Shell the bash tool runs commands with: `<program> <flag> <command>`.
+#[derive(Debug, Clone)]
+pub struct Shell {
+    pub program: PathBuf,
+    pub flag: String,
+}
+
+impl Shell {
+    /// Resolve `tools.shell` to an executable file. Bare names are looked
+    /// up on PATH; the result is absolute so the sandbox child can exec it.
+    pub fn resolve(program: &str, flag: &str) -> Result<Self> {
+        let expanded = PathBuf::from(shellexpand::tilde(program).to_string());
+        let candidates: Vec<PathBuf> = if expanded.components().count() > 1 {
+            vec![expanded]
+        } else {
+            std::env::var_os("PATH")
+                .map(|path| {
+                    std::env::split_paths(&path)
+                        .map(|dir| dir.join(&expanded))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let program = candidates
+            .into_iter()
+            .find(|p| is_executable(p))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Shell '{}' not found or not executable; set tools.shell to an installed shell (e.g. \"sh\")",
+                    program
+                )
+            })?;
+
+        // Not canonicalized: multi-call binaries (busybox) dispatch on the name
+        Ok(Self {
+            program: std::path::absolute(&program).unwrap_or(program),
+            flag: flag.to_string(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
 }
 
 // Bash Tool
+
+/// How bash commands are run, from `[tools]`, `[security]` and the
+/// sandbox policy.
+pub struct BashToolOptions {
+    /// Timeout when the call doesn't give one.
+    pub default_timeout_ms: u64,
+
+    /// Output cap for unsandboxed commands (0 = unlimited).
+    pub max_output_bytes: usize,
+
+    pub shell: Shell,
+
+    /// Run commands inside this sandbox, if any.
+    pub sandbox_policy: Option<SandboxPolicy>,
+
+    /// Drop inherited environment variables whose value looks like a secret.
+    pub scrub_secret_env: bool,
+
+    pub env_filter: security::EnvFilter,
+
+    /// Run commands in an empty network namespace (Linux only).
+    pub isolate_network: bool,
+
+    /// Run commands as this user instead of our own (Unix only).
+    pub run_as: Option<localgpt_sandbox::RunAs>,
+
+    pub risk_classifier: security::RiskClassifier,
+}
+
+impl BashToolOptions {
+    pub fn from_config(config: &Config, sandbox_policy: Option<SandboxPolicy>) -> Result<Self> {
+        Ok(Self {
+            default_timeout_ms: config.tools.bash_timeout_ms,
+            max_output_bytes: config.tools.bash_max_output_bytes,
+            shell: Shell::resolve(&config.tools.shell, &config.tools.shell_flag)?,
+            sandbox_policy,
+            scrub_secret_env: config.security.scrub_secret_env,
+            env_filter: security::EnvFilter::from_config(config),
+            isolate_network: config.security.isolate_bash_network,
+            run_as: config
+                .security
+                .bash_run_as_uid
+                .map(|uid| localgpt_sandbox::RunAs {
+                    uid,
+                    gid: config.security.bash_run_as_gid.unwrap_or(uid),
+                }),
+            risk_classifier: security::RiskClassifier::new(&config.security.high_risk_patterns)?,
+        })
+    }
+}
+
 pub struct BashTool {
     default_timeout_ms: u64,
+    max_output_bytes: usize,
     state_dir: PathBuf,
+    workspace: PathBuf,
+    denied_paths: DeniedPaths,
+    shell: Shell,
     sandbox_policy: Option<SandboxPolicy>,
+    temp_dir: Option<SessionTempDir>,
+    scrub_secret_env: bool,
+    env_filter: security::EnvFilter,
+    isolate_network: bool,
+    run_as: Option<localgpt_sandbox::RunAs>,
+    risk_classifier: security::RiskClassifier,
+    secret_scanner: security::SecretScanner,
+    mutation_audit: MutationAudit,
 }
 
 impl BashTool {
     pub fn new(
-        default_timeout_ms: u64,
+        options: BashToolOptions,
         state_dir: PathBuf,
-        sandbox_policy: Option<SandboxPolicy>,
+        workspace: PathBuf,
+        denied_paths: DeniedPaths,
+        secret_scanner: security::SecretScanner,
+        mutation_audit: MutationAudit,
     ) -> Self {
+        let BashToolOptions {
+            default_timeout_ms,
+            max_output_bytes,
+            shell,
+            sandbox_policy,
+            scrub_secret_env,
+            env_filter,
+            isolate_network,
+            run_as,
+            risk_classifier,
+        } = options;
+        let temp_dir = match SessionTempDir::create(&state_dir) {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                tracing::warn!("Failed to create session temp dir: {}", e);
+                None
+            }
+        };
+
+        // Sandboxed commands must be able to write to the scratch directory
+        // and run the configured shell
+        let sandbox_policy = sandbox_policy.map(|mut policy| {
+            if let Some(ref dir) = temp_dir {
+                policy.extra_write_paths.push(dir.path().to_path_buf());
+            }
+            policy.read_only_paths.push(shell.program.clone());
+            policy.shell = shell.program.clone();
+            policy.shell_flag = shell.flag.clone();
+            policy
+        });
+
+        // The unprivileged user must be able to write to the scratch directory
+        #[cfg(unix)]
+        if let (Some(run_as), Some(dir)) = (run_as, &temp_dir)
+            && let Err(e) = std::os::unix::fs::chown(dir.path(), Some(run_as.uid), Some(run_as.gid))
+        {
+            tracing::warn!(
+                "Failed to hand session temp dir to uid {}: {}",
+                run_as.uid,
+                e
+            );
+        }
+
+        let isolate_network = if isolate_network && !localgpt_sandbox::NETWORK_NAMESPACE_SUPPORTED {
+            tracing::warn!(
+                "isolate_bash_network is only supported on Linux; bash commands keep network access"
+            );
+            false
+        } else {
+            isolate_network
+        };
+
         Self {
             default_timeout_ms,
+            max_output_bytes,
             state_dir,
+            workspace,
+            denied_paths,
+            shell,
             sandbox_policy,
+            temp_dir,
+            scrub_secret_env,
+            env_filter,
+            isolate_network,
+            run_as,
+            risk_classifier,
+            secret_scanner,
+            mutation_audit,
+        }
+    }
+
+    /// Names of inherited environment variables to drop from the child.
+    ///
+    /// Variables the `env_filter` policy rejects by name are always dropped.
+    /// With `scrub_secret_env`, any variable whose value matches a known
+    /// secret pattern is dropped too. Only names are logged, never values.
+    fn env_to_remove(&self) -> Vec<String> {
+        if !self.scrub_secret_env && self.env_filter.policy() == security::EnvPolicy::Inherit {
+            return Vec::new();
+        }
+
+        let dropped: Vec<String> = std::env::vars_os()
+            .filter_map(|(key, value)| {
+                let key = key.to_string_lossy();
+                let denied = !self.env_filter.keeps(&key)
+                    || (self.scrub_secret_env
+                        && value
+                            .to_str()
+                            .is_some_and(|v| self.secret_scanner.contains_secret(v)));
+                denied.then(|| key.into_owned())
+            })
+            .collect();
+
+        if !dropped.is_empty() {
+            tracing::info!(
+                "Dropped {} denied or secret-like env var(s) from bash: {}",
+                dropped.len(),
+                dropped.join(", ")
+            );
+        }
+        dropped
+    }
+
+    /// Resolve the `cwd` argument: `~` is expanded, relative paths are
+    /// taken from the workspace, and the result must be an existing,
+    /// non-denied directory. Without one (or with `.`), the workspace path
+    /// is used as configured, even if it doesn't exist yet.
+    fn resolve_cwd(&self, cwd: Option<&str>) -> Result<PathBuf> {
+        let Some(cwd) = cwd.filter(|cwd| *cwd != ".") else {
+            self.denied_paths
+                .check("bash", "run commands in", &self.workspace)?;
+            return Ok(self.workspace.clone());
+        };
+        let path = PathBuf::from(shellexpand::tilde(cwd).to_string());
+        let path = if path.is_relative() {
+            self.workspace.join(path)
+        } else {
+            path
+        };
+        let path = path
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Invalid cwd {}: {}", path.display(), e))?;
+        if !path.is_dir() {
+            anyhow::bail!("cwd is not a directory: {}", path.display());
+        }
+        self.denied_paths.check("bash", "run commands in", &path)?;
+        Ok(path)
+    }
+
+    /// Run `command` (sandboxed if a policy is configured) in `cwd` and
+    /// collect its output and exit code.
+    async fn run_command(
+        &self,
+        command: &str,
+        timeout_ms: u64,
+        cwd: &Path,
+    ) -> Result<(String, i32)> {
+        let temp_dir = self.temp_dir.as_ref().map(|d| d.path());
+        let env_remove = self.env_to_remove();
+
+        // Use sandbox if policy is configured
+        if let Some(ref policy) = self.sandbox_policy {
+            let options = localgpt_sandbox::ExecOptions {
+                cwd: Some(cwd.to_path_buf()),
+                env: temp_dir
+                    .map(|d| vec![(SESSION_TMP_ENV.to_string(), d.display().to_string())])
+                    .unwrap_or_default(),
+                env_remove,
+                isolate_network: self.isolate_network,
+                run_as: self.run_as,
+            };
+            return localgpt_sandbox::run_sandboxed_with(command, policy, timeout_ms, &options)
+                .await;
+        }
+
+        // Fallback: run command directly without sandbox
+        let mut cmd = tokio::process::Command::new(&self.shell.program);
+        cmd.arg(&self.shell.flag).arg(command);
+        for key in &env_remove {
+            cmd.env_remove(key);
+        }
+        if let Some(dir) = temp_dir {
+            cmd.env(SESSION_TMP_ENV, dir);
+        }
+        cmd.current_dir(cwd);
+        if self.isolate_network {
+            localgpt_sandbox::isolate_network(&mut cmd);
+        }
+        if let Some(run_as) = self.run_as
+            && !localgpt_sandbox::run_as_user(&mut cmd, run_as)
+        {
+            anyhow::bail!("security.bash_run_as_uid is only supported on Unix; refusing to run");
         }
+        cmd.stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        let child = cmd.spawn().map_err(|e| match self.run_as {
+            Some(run_as) => anyhow::anyhow!(
+                "Refusing to run command: could not switch to uid {} / gid {}: {}",
+                run_as.uid,
+                run_as.gid,
+                e
+            ),
+            None => e.into(),
+        })?;
+
+        let output =
+            localgpt_sandbox::collect_capped_output(child, self.max_output_bytes, timeout_ms)
+                .await?;
+        Ok((output.render(), output.exit_code))
     }
 }
 
@@ -92,7 +625,11 @@ impl Tool for BashTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "bash".to_string(),
-            description: "Execute a bash command and return the output".to_string(),
+            description: format!(
+                "Execute a bash command and return the output. Runs in the workspace \
+                 unless cwd is given; ${} points at a per-session scratch directory.",
+                SESSION_TMP_ENV
+            ),
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -103,6 +640,10 @@ impl Tool for BashTool {
                     "timeout_ms": {
                         "type": "integer",
                         "description": format!("Optional timeout in milliseconds (default: {})", self.default_timeout_ms)
+                    },
+                    "cwd": {
+                        "type": "string",
+                        "description": "Optional working directory; relative paths are resolved from the workspace"
                     }
                 },
                 "required": ["command"]
@@ -120,13 +661,16 @@ impl Tool for BashTool {
             .as_u64()
             .unwrap_or(self.default_timeout_ms);
 
+        // Defaults to the workspace
+        let cwd = self.resolve_cwd(args["cwd"].as_str())?;
+
         // Best-effort protected file check for bash commands
         let suspicious = security::check_bash_command(command);
         if !suspicious.is_empty() {
             let detail = format!(
                 "Bash command references protected files: {:?} (cmd: {})",
                 suspicious,
-                &command[..command.len().min(200)]
+                truncate_at_char_boundary(command, 200)
             );
             let _ = security::append_audit_entry_with_detail(
                 &self.state_dir,
@@ -140,68 +684,56 @@ impl Tool for BashTool {
 
         debug!(
             "Executing bash command (timeout: {}ms): {}",
-            timeout_ms, command
+            timeout_ms,
+            self.secret_scanner.redact_secrets(command)
         );
 
-        // Use sandbox if policy is configured
-        if let Some(ref policy) = self.sandbox_policy {
-            let (output, exit_code) =
-                localgpt_sandbox::run_sandboxed(command, policy, timeout_ms).await?;
-
-            if output.is_empty() {
-                return Ok(format!("Command completed with exit code: {}", exit_code));
-            }
-
-            return Ok(output);
-        }
-
-        // Fallback: run command directly without sandbox
-        let timeout_duration = std::time::Duration::from_millis(timeout_ms);
-        let output = tokio::time::timeout(
-            timeout_duration,
-            tokio::process::Command::new("bash")
-                .arg("-c")
-                .arg(command)
-                .output(),
-        )
-        .await
-        .map_err(|_| anyhow::anyhow!("Command timed out after {}ms", timeout_ms))??;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-
-        let mut result = String::new();
-
-        if !stdout.is_empty() {
-            result.push_str(&stdout);
-        }
-
-        if !stderr.is_empty() {
-            if !result.is_empty() {
-                result.push_str("\n\nSTDERR:\n");
-            }
-            result.push_str(&stderr);
-        }
-
-        if result.is_empty() {
-            result = format!(
-                "Command completed with exit code: {}",
-                output.status.code().unwrap_or(-1)
+        let risk = self.risk_classifier.classify(command);
+        if risk.level > security::RiskLevel::Low {
+            debug!(
+                "Bash command risk {}: {}",
+                risk.level,
+                risk.reasons.join(", ")
             );
         }
 
-        Ok(result)
+        let (output, exit_code) = self.run_command(command, timeout_ms, &cwd).await?;
+        self.mutation_audit.record(
+            "bash",
+            &format!(
+                "exit {}: {}",
+                exit_code,
+                command.chars().take(200).collect::<String>()
+            ),
+        );
+        Ok(append_risk_note(output, &risk))
     }
 }
 
+/// Advisory note appended to bash output for medium/high risk commands.
+fn append_risk_note(mut output: String, risk: &security::RiskAssessment) -> String {
+    if risk.level > security::RiskLevel::Low {
+        output.push_str(&format!(
+            "\n\n[Risk: {} — {}]",
+            risk.level,
+            risk.reasons.join(", ")
+        ));
+    }
+    output
+}
+
 // Read File Tool
 pub struct ReadFileTool {
-    sandbox_policy: Option<SandboxPolicy>,
+    denied_paths: DeniedPaths,
+    max_bytes: u64,
 }
 
 impl ReadFileTool {
-    pub fn new(sandbox_policy: Option<SandboxPolicy>) -> Self {
-        Self { sandbox_policy }
+    pub fn new(denied_paths: DeniedPaths, max_bytes: u64) -> Self {
+        Self {
+            denied_paths,
+            max_bytes,
+        }
     }
 }
 
@@ -228,7 +760,19 @@ impl Tool for ReadFileTool {
                     },
                     "limit": {
                         "type": "integer",
-                        "description": "Maximum number of lines to read"
+                        "description": format!("Maximum number of lines to read (default: {})", READ_DEFAULT_LIMIT)
+                    },
+                    "tail": {
+                        "type": "integer",
+                        "description": "Return only the last N lines (reads from the end of the file; a negative offset does the same)"
+                    },
+                    "follow_ms": {
+                        "type": "integer",
+                        "description": format!("With tail: also wait this long (max {}ms) and return lines appended meanwhile", MAX_FOLLOW_MS)
+                    },
+                    "include_hash": {
+                        "type": "boolean",
+                        "description": "Also return a hash of the whole file; pass it as expected_hash to write_file/edit_file to fail instead of overwriting changes made since this read (default: false)"
                     }
                 },
                 "required": ["path"]
@@ -245,111 +789,668 @@ impl Tool for ReadFileTool {
         let path = shellexpand::tilde(path).to_string();
 
         // Check credential directory access
-        if let Some(ref policy) = self.sandbox_policy
-            && localgpt_sandbox::policy::is_path_denied(std::path::Path::new(&path), policy)
-        {
-            anyhow::bail!(
-                "Cannot read file in denied directory: {}. \
-                     This path is blocked by sandbox policy.",
-                path
-            );
-        }
+        self.denied_paths
+            .check("read_file", "read file in", Path::new(&path))?;
 
         debug!("Reading file: {}", path);
 
-        let content = fs::read_to_string(&path)?;
+        // Tail mode: `tail: N` or a negative offset
+        let tail = args["tail"].as_u64().or_else(|| {
+            args["offset"]
+                .as_i64()
+                .filter(|o| *o < 0)
+                .map(|o| o.unsigned_abs())
+        });
+        let include_hash = args["include_hash"].as_bool().unwrap_or(false);
+
+        // The hash is fed by the same read that produces the output, so the
+        // token never covers bytes the model didn't see
+        let mut hasher = include_hash.then(<sha2::Sha256 as sha2::Digest>::new);
+
+        if looks_binary(Path::new(&path))? {
+            let Some(mut hasher) = hasher else {
+                return Ok(format!(
+                    "<binary file, {} bytes, not shown>",
+                    fs::metadata(&path)?.len()
+                ));
+            };
+            let len = std::io::copy(&mut fs::File::open(&path)?, &mut hasher)?;
+            return Ok(format!(
+                "<binary file, {} bytes, not shown>\n\n[hash: {}]",
+                len,
+                finish_content_token(hasher)
+            ));
+        }
+
+        if let Some(n) = tail {
+            let follow_ms = args["follow_ms"].as_u64().unwrap_or(0).min(MAX_FOLLOW_MS);
+            let mut result = read_tail(
+                Path::new(&path),
+                n as usize,
+                follow_ms,
+                self.max_bytes,
+                hasher.as_mut(),
+            )
+            .await?;
+            if let Some(hasher) = hasher {
+                result.push_str(&format!("\n\n[hash: {}]", finish_content_token(hasher)));
+            }
+            return Ok(result);
+        }
 
         // Handle offset and limit
         let offset = args["offset"].as_u64().unwrap_or(0) as usize;
         let limit = args["limit"].as_u64().map(|l| l as usize);
 
-        let lines: Vec<&str> = content.lines().collect();
-        let total_lines = lines.len();
+        let (lines, more) = read_line_range(
+            Path::new(&path),
+            offset,
+            limit.unwrap_or(READ_DEFAULT_LIMIT),
+            self.max_bytes,
+            hasher.as_mut(),
+        )?;
 
-        let start = offset.min(total_lines);
-        let end = limit
-            .map(|l| (start + l).min(total_lines))
-            .unwrap_or(total_lines);
-
-        let selected: Vec<String> = lines[start..end]
+        let selected: Vec<String> = lines
             .iter()
             .enumerate()
-            .map(|(i, line)| format!("{:4}\t{}", start + i + 1, line))
+            .map(|(i, line)| format!("{:4}\t{}", offset + i + 1, line))
             .collect();
 
-        Ok(selected.join("\n"))
+        let mut result = selected.join("\n");
+        if more && limit.is_none() {
+            result.push_str(&format!(
+                "\n\n[Showing lines {}-{}; the file continues. Pass offset/limit to read more.]",
+                offset + 1,
+                offset + lines.len()
+            ));
+        }
+        if let Some(hasher) = hasher {
+            result.push_str(&format!("\n\n[hash: {}]", finish_content_token(hasher)));
+        }
+        Ok(result)
     }
 }
 
-// Write File Tool
-pub struct WriteFileTool {
-    state_dir: PathBuf,
-    sandbox_policy: Option<SandboxPolicy>,
+/// Token identifying a file's exact contents, returned by `read_file` with
+/// `include_hash` and checked by `expected_hash` on write_file/edit_file.
+fn content_token(bytes: &[u8]) -> String {
+    use sha2::Digest;
+    format!("sha256:{:x}", sha2::Sha256::digest(bytes))
 }
 
-impl WriteFileTool {
-    pub fn new(state_dir: PathBuf, sandbox_policy: Option<SandboxPolicy>) -> Self {
-        Self {
-            state_dir,
-            sandbox_policy,
-        }
-    }
+/// [`content_token`] of the bytes fed to `hasher`.
+fn finish_content_token(hasher: sha2::Sha256) -> String {
+    use sha2::Digest;
+    format!("sha256:{:x}", hasher.finalize())
 }
 
-#[async_trait]
-impl Tool for WriteFileTool {
-    fn name(&self) -> &str {
-        "write_file"
-    }
+/// Optimistic concurrency check: fail if `current` (the file's bytes now,
+/// or `None` if it's gone) doesn't match the token from an earlier read.
+fn check_expected_hash(path: &Path, expected: &str, current: Option<&[u8]>) -> Result<()> {
+    let expected = expected.trim().to_lowercase();
+    let expected = if expected.starts_with("sha256:") {
+        expected
+    } else {
+        format!("sha256:{}", expected)
+    };
 
-    fn schema(&self) -> ToolSchema {
-        ToolSchema {
-            name: "write_file".to_string(),
-            description: "Write content to a file (creates or overwrites)".to_string(),
-            parameters: json!({
-                "type": "object",
-                "properties": {
-                    "path": {
-                        "type": "string",
-                        "description": "The path to the file to write"
-                    },
-                    "content": {
-                        "type": "string",
-                        "description": "The content to write to the file"
-                    }
-                },
-                "required": ["path", "content"]
-            }),
-        }
+    let Some(current) = current else {
+        anyhow::bail!(
+            "Conflict: {} no longer exists (expected {}). Nothing was written.",
+            path.display(),
+            expected
+        );
+    };
+    let actual = content_token(current);
+    if actual != expected {
+        anyhow::bail!(
+            "Conflict: {} changed since it was read (expected {}, now {}). \
+             Nothing was written; read the file again and reapply the change.",
+            path.display(),
+            expected,
+            actual
+        );
     }
+    Ok(())
+}
 
-    async fn execute(&self, arguments: &str) -> Result<String> {
-        let args: Value = serde_json::from_str(arguments)?;
-        let path = args["path"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing path"))?;
-        let content = args["content"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing content"))?;
+/// Bytes `read_file` inspects to tell binary files from text.
+const BINARY_SNIFF_BYTES: u64 = 8192;
 
-        let path = shellexpand::tilde(path).to_string();
-        let path = PathBuf::from(&path);
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
 
-        // Check credential directory access
-        if let Some(ref policy) = self.sandbox_policy
-            && localgpt_sandbox::policy::is_path_denied(&path, policy)
-        {
-            anyhow::bail!(
-                "Cannot write to denied directory: {}. \
-                     This path is blocked by sandbox policy.",
-                path.display()
-            );
-        }
+/// Whether a file looks binary: a NUL byte or invalid UTF-8 in its first
+/// few KB. A multibyte character cut off by the sniff window is fine.
+fn looks_binary(path: &Path) -> Result<bool> {
+    use std::io::Read;
 
-        // Check protected files
-        if let Some(name) = path.file_name().and_then(|n| n.to_str())
-            && security::is_workspace_file_protected(name)
-        {
+    let mut head = Vec::new();
+    fs::File::open(path)?
+        .take(BINARY_SNIFF_BYTES)
+        .read_to_end(&mut head)?;
+    if head.contains(&0) {
+        return Ok(true);
+    }
+    Ok(std::str::from_utf8(&head).is_err_and(|e| e.error_len().is_some()))
+}
+
+/// Lines `read_file` returns when no limit is given.
+const READ_DEFAULT_LIMIT: usize = 2000;
+
+/// Read `limit` lines starting at line `offset` without loading the rest of
+/// the file. Also returns whether the file continues past them. Fails once
+/// more than `max_bytes` (0 = unlimited) would have to be read.
+///
+/// With a `hasher`, every byte of the file is fed to it in the same pass;
+/// the part after the returned lines is streamed through without the cap.
+fn read_line_range(
+    path: &Path,
+    offset: usize,
+    limit: usize,
+    max_bytes: u64,
+    mut hasher: Option<&mut sha2::Sha256>,
+) -> Result<(Vec<String>, bool)> {
+    use sha2::Digest;
+    use std::io::{BufRead, Read};
+
+    let cap = if max_bytes == 0 {
+        u64::MAX
+    } else {
+        max_bytes.saturating_add(1)
+    };
+    let mut reader = std::io::BufReader::new(fs::File::open(path)?.take(cap));
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    let mut bytes_read = 0u64;
+    let mut index = 0;
+    loop {
+        buf.clear();
+        let n = reader.read_until(b'\n', &mut buf)?;
+        if n == 0 {
+            return Ok((lines, false));
+        }
+        bytes_read += n as u64;
+        if max_bytes > 0 && bytes_read > max_bytes {
+            return Err(read_limit_error(
+                path,
+                max_bytes,
+                "Use tail to read the end of the file, or grep to find the lines you need.",
+            ));
+        }
+        if let Some(hasher) = hasher.as_deref_mut() {
+            hasher.update(&buf);
+        }
+        if index >= offset {
+            if lines.len() == limit {
+                if let Some(hasher) = hasher {
+                    hasher.update(reader.buffer());
+                    std::io::copy(&mut reader.into_inner().into_inner(), hasher)?;
+                }
+                return Ok((lines, true));
+            }
+            let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            // A UTF-8 byte order mark isn't part of the first line's text
+            let line = if index == 0 {
+                line.strip_prefix(UTF8_BOM).unwrap_or(line)
+            } else {
+                line
+            };
+            lines.push(String::from_utf8_lossy(line).into_owned());
+        }
+        index += 1;
+    }
+}
+
+/// The error `read_file` fails with when a read would go past
+/// `tools.read_file_max_bytes`.
+fn read_limit_error(path: &Path, max_bytes: u64, hint: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Stopped after reading {} bytes of {} (tools.read_file_max_bytes). {}",
+        max_bytes,
+        path.display(),
+        hint
+    )
+}
+
+/// Upper bound for `read_file`'s `follow_ms` window.
+const MAX_FOLLOW_MS: u64 = 30_000;
+
+/// Maximum bytes of appended output returned by `read_file` follow mode.
+const MAX_FOLLOW_BYTES: u64 = 256 * 1024;
+
+/// Return the last `n` lines of a file, optionally followed by whatever is
+/// appended during a `follow_ms` window. Neither read goes past
+/// `max_bytes` (0 = unlimited). With a `hasher`, the lines come from
+/// [`read_last_lines_hashed`] instead.
+async fn read_tail(
+    path: &Path,
+    n: usize,
+    follow_ms: u64,
+    max_bytes: u64,
+    hasher: Option<&mut sha2::Sha256>,
+) -> Result<String> {
+    let (lines, len, capped) = match hasher {
+        Some(hasher) => read_last_lines_hashed(path, n, max_bytes, hasher)?,
+        None => read_last_lines(path, n, max_bytes)?,
+    };
+    if capped && lines.is_empty() {
+        return Err(read_limit_error(
+            path,
+            max_bytes,
+            "Its last line alone is longer; use grep to find what you need.",
+        ));
+    }
+
+    let mut result = format!("[last {} line(s) of {}]\n", lines.len(), path.display());
+    result.push_str(&lines.join("\n"));
+    if capped {
+        result.push_str(&format!(
+            "\n\n[Only {} line(s) fit in the last {} bytes (tools.read_file_max_bytes)]",
+            lines.len(),
+            max_bytes
+        ));
+    }
+
+    if follow_ms > 0 {
+        tokio::time::sleep(std::time::Duration::from_millis(follow_ms)).await;
+        result.push_str(&format!("\n\n[appended during {}ms follow]\n", follow_ms));
+        result.push_str(&read_appended(path, len, max_bytes)?);
+    }
+
+    Ok(result)
+}
+
+/// Read the last `n` lines of a file by scanning backwards from its end,
+/// so only the tail of a large log is read. The scan stops after
+/// `max_bytes` (0 = unlimited), keeping only the complete lines found so
+/// far. Also returns the file length at the time of reading, and whether
+/// the cap cut the scan short.
+fn read_last_lines(path: &Path, n: usize, max_bytes: u64) -> Result<(Vec<String>, u64, bool)> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    const CHUNK: u64 = 8192;
+
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let stop = if max_bytes == 0 {
+        0
+    } else {
+        len.saturating_sub(max_bytes)
+    };
+    let mut pos = len;
+    let mut buf: Vec<u8> = Vec::new();
+    let mut newlines = 0;
+
+    // One newline more than needed guarantees the first kept line is complete
+    while pos > stop && newlines <= n {
+        let read = CHUNK.min(pos - stop);
+        pos -= read;
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk = vec![0; read as usize];
+        file.read_exact(&mut chunk)?;
+        newlines += chunk.iter().filter(|&&b| b == b'\n').count();
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+    }
+
+    // Stopped by the cap mid-line: the first line is incomplete
+    let capped = pos > 0 && newlines <= n;
+    if capped {
+        let first_newline = buf.iter().position(|&b| b == b'\n');
+        buf.drain(..first_newline.map_or(buf.len(), |i| i + 1));
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(n);
+    Ok((
+        lines[start..].iter().map(|l| l.to_string()).collect(),
+        len,
+        capped,
+    ))
+}
+
+/// Forward-reading [`read_last_lines`] that feeds every byte to `hasher`,
+/// for `include_hash`. Reads the whole file but only keeps the last `n`
+/// lines, and of those only the ones that fit in `max_bytes`.
+fn read_last_lines_hashed(
+    path: &Path,
+    n: usize,
+    max_bytes: u64,
+    hasher: &mut sha2::Sha256,
+) -> Result<(Vec<String>, u64, bool)> {
+    use sha2::Digest;
+    use std::collections::VecDeque;
+    use std::io::BufRead;
+
+    let mut reader = std::io::BufReader::new(fs::File::open(path)?);
+    let mut kept: VecDeque<Vec<u8>> = VecDeque::new();
+    let mut kept_bytes = 0u64;
+    let mut len = 0u64;
+    let mut capped = false;
+    loop {
+        let mut line = Vec::new();
+        let read = reader.read_until(b'\n', &mut line)? as u64;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&line);
+        len += read;
+        kept_bytes += read;
+        kept.push_back(line);
+        if kept.len() > n {
+            kept_bytes -= kept.pop_front().map_or(0, |l| l.len() as u64);
+        }
+        while max_bytes > 0 && kept_bytes > max_bytes {
+            kept_bytes -= kept.pop_front().map_or(0, |l| l.len() as u64);
+            capped = true;
+        }
+    }
+
+    let lines = kept
+        .iter()
+        .map(|line| {
+            let line = match line.strip_suffix(b"\n") {
+                Some(line) => line.strip_suffix(b"\r").unwrap_or(line),
+                None => line,
+            };
+            String::from_utf8_lossy(line).into_owned()
+        })
+        .collect();
+    Ok((lines, len, capped))
+}
+
+/// Read what was appended to a file after byte offset `from`, up to
+/// [`MAX_FOLLOW_BYTES`] or `max_bytes` (0 = unlimited), whichever is lower.
+fn read_appended(path: &Path, from: u64, max_bytes: u64) -> Result<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < from {
+        return Ok("[file was truncated or rotated]".to_string());
+    }
+    if len == from {
+        return Ok("[no new lines]".to_string());
+    }
+
+    let cap = if max_bytes == 0 {
+        MAX_FOLLOW_BYTES
+    } else {
+        MAX_FOLLOW_BYTES.min(max_bytes)
+    };
+    file.seek(SeekFrom::Start(from))?;
+    let mut buf = Vec::new();
+    file.take(cap).read_to_end(&mut buf)?;
+
+    let mut text = String::from_utf8_lossy(&buf).into_owned();
+    if len - from > cap {
+        text.push_str(&format!("\n[...{} more bytes appended]", len - from - cap));
+    }
+    Ok(text)
+}
+
+// Read Glob Tool
+
+/// Default and maximum number of files read_glob returns.
+const READ_GLOB_DEFAULT_FILES: usize = 20;
+const READ_GLOB_MAX_FILES: usize = 200;
+
+/// Default bytes read_glob shows from each file.
+const READ_GLOB_DEFAULT_FILE_BYTES: usize = 64 * 1024;
+
+/// Total output read_glob returns; files past it are only counted.
+const READ_GLOB_MAX_TOTAL_BYTES: usize = 512 * 1024;
+
+pub struct ReadGlobTool {
+    denied_paths: DeniedPaths,
+}
+
+impl ReadGlobTool {
+    pub fn new(denied_paths: DeniedPaths) -> Self {
+        Self { denied_paths }
+    }
+}
+
+#[async_trait]
+impl Tool for ReadGlobTool {
+    fn name(&self) -> &str {
+        "read_glob"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "read_glob".to_string(),
+            description: "Read every file matching a glob pattern (e.g. src/**/*.rs) in one call"
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "glob": {
+                        "type": "string",
+                        "description": "Glob pattern for the files to read"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Maximum number of files to read (default: {}, max: {})", READ_GLOB_DEFAULT_FILES, READ_GLOB_MAX_FILES)
+                    },
+                    "max_bytes_per_file": {
+                        "type": "integer",
+                        "description": format!("Bytes shown from each file before it is cut off (default: {})", READ_GLOB_DEFAULT_FILE_BYTES)
+                    }
+                },
+                "required": ["glob"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let pattern = args["glob"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing glob"))?;
+        let max_files = args["max_files"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(READ_GLOB_DEFAULT_FILES)
+            .clamp(1, READ_GLOB_MAX_FILES);
+        let max_bytes_per_file = args["max_bytes_per_file"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(READ_GLOB_DEFAULT_FILE_BYTES)
+            .max(1);
+
+        let pattern = shellexpand::tilde(pattern).to_string();
+        debug!("Reading files matching: {}", pattern);
+
+        // Refuse to walk a denied tree at all rather than filtering each match
+        self.denied_paths
+            .check("read_glob", "read files in", &glob_base_dir(&pattern))?;
+
+        let mut result = String::new();
+        let mut read = 0;
+        let mut more = false;
+        let mut skipped = 0;
+        let mut unreadable = 0;
+        for entry in glob::glob(&pattern)? {
+            let Ok(path) = entry else {
+                unreadable += 1;
+                continue;
+            };
+            if !path.is_file() {
+                continue;
+            }
+            if self.denied_paths.is_denied(&path) {
+                skipped += 1;
+                continue;
+            }
+            if read >= max_files || result.len() >= READ_GLOB_MAX_TOTAL_BYTES {
+                // Stop walking; a broad glob could otherwise traverse the whole disk
+                more = true;
+                break;
+            }
+
+            let Ok(contents) = read_glob_file(&path, max_bytes_per_file) else {
+                unreadable += 1;
+                continue;
+            };
+            let section = format!("=== {} ===\n{}\n\n", path.display(), contents);
+            let remaining = READ_GLOB_MAX_TOTAL_BYTES - result.len();
+            if section.len() > remaining {
+                result.push_str(truncate_at_char_boundary(&section, remaining));
+                result.push_str("\n[output limit reached]\n");
+            } else {
+                result.push_str(&section);
+            }
+            read += 1;
+        }
+
+        if read == 0 && skipped == 0 && unreadable == 0 {
+            anyhow::bail!("No files match {}", pattern);
+        }
+        let mut result = result.trim_end().to_string();
+        if more {
+            result.push_str(
+                "\n\n[at least 1 more matching file omitted; narrow the glob or raise max_files]",
+            );
+        }
+        if skipped > 0 {
+            result.push_str(&format!(
+                "\n\n[{} file(s) in denied directories skipped]",
+                skipped
+            ));
+        }
+        if unreadable > 0 {
+            result.push_str(&format!("\n\n[{} unreadable path(s) skipped]", unreadable));
+        }
+        Ok(result)
+    }
+}
+
+/// The literal directory a glob pattern starts from: every leading
+/// component before the first one containing a wildcard.
+fn glob_base_dir(pattern: &str) -> PathBuf {
+    let mut base = PathBuf::new();
+    for component in Path::new(pattern).components() {
+        let text = component.as_os_str().to_string_lossy();
+        if text.contains(['*', '?', '[']) {
+            break;
+        }
+        base.push(component);
+    }
+    if base.as_os_str().is_empty() {
+        base.push(".");
+    }
+    base
+}
+
+/// One file's section of a read_glob result: text up to `max_bytes`, or a
+/// placeholder for binary files.
+fn read_glob_file(path: &Path, max_bytes: usize) -> Result<String> {
+    use std::io::Read;
+
+    if looks_binary(path)? {
+        return Ok(format!(
+            "<binary file, {} bytes, not shown>",
+            fs::metadata(path)?.len()
+        ));
+    }
+
+    let size = fs::metadata(path)?.len();
+    let mut bytes = Vec::new();
+    fs::File::open(path)?
+        .take(max_bytes as u64)
+        .read_to_end(&mut bytes)?;
+    let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(&bytes);
+    let text = String::from_utf8_lossy(bytes);
+
+    if size > max_bytes as u64 {
+        // The byte cap may split a multibyte character at the end
+        let text = text.trim_end_matches(char::REPLACEMENT_CHARACTER);
+        return Ok(format!(
+            "{}\n[truncated: showing {} of {} bytes]",
+            text, max_bytes, size
+        ));
+    }
+    Ok(text.into_owned())
+}
+
+// Write File Tool
+pub struct WriteFileTool {
+    state_dir: PathBuf,
+    denied_paths: DeniedPaths,
+    mutation_audit: MutationAudit,
+}
+
+impl WriteFileTool {
+    pub fn new(
+        state_dir: PathBuf,
+        denied_paths: DeniedPaths,
+        mutation_audit: MutationAudit,
+    ) -> Self {
+        Self {
+            state_dir,
+            denied_paths,
+            mutation_audit,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for WriteFileTool {
+    fn name(&self) -> &str {
+        "write_file"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "write_file".to_string(),
+            description: "Write content to a file (creates or overwrites)".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "The path to the file to write"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "The content to write to the file"
+                    },
+                    "expected_hash": {
+                        "type": "string",
+                        "description": "Hash from read_file (include_hash); the write fails if the file changed since"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Run all checks and return a unified diff of the change without writing (default: false)"
+                    }
+                },
+                "required": ["path", "content"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let path = args["path"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing path"))?;
+        let content = args["content"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing content"))?;
+
+        let path = shellexpand::tilde(path).to_string();
+        let path = PathBuf::from(&path);
+
+        // Check credential directory access
+        self.denied_paths.check("write_file", "write to", &path)?;
+
+        // Check protected files
+        if let Some(name) = path.file_name().and_then(|n| n.to_str())
+            && security::is_workspace_file_protected(name)
+        {
             let detail = format!("Agent attempted write to {}", path.display());
             let _ = security::append_audit_entry_with_detail(
                 &self.state_dir,
@@ -365,6 +1466,25 @@ impl Tool for WriteFileTool {
             );
         }
 
+        let expected_hash = args["expected_hash"].as_str();
+        if let Some(expected) = expected_hash {
+            let current = match fs::read(&path) {
+                Ok(bytes) => Some(bytes),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+            check_expected_hash(&path, expected, current.as_deref())?;
+        }
+
+        if args["dry_run"].as_bool().unwrap_or(false) {
+            let old = match fs::read(&path) {
+                Ok(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+            return Ok(dry_run_result(&path, old.as_deref(), content));
+        }
+
         debug!("Writing file: {}", path.display());
 
         // Create parent directories if needed
@@ -373,26 +1493,40 @@ impl Tool for WriteFileTool {
         }
 
         fs::write(&path, content)?;
+        self.mutation_audit.record(
+            "write_file",
+            &format!("{}: {} bytes", path.display(), content.len()),
+        );
 
-        Ok(format!(
+        let mut result = format!(
             "Successfully wrote {} bytes to {}",
             content.len(),
             path.display()
-        ))
+        );
+        if expected_hash.is_some() {
+            result.push_str(&format!(" [hash: {}]", content_token(content.as_bytes())));
+        }
+        Ok(result)
     }
 }
 
 // Edit File Tool
 pub struct EditFileTool {
     state_dir: PathBuf,
-    sandbox_policy: Option<SandboxPolicy>,
+    denied_paths: DeniedPaths,
+    mutation_audit: MutationAudit,
 }
 
 impl EditFileTool {
-    pub fn new(state_dir: PathBuf, sandbox_policy: Option<SandboxPolicy>) -> Self {
+    pub fn new(
+        state_dir: PathBuf,
+        denied_paths: DeniedPaths,
+        mutation_audit: MutationAudit,
+    ) -> Self {
         Self {
             state_dir,
-            sandbox_policy,
+            denied_paths,
+            mutation_audit,
         }
     }
 }
@@ -406,7 +1540,7 @@ impl Tool for EditFileTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "edit_file".to_string(),
-            description: "Edit a file by replacing old_string with new_string".to_string(),
+            description: "Edit a file by replacing old_string with new_string. Returns a diff of the changed lines.".to_string(),
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -425,6 +1559,18 @@ impl Tool for EditFileTool {
                     "replace_all": {
                         "type": "boolean",
                         "description": "Replace all occurrences (default: false)"
+                    },
+                    "ignore_whitespace": {
+                        "type": "boolean",
+                        "description": "Match old_string treating runs of whitespace (indentation, trailing spaces) as equal (default: false)"
+                    },
+                    "expected_hash": {
+                        "type": "string",
+                        "description": "Hash from read_file (include_hash); the edit fails if the file changed since"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Run all checks and return a unified diff of the change without writing (default: false)"
                     }
                 },
                 "required": ["path", "old_string", "new_string"]
@@ -444,19 +1590,13 @@ impl Tool for EditFileTool {
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Missing new_string"))?;
         let replace_all = args["replace_all"].as_bool().unwrap_or(false);
+        let ignore_whitespace = args["ignore_whitespace"].as_bool().unwrap_or(false);
 
         let path = shellexpand::tilde(path).to_string();
 
         // Check credential directory access
-        if let Some(ref policy) = self.sandbox_policy
-            && localgpt_sandbox::policy::is_path_denied(std::path::Path::new(&path), policy)
-        {
-            anyhow::bail!(
-                "Cannot edit file in denied directory: {}. \
-                     This path is blocked by sandbox policy.",
-                path
-            );
-        }
+        self.denied_paths
+            .check("edit_file", "edit file in", Path::new(&path))?;
 
         // Check protected files
         if let Some(name) = std::path::Path::new(&path)
@@ -482,7 +1622,31 @@ impl Tool for EditFileTool {
 
         let content = fs::read_to_string(&path)?;
 
-        let (new_content, count) = if replace_all {
+        let expected_hash = args["expected_hash"].as_str();
+        if let Some(expected) = expected_hash {
+            check_expected_hash(Path::new(&path), expected, Some(content.as_bytes()))?;
+        }
+
+        let (new_content, count) = if ignore_whitespace {
+            let ranges = find_whitespace_insensitive(&content, old_string);
+            if ranges.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "old_string not found in file (ignoring whitespace)"
+                ));
+            }
+            if ranges.len() > 1 && !replace_all {
+                return Err(anyhow::anyhow!(
+                    "old_string matches {} locations when ignoring whitespace; \
+                     add more context or set replace_all",
+                    ranges.len()
+                ));
+            }
+            let mut new_content = content.clone();
+            for &(start, end) in ranges.iter().rev() {
+                new_content.replace_range(start..end, new_string);
+            }
+            (new_content, ranges.len())
+        } else if replace_all {
             let count = content.matches(old_string).count();
             (content.replace(old_string, new_string), count)
         } else if content.contains(old_string) {
@@ -491,8 +1655,1702 @@ impl Tool for EditFileTool {
             return Err(anyhow::anyhow!("old_string not found in file"));
         };
 
+        if args["dry_run"].as_bool().unwrap_or(false) {
+            return Ok(dry_run_result(
+                Path::new(&path),
+                Some(&content),
+                &new_content,
+            ));
+        }
+
         fs::write(&path, &new_content)?;
+        self.mutation_audit
+            .record("edit_file", &format!("{}: {} occurrence(s)", path, count));
+
+        let mut result = format!("Replaced {} occurrence(s) in {}", count, path);
+        if expected_hash.is_some() {
+            result.push_str(&format!(
+                " [hash: {}]",
+                content_token(new_content.as_bytes())
+            ));
+        }
+        let hunks = edit_diff_hunks(&content, &new_content);
+        if !hunks.is_empty() {
+            result.push_str("\n\n");
+            result.push_str(hunks.trim_end());
+        }
+        Ok(result)
+    }
+}
+
+/// Diff hunks shown in an edit_file result; the rest are only counted.
+const EDIT_MAX_HUNKS: usize = 5;
+
+/// Unified diff hunks (two lines of context) for an edit, so the model can
+/// see what changed. Only the first [`EDIT_MAX_HUNKS`] are included.
+fn edit_diff_hunks(old: &str, new: &str) -> String {
+    let diff = similar::TextDiff::from_lines(old, new);
+    let mut unified = diff.unified_diff();
+    unified.context_radius(2);
+    let hunks: Vec<String> = unified.iter_hunks().map(|h| h.to_string()).collect();
+
+    let mut result: String = hunks
+        .iter()
+        .take(EDIT_MAX_HUNKS)
+        .map(String::as_str)
+        .collect();
+    if hunks.len() > EDIT_MAX_HUNKS {
+        result.push_str(&format!(
+            "[{} more hunk(s) omitted]\n",
+            hunks.len() - EDIT_MAX_HUNKS
+        ));
+    }
+    result
+}
+
+/// Result of a write_file/edit_file dry run: a unified diff from `old`
+/// (`None` if the file doesn't exist yet) to `new`.
+fn dry_run_result(path: &Path, old: Option<&str>, new: &str) -> String {
+    if old == Some(new) {
+        return format!("Dry run: {} would be unchanged", path.display());
+    }
+    let old_header = match old {
+        Some(_) => format!("a/{}", path.display()),
+        None => "/dev/null".to_string(),
+    };
+    let diff = similar::TextDiff::from_lines(old.unwrap_or(""), new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&old_header, &format!("b/{}", path.display()))
+        .to_string();
+    format!("Dry run: nothing written to {}\n\n{}", path.display(), diff)
+}
+
+/// Collapse every run of whitespace to a single space. Alongside the
+/// normalized text, returns for each of its bytes the byte range in `text`
+/// it was produced from.
+fn normalize_whitespace(text: &str) -> (String, Vec<(usize, usize)>) {
+    let mut normalized = String::with_capacity(text.len());
+    let mut spans = Vec::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let mut end = i + c.len_utf8();
+        if c.is_whitespace() {
+            while let Some(&(j, next)) = chars.peek() {
+                if !next.is_whitespace() {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            normalized.push(' ');
+            spans.push((i, end));
+        } else {
+            normalized.push(c);
+            spans.extend(std::iter::repeat_n((i, end), c.len_utf8()));
+        }
+    }
+
+    (normalized, spans)
+}
+
+/// Find the regions of `content` that match `needle` when runs of whitespace
+/// are treated as equal. Returns non-overlapping byte ranges into the
+/// original `content`, so the caller can replace the exact original bytes.
+///
+/// If the needle starts with indentation, the match is widened to cover the
+/// file's own indentation on that line; likewise trailing spaces and a final
+/// newline are absorbed, so the replacement text's layout wins.
+fn find_whitespace_insensitive(content: &str, needle: &str) -> Vec<(usize, usize)> {
+    let (normalized_needle, _) = normalize_whitespace(needle.trim());
+    if normalized_needle.is_empty() {
+        return Vec::new();
+    }
+
+    let leading = needle.starts_with(char::is_whitespace);
+    let trailing = needle.ends_with(char::is_whitespace);
+    let trailing_newline = needle.ends_with('\n');
+    let bytes = content.as_bytes();
+
+    let (haystack, spans) = normalize_whitespace(content);
+    let mut ranges: Vec<(usize, usize)> = haystack
+        .match_indices(normalized_needle.as_str())
+        .map(|(idx, m)| {
+            let mut start = spans[idx].0;
+            let mut end = spans[idx + m.len() - 1].1;
+            if leading {
+                while start > 0 && matches!(bytes[start - 1], b' ' | b'\t') {
+                    start -= 1;
+                }
+            }
+            if trailing {
+                while end < bytes.len() && matches!(bytes[end], b' ' | b'\t' | b'\r') {
+                    end += 1;
+                }
+                if trailing_newline && end < bytes.len() && bytes[end] == b'\n' {
+                    end += 1;
+                }
+            }
+            (start, end)
+        })
+        .collect();
+
+    // Widening may make neighbouring matches share whitespace; keep them disjoint.
+    for i in 1..ranges.len() {
+        if ranges[i].0 < ranges[i - 1].1 {
+            ranges[i].0 = ranges[i - 1].1;
+        }
+    }
+    ranges
+}
+
+// Move File Tool
+pub struct MoveFileTool {
+    state_dir: PathBuf,
+    denied_paths: DeniedPaths,
+    mutation_audit: MutationAudit,
+}
+
+impl MoveFileTool {
+    pub fn new(
+        state_dir: PathBuf,
+        denied_paths: DeniedPaths,
+        mutation_audit: MutationAudit,
+    ) -> Self {
+        Self {
+            state_dir,
+            denied_paths,
+            mutation_audit,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for MoveFileTool {
+    fn name(&self) -> &str {
+        "move_file"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "move_file".to_string(),
+            description: "Move or rename a file or directory".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "The path to move"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "The destination path"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace an existing file at the destination (default: false)"
+                    }
+                },
+                "required": ["from", "to"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let from = args["from"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing from"))?;
+        let to = args["to"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing to"))?;
+        let overwrite = args["overwrite"].as_bool().unwrap_or(false);
+
+        let from = PathBuf::from(shellexpand::tilde(from).to_string());
+        let to = PathBuf::from(shellexpand::tilde(to).to_string());
+
+        self.denied_paths
+            .check_with_contents("move_file", "move from", &from)?;
+        self.denied_paths
+            .check_with_contents("move_file", "move to", &to)?;
+        check_not_protected(&self.state_dir, "move_file", "move", &from)?;
+        check_not_protected(&self.state_dir, "move_file", "overwrite", &to)?;
+
+        let source = fs::symlink_metadata(&from)
+            .map_err(|e| anyhow::anyhow!("Cannot move {}: {}", from.display(), e))?;
+        // A protected file can't leave, or be replaced, along with its directory
+        if source.is_dir()
+            && let Some(protected) = find_protected_file(&from)?
+        {
+            check_not_protected(&self.state_dir, "move_file", "move", &protected)?;
+        }
+        if let Ok(existing) = fs::symlink_metadata(&to) {
+            if !overwrite {
+                anyhow::bail!(
+                    "Destination already exists: {}. Set overwrite to replace it.",
+                    to.display()
+                );
+            }
+            if existing.is_dir()
+                && let Some(protected) = find_protected_file(&to)?
+            {
+                check_not_protected(&self.state_dir, "move_file", "overwrite", &protected)?;
+            }
+            if existing.is_dir() {
+                anyhow::bail!(
+                    "Destination is a directory: {}. Only files are overwritten.",
+                    to.display()
+                );
+            }
+        }
+
+        debug!("Moving {} to {}", from.display(), to.display());
+
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_path(&from, &to, source.is_dir())?;
+        self.mutation_audit.record(
+            "move_file",
+            &format!("{} -> {}", from.display(), to.display()),
+        );
+
+        Ok(format!("Moved {} to {}", from.display(), to.display()))
+    }
+}
+
+/// Refuse to touch a protected workspace file (LocalGPT.md and friends),
+/// auditing the attempt as `WriteBlocked`. `action` completes "Cannot
+/// <action> protected file".
+fn check_not_protected(state_dir: &Path, tool: &str, action: &str, path: &Path) -> Result<()> {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return Ok(());
+    };
+    if !security::is_workspace_file_protected(name) {
+        return Ok(());
+    }
+
+    let detail = format!("Agent attempted to {} {}", action, path.display());
+    let _ = security::append_audit_entry_with_detail(
+        state_dir,
+        security::AuditAction::WriteBlocked,
+        "",
+        &format!("tool:{}", tool),
+        Some(&detail),
+    );
+    anyhow::bail!(
+        "Cannot {} protected file: {}. This file is managed by the security system. \
+         Use `localgpt md sign` to update the security policy.",
+        action,
+        path.display()
+    );
+}
+
+/// `fs::rename`, falling back to copy and delete when `from` and `to` are
+/// on different filesystems.
+fn move_path(from: &Path, to: &Path, is_dir: bool) -> Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            if is_dir {
+                copy_dir_all(from, to)?;
+                fs::remove_dir_all(from)?;
+            } else {
+                fs::copy(from, to)?;
+                fs::remove_file(from)?;
+            }
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Recursively copy the directory `from` to `to`. Symlinks are recreated,
+/// not followed.
+fn copy_dir_all(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+            #[cfg(not(unix))]
+            fs::copy(entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+// Delete File Tool
+
+/// Directory under the state dir that delete_file moves targets into
+/// unless `tools.hard_delete` is set.
+const TRASH_DIR: &str = ".localgpt_trash";
+
+pub struct DeleteFileTool {
+    state_dir: PathBuf,
+    denied_paths: DeniedPaths,
+    hard_delete: bool,
+    mutation_audit: MutationAudit,
+}
+
+impl DeleteFileTool {
+    pub fn new(
+        state_dir: PathBuf,
+        denied_paths: DeniedPaths,
+        hard_delete: bool,
+        mutation_audit: MutationAudit,
+    ) -> Self {
+        Self {
+            state_dir,
+            denied_paths,
+            hard_delete,
+            mutation_audit,
+        }
+    }
+
+    /// A fresh path in the trash for `path`, named after it with a
+    /// timestamp prefix.
+    fn trash_path(&self, path: &Path) -> Result<PathBuf> {
+        let trash = self.state_dir.join(TRASH_DIR);
+        fs::create_dir_all(&trash)?;
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "unnamed".to_string());
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3f");
+
+        let mut target = trash.join(format!("{}-{}", stamp, name));
+        let mut n = 1;
+        while fs::symlink_metadata(&target).is_ok() {
+            target = trash.join(format!("{}-{}-{}", stamp, n, name));
+            n += 1;
+        }
+        Ok(target)
+    }
+}
+
+#[async_trait]
+impl Tool for DeleteFileTool {
+    fn name(&self) -> &str {
+        "delete_file"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        let description = if self.hard_delete {
+            "Delete a file or directory permanently".to_string()
+        } else {
+            format!(
+                "Delete a file or directory by moving it to the trash ({}/)",
+                TRASH_DIR
+            )
+        };
+        ToolSchema {
+            name: "delete_file".to_string(),
+            description,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "The path to delete"
+                    },
+                    "recursive": {
+                        "type": "boolean",
+                        "description": "Required to delete a directory and everything in it (default: false)"
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let path = args["path"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing path"))?;
+        let recursive = args["recursive"].as_bool().unwrap_or(false);
+
+        let path = PathBuf::from(shellexpand::tilde(path).to_string());
+
+        self.denied_paths
+            .check_with_contents("delete_file", "delete in", &path)?;
+        check_not_protected(&self.state_dir, "delete_file", "delete", &path)?;
+
+        let metadata = fs::symlink_metadata(&path)
+            .map_err(|e| anyhow::anyhow!("Cannot delete {}: {}", path.display(), e))?;
+        let is_dir = metadata.is_dir();
+        if is_dir {
+            if !recursive {
+                anyhow::bail!(
+                    "{} is a directory. Set recursive to delete it and its contents.",
+                    path.display()
+                );
+            }
+            if let Some(protected) = find_protected_file(&path)? {
+                check_not_protected(&self.state_dir, "delete_file", "delete", &protected)?;
+            }
+        }
+
+        debug!("Deleting {}", path.display());
+
+        let outcome = if self.hard_delete {
+            if is_dir {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+            "deleted permanently".to_string()
+        } else {
+            let target = self.trash_path(&path)?;
+            move_path(&path, &target, is_dir)?;
+            format!("moved to {}", target.display())
+        };
+
+        self.mutation_audit
+            .record("delete_file", &format!("{}: {}", path.display(), outcome));
+
+        Ok(format!("Deleted {} ({})", path.display(), outcome))
+    }
+}
+
+/// First protected workspace file anywhere under `dir`, so a recursive
+/// delete or a directory move can't take one along with its directory.
+/// Symlinks aren't followed.
+fn find_protected_file(dir: &Path) -> Result<Option<PathBuf>> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry
+            .file_name()
+            .to_str()
+            .is_some_and(security::is_workspace_file_protected)
+        {
+            return Ok(Some(path));
+        }
+        if entry.file_type()?.is_dir()
+            && let Some(found) = find_protected_file(&path)?
+        {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
+
+// Hash File Tool
+
+/// Maximum files hashed by a single `hash_file` call.
+const MAX_HASH_FILES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HashAlgorithm {
+    Sha256,
+    Sha1,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sha256" => Ok(Self::Sha256),
+            "sha1" => Ok(Self::Sha1),
+            "blake3" => Ok(Self::Blake3),
+            other => anyhow::bail!(
+                "Unsupported hash algorithm '{}' (use sha256, sha1 or blake3)",
+                other
+            ),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha1 => "sha1",
+            Self::Blake3 => "blake3",
+        }
+    }
+}
+
+enum Hasher {
+    Sha256(sha2::Sha256),
+    Sha1(sha1::Sha1),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        use sha2::Digest;
+        match algorithm {
+            HashAlgorithm::Sha256 => Self::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Sha1 => Self::Sha1(sha1::Sha1::new()),
+            HashAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        use sha2::Digest;
+        match self {
+            Self::Sha256(h) => h.update(data),
+            Self::Sha1(h) => h.update(data),
+            Self::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        use sha2::Digest;
+        match self {
+            Self::Sha256(h) => format!("{:x}", h.finalize()),
+            Self::Sha1(h) => format!("{:x}", h.finalize()),
+            Self::Blake3(h) => h.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Hasher::new(algorithm);
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize_hex())
+}
+
+/// Collect regular files under `dir`, sorted by path. Symlinks are not
+/// followed so the aggregate can't escape the directory.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.collect::<std::io::Result<_>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+        if files.len() > MAX_HASH_FILES {
+            anyhow::bail!("More than {} files to hash", MAX_HASH_FILES);
+        }
+    }
+    Ok(())
+}
+
+pub struct HashFileTool {
+    denied_paths: DeniedPaths,
+}
+
+impl HashFileTool {
+    pub fn new(denied_paths: DeniedPaths) -> Self {
+        Self { denied_paths }
+    }
+
+    fn is_denied(&self, path: &Path) -> bool {
+        self.denied_paths.is_denied(path)
+    }
+
+    /// Hash a directory: per-file digests plus an aggregate over
+    /// `<relative path>\0<digest>\n` lines in sorted path order, so the
+    /// aggregate changes when any file is added, removed, renamed or edited.
+    fn hash_directory(&self, dir: &Path, algorithm: HashAlgorithm) -> Result<String> {
+        let mut files = Vec::new();
+        collect_files(dir, &mut files)?;
+
+        let mut aggregate = Hasher::new(algorithm);
+        let mut lines = Vec::with_capacity(files.len());
+        let mut skipped = 0;
+        for file in &files {
+            if self.is_denied(file) {
+                skipped += 1;
+                continue;
+            }
+            let digest = hash_file(file, algorithm)?;
+            let relative = file.strip_prefix(dir).unwrap_or(file).to_string_lossy();
+            aggregate.update(relative.as_bytes());
+            aggregate.update(b"\0");
+            aggregate.update(digest.as_bytes());
+            aggregate.update(b"\n");
+            lines.push(format!("{}  {}", digest, relative));
+        }
+
+        let mut result = format!(
+            "{} ({}, {} files, {})\n",
+            aggregate.finalize_hex(),
+            dir.display(),
+            lines.len(),
+            algorithm.as_str()
+        );
+        result.push_str(&lines.join("\n"));
+        if skipped > 0 {
+            result.push_str(&format!(
+                "\n[{} file(s) in denied directories skipped]",
+                skipped
+            ));
+        }
+        Ok(result)
+    }
+
+    fn hash_glob(&self, pattern: &str, algorithm: HashAlgorithm) -> Result<String> {
+        let mut lines = Vec::new();
+        let mut skipped = 0;
+        for entry in glob::glob(pattern)? {
+            let path = entry?;
+            if !path.is_file() {
+                continue;
+            }
+            if self.is_denied(&path) {
+                skipped += 1;
+                continue;
+            }
+            if lines.len() >= MAX_HASH_FILES {
+                anyhow::bail!("More than {} files match {}", MAX_HASH_FILES, pattern);
+            }
+            lines.push(format!(
+                "{}  {}",
+                hash_file(&path, algorithm)?,
+                path.display()
+            ));
+        }
+
+        if lines.is_empty() && skipped == 0 {
+            anyhow::bail!("No files match {}", pattern);
+        }
+        let mut result = lines.join("\n");
+        if skipped > 0 {
+            result.push_str(&format!(
+                "\n[{} file(s) in denied directories skipped]",
+                skipped
+            ));
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl Tool for HashFileTool {
+    fn name(&self) -> &str {
+        "hash_file"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "hash_file".to_string(),
+            description: "Compute checksums of a file, every file matching a glob, or a directory (per-file digests plus a stable aggregate hash). Optionally verify against an expected digest.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "File, directory, or glob pattern (e.g. dist/*.tar.gz)"
+                    },
+                    "algorithm": {
+                        "type": "string",
+                        "enum": ["sha256", "sha1", "blake3"],
+                        "description": "Hash algorithm (default: sha256)"
+                    },
+                    "expected": {
+                        "type": "string",
+                        "description": "Expected hex digest of a single file; the result reports whether it matches"
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let path = args["path"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing path"))?;
+        let algorithm = HashAlgorithm::parse(args["algorithm"].as_str().unwrap_or("sha256"))?;
+        let expected = args["expected"].as_str().map(|e| e.trim().to_lowercase());
+
+        let path = shellexpand::tilde(path).to_string();
+        let is_glob = path.contains(['*', '?', '[']);
+        let target = PathBuf::from(&path);
+
+        if !is_glob {
+            self.denied_paths
+                .check("hash_file", "hash file in", &target)?;
+        }
+        if expected.is_some() && (is_glob || target.is_dir()) {
+            anyhow::bail!("expected can only be used with a single file");
+        }
+
+        debug!("Hashing {} with {}", path, algorithm.as_str());
+
+        let tool = HashFileTool::new(self.denied_paths.clone());
+        tokio::task::spawn_blocking(move || {
+            if is_glob {
+                return tool.hash_glob(&path, algorithm);
+            }
+            if target.is_dir() {
+                return tool.hash_directory(&target, algorithm);
+            }
+
+            let digest = hash_file(&target, algorithm)?;
+            let mut result = format!("{}  {} ({})", digest, path, algorithm.as_str());
+            if let Some(expected) = expected {
+                if expected == digest {
+                    result.push_str("\nOK: matches expected digest");
+                } else {
+                    result.push_str(&format!("\nMISMATCH: expected {}", expected));
+                }
+            }
+            Ok(result)
+        })
+        .await?
+    }
+}
+
+// Grep Tool
+
+/// Default and maximum matches returned by one `grep` call.
+const GREP_DEFAULT_RESULTS: usize = 100;
+const GREP_MAX_RESULTS: usize = 1000;
+
+/// Matched lines longer than this are cut, so minified files don't flood
+/// the context.
+const GREP_MAX_LINE_CHARS: usize = 300;
+
+/// Files larger than this are skipped.
+const GREP_MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Directories never descended into: VCS metadata and build/dependency
+/// output, which are large and rarely what the agent is looking for.
+const GREP_SKIP_DIRS: &[&str] = &[".git", ".hg", ".svn", "node_modules", "target"];
+
+pub struct GrepTool {
+    workspace: PathBuf,
+    denied_paths: DeniedPaths,
+}
+
+impl GrepTool {
+    pub fn new(workspace: PathBuf, denied_paths: DeniedPaths) -> Self {
+        Self {
+            workspace,
+            denied_paths,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for GrepTool {
+    fn name(&self) -> &str {
+        "grep"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "grep".to_string(),
+            description: "Search file contents under a directory with a regular expression. Returns `file:line: text` rows. Skips binary files, .git, node_modules and target.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Regular expression to search for"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "File or directory to search (default: the workspace)"
+                    },
+                    "glob": {
+                        "type": "string",
+                        "description": "Only search files whose name or relative path matches this glob, e.g. \"*.rs\" or \"src/**/*.ts\""
+                    },
+                    "ignore_case": {
+                        "type": "boolean",
+                        "description": "Case-insensitive match (default: false)"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": format!("Maximum matching lines to return (default: {}, max {})", GREP_DEFAULT_RESULTS, GREP_MAX_RESULTS)
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let pattern = args["pattern"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing pattern"))?;
+        let path = match args["path"].as_str() {
+            Some(path) => shellexpand::tilde(path).to_string(),
+            None => self.workspace.display().to_string(),
+        };
+        let ignore_case = args["ignore_case"].as_bool().unwrap_or(false);
+        let max_results = args["max_results"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(GREP_DEFAULT_RESULTS)
+            .clamp(1, GREP_MAX_RESULTS);
+
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid pattern: {}", e))?;
+        let glob = args["glob"]
+            .as_str()
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid glob: {}", e))?;
+
+        let root = PathBuf::from(&path);
+        self.denied_paths.check("grep", "search", &root)?;
+
+        debug!("Grep {:?} in {}", pattern, path);
+
+        let denied_paths = self.denied_paths.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut files = Vec::new();
+            if root.is_dir() {
+                collect_grep_files(&root, &denied_paths, &mut files);
+            } else {
+                files.push(root.clone());
+            }
+
+            let mut rows = Vec::new();
+            let mut truncated = false;
+            'files: for file in &files {
+                let relative = file.strip_prefix(&root).unwrap_or(file);
+                if let Some(ref glob) = glob
+                    && !glob.matches_path(relative)
+                    && !file
+                        .file_name()
+                        .is_some_and(|name| glob.matches(&name.to_string_lossy()))
+                {
+                    continue;
+                }
+                let Some(content) = read_text_file(file) else {
+                    continue;
+                };
+                for (i, line) in content.lines().enumerate() {
+                    if !regex.is_match(line) {
+                        continue;
+                    }
+                    if rows.len() == max_results {
+                        truncated = true;
+                        break 'files;
+                    }
+                    rows.push(format!(
+                        "{}:{}: {}",
+                        file.display(),
+                        i + 1,
+                        truncate_grep_line(line)
+                    ));
+                }
+            }
+
+            if rows.is_empty() {
+                return Ok(format!("No matches for {:?} in {}", regex.as_str(), path));
+            }
+            let mut result = rows.join("\n");
+            if truncated {
+                result.push_str(&format!(
+                    "\n\n[Stopped after {} matches; narrow the pattern, path or glob, or raise max_results]",
+                    max_results
+                ));
+            }
+            Ok(result)
+        })
+        .await?
+    }
+}
+
+/// Collect files under `dir` for grep, sorted by path. Denied and
+/// skip-listed directories are left out silently, and symlinks are not
+/// followed.
+fn collect_grep_files(dir: &Path, denied_paths: &DeniedPaths, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if denied_paths.is_denied(&path) {
+            continue;
+        }
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if !GREP_SKIP_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                collect_grep_files(&path, denied_paths, files);
+            }
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+}
+
+/// File contents as text, or `None` for large, unreadable or binary files.
+fn read_text_file(path: &Path) -> Option<String> {
+    if fs::metadata(path).ok()?.len() > GREP_MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn truncate_grep_line(line: &str) -> String {
+    let line = line.trim_end();
+    match line.char_indices().nth(GREP_MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+// List Directory Tool
+
+/// Default and maximum depth of a `list_directory` tree.
+const LIST_DEFAULT_DEPTH: usize = 2;
+const LIST_MAX_DEPTH: usize = 10;
+
+/// Entries listed before the tree is cut off.
+const LIST_MAX_ENTRIES: usize = 1000;
+
+pub struct ListDirectoryTool {
+    workspace: PathBuf,
+    denied_paths: DeniedPaths,
+}
+
+impl ListDirectoryTool {
+    pub fn new(workspace: PathBuf, denied_paths: DeniedPaths) -> Self {
+        Self {
+            workspace,
+            denied_paths,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ListDirectoryTool {
+    fn name(&self) -> &str {
+        "list_directory"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "list_directory".to_string(),
+            description: "List a directory as an indented tree with file sizes. Skips hidden files, .git and anything matched by a .gitignore unless include_hidden is set.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Directory to list (default: the workspace)"
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "description": format!("Levels to descend; 1 lists only direct children (default: {}, max {})", LIST_DEFAULT_DEPTH, LIST_MAX_DEPTH)
+                    },
+                    "include_hidden": {
+                        "type": "boolean",
+                        "description": "Also list dotfiles, .git and gitignored entries (default: false)"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let path = match args["path"].as_str() {
+            Some(path) => shellexpand::tilde(path).to_string(),
+            None => self.workspace.display().to_string(),
+        };
+        let max_depth = args["max_depth"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(LIST_DEFAULT_DEPTH)
+            .clamp(1, LIST_MAX_DEPTH);
+        let include_hidden = args["include_hidden"].as_bool().unwrap_or(false);
+
+        let root = PathBuf::from(&path);
+        self.denied_paths.check("list_directory", "list", &root)?;
+        if !root.is_dir() {
+            anyhow::bail!("Not a directory: {}", path);
+        }
+
+        debug!("Listing directory: {} (depth {})", path, max_depth);
+
+        let denied_paths = self.denied_paths.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut walk = DirectoryListing {
+                denied_paths,
+                max_depth,
+                include_hidden,
+                gitignores: Vec::new(),
+                lines: vec![format!("{}/", root.display())],
+                truncated: false,
+            };
+            walk.list(&root, 1);
+
+            let mut result = walk.lines.join("\n");
+            if walk.truncated {
+                result.push_str(&format!(
+                    "\n\n[Stopped after {} entries; list a subdirectory or lower max_depth]",
+                    LIST_MAX_ENTRIES
+                ));
+            }
+            Ok(result)
+        })
+        .await?
+    }
+}
+
+/// State of one `list_directory` walk.
+struct DirectoryListing {
+    denied_paths: DeniedPaths,
+    max_depth: usize,
+    include_hidden: bool,
+    /// `.gitignore` files of the directories currently being walked,
+    /// outermost first.
+    gitignores: Vec<Gitignore>,
+    lines: Vec<String>,
+    truncated: bool,
+}
+
+impl DirectoryListing {
+    /// List the entries of `dir` at `depth` (1 = direct children of the
+    /// root): directories first, then files, each sorted by name. Symlinks
+    /// are shown but not followed.
+    fn list(&mut self, dir: &Path, depth: usize) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| Some((e.file_type().ok()?, e)))
+            .collect();
+        entries.sort_by_key(|(file_type, e)| (!file_type.is_dir(), e.file_name()));
+
+        let gitignore = if self.include_hidden {
+            None
+        } else {
+            Gitignore::load(dir)
+        };
+        let pushed = gitignore.is_some();
+        self.gitignores.extend(gitignore);
+
+        let indent = "  ".repeat(depth);
+        for (file_type, entry) in entries {
+            if self.lines.len() > LIST_MAX_ENTRIES {
+                self.truncated = true;
+                break;
+            }
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if self.denied_paths.is_denied(&path) {
+                continue;
+            }
+            if !self.include_hidden
+                && (name.starts_with('.') || self.is_gitignored(&path, file_type.is_dir()))
+            {
+                continue;
+            }
+
+            if file_type.is_symlink() {
+                let target = fs::read_link(&path)
+                    .map(|t| t.display().to_string())
+                    .unwrap_or_default();
+                self.lines.push(format!("{}{} -> {}", indent, name, target));
+            } else if file_type.is_dir() {
+                self.lines.push(format!("{}{}/", indent, name));
+                if depth < self.max_depth {
+                    self.list(&path, depth + 1);
+                }
+            } else {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                self.lines
+                    .push(format!("{}{} ({})", indent, name, format_file_size(size)));
+            }
+        }
+
+        if pushed {
+            self.gitignores.pop();
+        }
+    }
+
+    /// Whether the innermost matching `.gitignore` rule ignores `path`.
+    fn is_gitignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for gitignore in &self.gitignores {
+            if let Some(negated) = gitignore.matches(path, is_dir) {
+                ignored = !negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// The patterns of one `.gitignore` file. Covers the common syntax:
+/// comments, `!` negation, trailing `/` for directories only, and
+/// patterns containing `/` being relative to the file's directory.
+struct Gitignore {
+    dir: PathBuf,
+    rules: Vec<GitignoreRule>,
+}
+
+struct GitignoreRule {
+    pattern: glob::Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Matched against the path relative to the `.gitignore` directory
+    /// rather than just the file name.
+    anchored: bool,
+}
+
+impl Gitignore {
+    fn load(dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(dir.join(".gitignore")).ok()?;
+        let rules: Vec<_> = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let pattern = glob::Pattern::new(line.trim_start_matches('/')).ok()?;
+                Some(GitignoreRule {
+                    pattern,
+                    negated,
+                    dir_only,
+                    anchored,
+                })
+            })
+            .collect();
+        (!rules.is_empty()).then(|| Self {
+            dir: dir.to_path_buf(),
+            rules,
+        })
+    }
+
+    /// `Some(negated)` for the last rule matching `path`, `None` if none do.
+    fn matches(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.dir).ok()?;
+        let name = path.file_name()?.to_string_lossy();
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                (is_dir || !rule.dir_only)
+                    && if rule.anchored {
+                        rule.pattern.matches_path_with(relative, options)
+                    } else {
+                        rule.pattern.matches_with(&name, options)
+                    }
+            })
+            .map(|rule| rule.negated)
+    }
+}
+
+fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_denied_paths(state_dir: &Path) -> DeniedPaths {
+        DeniedPaths::new(None, state_dir.to_path_buf(), false)
+    }
+
+    fn denying(state_dir: &Path, deny: PathBuf) -> DeniedPaths {
+        let mut policy = localgpt_sandbox::build_policy(
+            &localgpt_core::config::SandboxConfig::default(),
+            state_dir,
+            localgpt_sandbox::SandboxLevel::Standard,
+        );
+        policy.deny_paths = vec![deny];
+        DeniedPaths::new(Some(policy), state_dir.to_path_buf(), false)
+    }
+
+    fn write(path: &Path, content: &str) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_whitespace_insensitive_match_keeps_original_bytes() {
+        let content = "fn main() {\n    let  x =\t1;\n}\n";
+        let ranges = find_whitespace_insensitive(content, "let x = 1;");
+        assert_eq!(ranges.len(), 1);
+        let (start, end) = ranges[0];
+        assert_eq!(&content[start..end], "let  x =\t1;");
+    }
+
+    #[test]
+    fn test_whitespace_insensitive_match_spans_crlf() {
+        let content = "a\r\n  b\r\nc\r\n";
+        let ranges = find_whitespace_insensitive(content, "a\nb");
+        assert_eq!(ranges.len(), 1);
+        let (start, end) = ranges[0];
+        assert_eq!(&content[start..end], "a\r\n  b");
+    }
+
+    #[test]
+    fn test_whitespace_insensitive_match_widens_to_indentation_and_newline() {
+        let content = "fn f() {\n\tlet x = 1;\n}\n";
+        let ranges = find_whitespace_insensitive(content, "    let x = 1;\n");
+        assert_eq!(ranges.len(), 1);
+        let (start, end) = ranges[0];
+        assert_eq!(&content[start..end], "\tlet x = 1;\n");
+    }
+
+    #[test]
+    fn test_whitespace_insensitive_match_ignores_blank_needle() {
+        assert!(find_whitespace_insensitive("a b c", " \n\t").is_empty());
+        assert!(find_whitespace_insensitive("", "x").is_empty());
+    }
+
+    #[test]
+    fn test_read_line_range_edge_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("f.txt");
+
+        write(&file, "");
+        assert_eq!(
+            read_line_range(&file, 0, 10, 0, None).unwrap(),
+            (vec![], false)
+        );
+
+        write(&file, "a\nb");
+        let (lines, more) = read_line_range(&file, 0, 10, 0, None).unwrap();
+        assert_eq!(lines, vec!["a", "b"]);
+        assert!(!more);
+
+        write(&file, "\u{feff}a\r\nb\r\n");
+        let (lines, _) = read_line_range(&file, 0, 10, 0, None).unwrap();
+        assert_eq!(lines, vec!["a", "b"]);
+
+        write(&file, "1\n2\n3\n4\n");
+        let (lines, more) = read_line_range(&file, 1, 2, 0, None).unwrap();
+        assert_eq!(lines, vec!["2", "3"]);
+        assert!(more);
+        let (lines, more) = read_line_range(&file, 2, 2, 0, None).unwrap();
+        assert_eq!(lines, vec!["3", "4"]);
+        assert!(!more);
+        let (lines, more) = read_line_range(&file, 10, 2, 0, None).unwrap();
+        assert!(lines.is_empty());
+        assert!(!more);
+    }
+
+    #[test]
+    fn test_read_line_range_stops_at_max_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("f.txt");
+        write(&file, "aaaa\nbbbb\ncccc\n");
+        let err = read_line_range(&file, 0, 10, 8, None).unwrap_err();
+        assert!(err.to_string().contains("Stopped after reading 8 bytes"));
+        assert!(read_line_range(&file, 0, 10, 15, None).is_ok());
+    }
+
+    #[test]
+    fn test_read_last_lines_edge_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("f.log");
+
+        write(&file, "");
+        assert_eq!(read_last_lines(&file, 5, 0).unwrap(), (vec![], 0, false));
+
+        write(&file, "a\nb\nc");
+        let (lines, len, capped) = read_last_lines(&file, 2, 0).unwrap();
+        assert_eq!(lines, vec!["b", "c"]);
+        assert_eq!(len, 5);
+        assert!(!capped);
+
+        write(&file, "a\r\nb\r\n");
+        let (lines, _, _) = read_last_lines(&file, 1, 0).unwrap();
+        assert_eq!(lines, vec!["b"]);
+
+        write(&file, "a\nb\n");
+        let (lines, _, _) = read_last_lines(&file, 10, 0).unwrap();
+        assert_eq!(lines, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_read_last_lines_keeps_only_complete_lines_under_the_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("f.log");
+        write(&file, "aaaa\nbbbb\ncccc\n");
+        let (lines, len, capped) = read_last_lines(&file, 3, 7).unwrap();
+        assert_eq!(lines, vec!["cccc"]);
+        assert_eq!(len, 15);
+        assert!(capped);
+    }
+
+    #[test]
+    fn test_read_hash_covers_the_bytes_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("f.log");
+        let content = "1\n2\r\n3\n4";
+        write(&file, content);
+        let token = content_token(content.as_bytes());
+        let hasher = <sha2::Sha256 as sha2::Digest>::new;
+
+        // A line range still hashes the whole file
+        let mut h = hasher();
+        let (lines, more) = read_line_range(&file, 1, 1, 0, Some(&mut h)).unwrap();
+        assert_eq!(lines, vec!["2"]);
+        assert!(more);
+        assert_eq!(finish_content_token(h), token);
+
+        let mut h = hasher();
+        read_line_range(&file, 0, 10, 0, Some(&mut h)).unwrap();
+        assert_eq!(finish_content_token(h), token);
+
+        // The forward tail matches the backward one
+        for (n, max_bytes) in [(0, 0), (2, 0), (10, 0), (3, 4)] {
+            let mut h = hasher();
+            assert_eq!(
+                read_last_lines_hashed(&file, n, max_bytes, &mut h).unwrap(),
+                read_last_lines(&file, n, max_bytes).unwrap()
+            );
+            assert_eq!(finish_content_token(h), token);
+        }
+    }
+
+    #[test]
+    fn test_read_appended_reports_new_and_rotated_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("f.log");
+        write(&file, "one\n");
+        let (_, len, _) = read_last_lines(&file, 1, 0).unwrap();
+        assert_eq!(read_appended(&file, len, 0).unwrap(), "[no new lines]");
+
+        write(&file, "one\ntwo\nthree");
+        assert_eq!(read_appended(&file, len, 0).unwrap(), "two\nthree");
+        let capped = read_appended(&file, len, 4).unwrap();
+        assert!(capped.starts_with("two\n"));
+        assert!(capped.contains("[...5 more bytes appended]"));
+
+        write(&file, "");
+        assert_eq!(
+            read_appended(&file, len, 0).unwrap(),
+            "[file was truncated or rotated]"
+        );
+    }
+
+    #[test]
+    fn test_glob_base_dir_stops_at_first_wildcard() {
+        assert_eq!(glob_base_dir("src/**/*.rs"), PathBuf::from("src"));
+        assert_eq!(
+            glob_base_dir("/tmp/logs/app-?.log"),
+            PathBuf::from("/tmp/logs")
+        );
+        assert_eq!(glob_base_dir("docs/[ab]/x.md"), PathBuf::from("docs"));
+        assert_eq!(glob_base_dir("*.md"), PathBuf::from("."));
+        assert_eq!(
+            glob_base_dir("notes/todo.md"),
+            PathBuf::from("notes/todo.md")
+        );
+    }
+
+    #[test]
+    fn test_gitignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join(".gitignore"),
+            "# build output\n*.log\n!keep.log\nbuild/\n/docs/*.tmp\n\n",
+        );
+        let gitignore = Gitignore::load(root).unwrap();
+
+        assert_eq!(gitignore.matches(&root.join("app.log"), false), Some(false));
+        assert_eq!(
+            gitignore.matches(&root.join("sub/app.log"), false),
+            Some(false)
+        );
+        assert_eq!(gitignore.matches(&root.join("keep.log"), false), Some(true));
+        assert_eq!(gitignore.matches(&root.join("build"), true), Some(false));
+        assert_eq!(gitignore.matches(&root.join("build"), false), None);
+        assert_eq!(
+            gitignore.matches(&root.join("docs/a.tmp"), false),
+            Some(false)
+        );
+        assert_eq!(gitignore.matches(&root.join("sub/docs/a.tmp"), false), None);
+        assert_eq!(gitignore.matches(&root.join("main.rs"), false), None);
+    }
+
+    #[test]
+    fn test_gitignore_without_rules_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Gitignore::load(dir.path()).is_none());
+        write(&dir.path().join(".gitignore"), "# nothing here\n\n");
+        assert!(Gitignore::load(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_dry_run_result_shows_a_unified_diff() {
+        let path = Path::new("notes.md");
+        let changed = dry_run_result(path, Some("a\nb\n"), "a\nc\n");
+        assert!(changed.starts_with("Dry run: nothing written to notes.md"));
+        assert!(changed.contains("--- a/notes.md\n+++ b/notes.md\n"));
+        assert!(changed.contains("-b\n+c\n"));
+
+        let created = dry_run_result(path, None, "new\n");
+        assert!(created.contains("--- /dev/null\n"));
+        assert!(created.contains("+new\n"));
+
+        assert_eq!(
+            dry_run_result(path, Some("same\n"), "same\n"),
+            "Dry run: notes.md would be unchanged"
+        );
+    }
+
+    #[test]
+    fn test_edit_diff_hunks_are_capped() {
+        let old: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+        let new: String = (0..100)
+            .map(|i| {
+                if i % 10 == 0 {
+                    format!("changed {}\n", i)
+                } else {
+                    format!("line {}\n", i)
+                }
+            })
+            .collect();
+        let hunks = edit_diff_hunks(&old, &new);
+        assert_eq!(hunks.matches("@@ -").count(), EDIT_MAX_HUNKS);
+        assert!(hunks.ends_with("[5 more hunk(s) omitted]\n"));
+        assert!(hunks.contains("-line 0\n+changed 0\n"));
+
+        assert_eq!(edit_diff_hunks(&old, &old), "");
+    }
+
+    #[test]
+    fn test_find_protected_file_searches_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("a/b/notes.md"), "");
+        assert_eq!(find_protected_file(dir.path()).unwrap(), None);
+
+        let protected = dir.path().join("a/b/LocalGPT.md");
+        write(&protected, "");
+        assert_eq!(find_protected_file(dir.path()).unwrap(), Some(protected));
+    }
+
+    #[test]
+    fn test_move_path_moves_files_and_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        write(&file, "a");
+        move_path(&file, &dir.path().join("b.txt"), false).unwrap();
+        assert!(!file.exists());
+        assert_eq!(fs::read_to_string(dir.path().join("b.txt")).unwrap(), "a");
+
+        let src = dir.path().join("src");
+        write(&src.join("nested/c.txt"), "c");
+        move_path(&src, &dir.path().join("dst"), true).unwrap();
+        assert!(!src.exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dst/nested/c.txt")).unwrap(),
+            "c"
+        );
+    }
+
+    #[test]
+    fn test_copy_dir_all_copies_nested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        write(&src.join("top.txt"), "top");
+        write(&src.join("a/b/deep.txt"), "deep");
+        fs::create_dir_all(src.join("empty")).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("top.txt", src.join("link")).unwrap();
+
+        let dst = dir.path().join("dst");
+        copy_dir_all(&src, &dst).unwrap();
+        assert_eq!(fs::read_to_string(dst.join("top.txt")).unwrap(), "top");
+        assert_eq!(
+            fs::read_to_string(dst.join("a/b/deep.txt")).unwrap(),
+            "deep"
+        );
+        assert!(dst.join("empty").is_dir());
+        #[cfg(unix)]
+        assert_eq!(
+            fs::read_link(dst.join("link")).unwrap(),
+            PathBuf::from("top.txt")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_move_file_onto_existing_target_needs_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = dir.path().join("state");
+        let tool = MoveFileTool::new(
+            state_dir.clone(),
+            no_denied_paths(&state_dir),
+            MutationAudit::new(state_dir, false, security::SecretScanner::default()),
+        );
+        let from = dir.path().join("from.txt");
+        let to = dir.path().join("to.txt");
+        write(&from, "new");
+        write(&to, "old");
+        let args =
+            |overwrite: bool| json!({ "from": from, "to": to, "overwrite": overwrite }).to_string();
+
+        let err = tool.execute(&args(false)).await.unwrap_err();
+        assert!(err.to_string().contains("Destination already exists"));
+        assert_eq!(fs::read_to_string(&to).unwrap(), "old");
+        assert!(from.exists());
+
+        tool.execute(&args(true)).await.unwrap();
+        assert_eq!(fs::read_to_string(&to).unwrap(), "new");
+        assert!(!from.exists());
+
+        let target_dir = dir.path().join("target_dir");
+        fs::create_dir_all(&target_dir).unwrap();
+        write(&from, "again");
+        let args = json!({ "from": from, "to": target_dir, "overwrite": true }).to_string();
+        let err = tool.execute(&args).await.unwrap_err();
+        assert!(err.to_string().contains("Destination is a directory"));
+        assert!(from.exists());
+    }
+
+    #[tokio::test]
+    async fn test_move_refuses_ancestors_of_denied_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = dir.path().join("state");
+        let home = dir.path().join("home");
+        let ssh_key = home.join(".ssh/id_rsa");
+        write(&ssh_key, "secret");
+        let tool = MoveFileTool::new(
+            state_dir.clone(),
+            denying(&state_dir, home.join(".ssh")),
+            MutationAudit::new(state_dir.clone(), false, security::SecretScanner::default()),
+        );
+
+        let args = json!({ "from": home, "to": dir.path().join("moved") }).to_string();
+        let err = tool.execute(&args).await.unwrap_err();
+        assert!(err.to_string().contains("blocked by sandbox policy"));
+
+        // Nor can something be moved over the top of it
+        let other = dir.path().join("other");
+        fs::create_dir_all(&other).unwrap();
+        let args = json!({ "from": other, "to": home, "overwrite": true }).to_string();
+        let err = tool.execute(&args).await.unwrap_err();
+        assert!(err.to_string().contains("blocked by sandbox policy"));
+        assert_eq!(fs::read_to_string(&ssh_key).unwrap(), "secret");
+
+        let args = json!({ "from": other, "to": dir.path().join("renamed") }).to_string();
+        tool.execute(&args).await.unwrap();
+        assert!(dir.path().join("renamed").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_delete_refuses_ancestors_of_denied_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_dir = dir.path().join("state");
+        let home = dir.path().join("home");
+        let ssh_key = home.join(".ssh/id_rsa");
+        write(&ssh_key, "secret");
+        let denied = || denying(&state_dir, home.join(".ssh"));
+        let audit =
+            || MutationAudit::new(state_dir.clone(), false, security::SecretScanner::default());
+
+        for hard_delete in [false, true] {
+            let deleter = DeleteFileTool::new(state_dir.clone(), denied(), hard_delete, audit());
+            let args = json!({ "path": home, "recursive": true }).to_string();
+            let err = deleter.execute(&args).await.unwrap_err();
+            assert!(err.to_string().contains("blocked by sandbox policy"));
+        }
+        assert_eq!(fs::read_to_string(&ssh_key).unwrap(), "secret");
+
+        // Unrelated directories are still fine
+        let other = dir.path().join("other");
+        fs::create_dir_all(&other).unwrap();
+        let deleter = DeleteFileTool::new(state_dir.clone(), denied(), true, audit());
+        let args = json!({ "path": other, "recursive": true }).to_string();
+        deleter.execute(&args).await.unwrap();
+        assert!(!other.exists());
+    }
+
+    #[test]
+    fn test_hash_directory_tracks_contents_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = HashFileTool::new(no_denied_paths(dir.path()));
+        let root = dir.path().join("tree");
+        fs::create_dir_all(&root).unwrap();
+
+        let empty = tool.hash_directory(&root, HashAlgorithm::Sha256).unwrap();
+        assert!(empty.contains("0 files, sha256"));
+
+        write(&root.join("a.txt"), "a");
+        write(&root.join("sub/b.txt"), "b");
+        let hashed = tool.hash_directory(&root, HashAlgorithm::Sha256).unwrap();
+        let lines: Vec<&str> = hashed.lines().collect();
+        assert!(lines[0].contains("2 files, sha256"));
+        assert!(lines[1].ends_with("  a.txt"));
+        assert!(lines[2].ends_with("  sub/b.txt"));
+        let aggregate = |output: &str| output.split_whitespace().next().unwrap().to_string();
+
+        // Same listing, same aggregate
+        let again = tool.hash_directory(&root, HashAlgorithm::Sha256).unwrap();
+        assert_eq!(aggregate(&again), aggregate(&hashed));
+
+        // A rename with identical content still changes the aggregate
+        fs::rename(root.join("a.txt"), root.join("c.txt")).unwrap();
+        let renamed = tool.hash_directory(&root, HashAlgorithm::Sha256).unwrap();
+        assert_ne!(aggregate(&renamed), aggregate(&hashed));
 
-        Ok(format!("Replaced {} occurrence(s) in {}", count, path))
+        // Empty files hash like empty input
+        write(&root.join("empty.txt"), "");
+        let with_empty = tool.hash_directory(&root, HashAlgorithm::Sha256).unwrap();
+        assert!(with_empty.contains(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  empty.txt"
+        ));
     }
 }
@@ -1,4 +1,5 @@
 pub mod providers;
+pub mod replay;
 pub mod sanitize;
 pub mod session;
 pub mod session_store;
@@ -10,11 +11,12 @@ pub use providers::{
     ImageAttachment, LLMProvider, LLMResponse, LLMResponseContent, Message, Role, StreamChunk,
     StreamEvent, StreamResult, ToolCall, ToolSchema, Usage,
 };
+pub use replay::{ToolCallRecord, ToolRecorder, ToolReplay};
 pub use sanitize::{
-    EXTERNAL_CONTENT_END, EXTERNAL_CONTENT_START, MEMORY_CONTENT_END, MEMORY_CONTENT_START,
-    MemorySource, SanitizeResult, TOOL_OUTPUT_END, TOOL_OUTPUT_START, detect_suspicious_patterns,
-    sanitize_tool_output, truncate_with_notice, wrap_external_content, wrap_memory_content,
-    wrap_tool_output,
+    EXTERNAL_CONTENT_END, EXTERNAL_CONTENT_START, FullOutputStore, MEMORY_CONTENT_END,
+    MEMORY_CONTENT_START, MemorySource, SanitizeResult, TOOL_OUTPUT_END, TOOL_OUTPUT_START,
+    Truncation, detect_suspicious_patterns, sanitize_tool_output, truncate_at_char_boundary,
+    truncate_with_notice, wrap_external_content, wrap_memory_content, wrap_tool_output,
 };
 pub use session::{
     DEFAULT_AGENT_ID, Session, SessionInfo, SessionMessage, SessionSearchResult, SessionStatus,
@@ -27,7 +29,7 @@ pub use system_prompt::{
     HEARTBEAT_OK_TOKEN, SILENT_REPLY_TOKEN, build_heartbeat_prompt, is_heartbeat_ok,
     is_silent_reply,
 };
-pub use tools::{Tool, ToolResult, extract_tool_detail};
+pub use tools::{ApprovalPolicy, Tool, ToolResult, extract_tool_detail};
 
 use anyhow::Result;
 use std::path::PathBuf;
@@ -64,6 +66,39 @@ fn generate_slug(text: &str) -> String {
         .collect()
 }
 
+/// Open the tool recorder/replay configured in `tools.record_path` and
+/// `tools.replay_path`.
+fn open_tool_recording(config: &Config) -> Result<(Option<ToolRecorder>, Option<ToolReplay>)> {
+    let expand = |p: &str| PathBuf::from(shellexpand::tilde(p).to_string());
+
+    let replay = match config.tools.replay_path {
+        Some(ref path) => {
+            let replay = ToolReplay::load(&expand(path))?;
+            info!(
+                "Replaying {} recorded tool calls from {}",
+                replay.remaining(),
+                path
+            );
+            Some(replay)
+        }
+        None => None,
+    };
+
+    // Recording a replay would just duplicate the fixture
+    let recorder = match config.tools.record_path {
+        Some(ref path) if replay.is_none() => {
+            info!("Recording tool calls to {}", path);
+            Some(ToolRecorder::open(
+                &expand(path),
+                config.tools.record_redact,
+            )?)
+        }
+        _ => None,
+    };
+
+    Ok((recorder, replay))
+}
+
 #[derive(Debug, Clone)]
 pub struct AgentConfig {
     pub model: String,
@@ -82,6 +117,16 @@ pub struct Agent {
     cumulative_usage: Usage,
     /// Verified security policy content (None if missing, unsigned, or tampered)
     verified_security_policy: Option<String>,
+    /// Records executed tool calls (`tools.record_path`)
+    tool_recorder: Option<ToolRecorder>,
+    /// Serves recorded tool results instead of executing (`tools.replay_path`)
+    tool_replay: Option<ToolReplay>,
+    /// Built-in plus `security.secret_patterns` formats, for log redaction
+    secret_scanner: crate::security::SecretScanner,
+    /// Where truncated tool output is saved in full
+    full_output: FullOutputStore,
+    /// Which tool calls need user confirmation
+    approval_policy: ApprovalPolicy,
 }
 
 impl Agent {
@@ -191,6 +236,12 @@ impl Agent {
             }
         };
 
+        let (tool_recorder, tool_replay) = open_tool_recording(app_config)?;
+        let secret_scanner = crate::security::SecretScanner::from_config(app_config)?;
+        let approval_policy = ApprovalPolicy::from_config(app_config);
+        let full_output = FullOutputStore::new(&app_config.paths.state_dir, secret_scanner.clone());
+        full_output.prune();
+
         Ok(Self {
             config,
             app_config: app_config.clone(),
@@ -200,6 +251,11 @@ impl Agent {
             tools,
             cumulative_usage: Usage::default(),
             verified_security_policy,
+            tool_recorder,
+            tool_replay,
+            secret_scanner,
+            full_output,
+            approval_policy,
         })
     }
 
@@ -210,6 +266,8 @@ impl Agent {
         memory: Arc<MemoryManager>,
         tools: Vec<Box<dyn Tool>>,
     ) -> Result<Self> {
+        tools::validate_tool_names(&tools)?;
+
         let agent_config = AgentConfig {
             model: app_config.agent.default_model.clone(),
             context_window: app_config.agent.context_window,
@@ -229,6 +287,12 @@ impl Agent {
             }
         };
 
+        let (tool_recorder, tool_replay) = open_tool_recording(&app_config)?;
+        let secret_scanner = crate::security::SecretScanner::from_config(&app_config)?;
+        let approval_policy = ApprovalPolicy::from_config(&app_config);
+        let full_output = FullOutputStore::new(&app_config.paths.state_dir, secret_scanner.clone());
+        full_output.prune();
+
         Ok(Self {
             config: agent_config,
             app_config,
@@ -238,25 +302,69 @@ impl Agent {
             tools,
             cumulative_usage: Usage::default(),
             verified_security_policy,
+            tool_recorder,
+            tool_replay,
+            secret_scanner,
+            full_output,
+            approval_policy,
         })
     }
 
     /// Add extra tools to an already-constructed agent (e.g., dangerous CLI tools).
-    pub fn extend_tools(&mut self, extra: Vec<Box<dyn Tool>>) {
+    ///
+    /// Fails if any tool name collides with a tool that is already registered.
+    pub fn extend_tools(&mut self, extra: Vec<Box<dyn Tool>>) -> Result<()> {
+        for tool in &extra {
+            if self.tools.iter().any(|t| t.name() == tool.name()) {
+                anyhow::bail!("Tool '{}' is already registered", tool.name());
+            }
+        }
+        tools::validate_tool_names(&extra)?;
         self.tools.extend(extra);
+        Ok(())
+    }
+
+    /// Register a single extension tool.
+    ///
+    /// Names of built-in tools are reserved: registering e.g. a `bash` tool is
+    /// rejected unless `allow_override` is set, in which case the existing
+    /// tool with that name is replaced.
+    pub fn register_tool(&mut self, tool: Box<dyn Tool>, allow_override: bool) -> Result<()> {
+        let name = tool.name().to_string();
+        if name.trim().is_empty() {
+            anyhow::bail!("Tool with empty name");
+        }
+        let existing = self.tools.iter().position(|t| t.name() == name);
+        if !allow_override {
+            if tools::is_reserved_tool_name(&name) {
+                anyhow::bail!("Tool name '{}' is reserved for a built-in tool", name);
+            }
+            if existing.is_some() {
+                anyhow::bail!("Tool '{}' is already registered", name);
+            }
+        }
+        match existing {
+            Some(idx) => {
+                tracing::warn!("Overriding registered tool '{}'", name);
+                self.tools[idx] = tool;
+            }
+            None => self.tools.push(tool),
+        }
+        Ok(())
     }
 
     pub fn model(&self) -> &str {
         &self.config.model
     }
 
-    /// Check if a tool requires user approval before execution
-    pub fn requires_approval(&self, tool_name: &str) -> bool {
-        self.app_config
-            .tools
-            .require_approval
-            .iter()
-            .any(|t| t == tool_name)
+    /// Check if a tool call requires user approval before execution
+    pub fn requires_approval(&self, tool_name: &str, arguments: &str) -> bool {
+        self.approval_policy.requires_approval(tool_name, arguments)
+    }
+
+    /// Approval rules for this agent's config
+    pub fn approval_policy(&self) -> &ApprovalPolicy {
+        &self.approval_policy
     }
 
     /// Get the list of tools that require approval
@@ -273,6 +381,11 @@ impl Agent {
         Ok(())
     }
 
+    /// The memory manager this agent's tools share.
+    pub fn memory_manager(&self) -> Arc<MemoryManager> {
+        Arc::clone(&self.memory)
+    }
+
     pub fn memory_chunk_count(&self) -> usize {
         self.memory.chunk_count().unwrap_or(0)
     }
@@ -480,7 +593,8 @@ impl Agent {
                 for call in &calls {
                     debug!(
                         "Executing tool: {} with args: {}",
-                        call.name, call.arguments
+                        call.name,
+                        self.loggable_args(call)
                     );
 
                     let result = self.execute_tool(call).await;
@@ -528,10 +642,67 @@ impl Agent {
         }
     }
 
+    /// Tool call arguments as they may appear in logs (secrets redacted per config).
+    fn loggable_args(&self, call: &ToolCall) -> String {
+        tools::redact_for_log(
+            &call.name,
+            &call.arguments,
+            &self.app_config.tools.log_redaction_exempt,
+            &self.secret_scanner,
+        )
+    }
+
+    /// Execute a tool, or serve its recorded result in replay mode.
+    async fn run_tool(&self, tool: &dyn Tool, call: &ToolCall) -> Result<String> {
+        if let Some(ref replay) = self.tool_replay {
+            return replay.next(&call.name, &call.arguments);
+        }
+
+        let result = tool.execute(&call.arguments).await;
+        // Independent of `audit_tool_mutations`: a write can log both a
+        // ToolMutation (what changed) and a ToolInvoked (that it was called)
+        if self.app_config.security.audit_tool_invocations() {
+            let detail = tools::invocation_audit_detail(
+                &call.name,
+                &call.arguments,
+                &result,
+                &self.secret_scanner,
+            );
+            let state_dir = self.app_config.paths.state_dir.clone();
+            let source = format!("tool:{}", call.name);
+            // Appending takes a blocking file lock
+            let appended = tokio::task::spawn_blocking(move || {
+                crate::security::append_audit_entry_with_detail(
+                    &state_dir,
+                    crate::security::AuditAction::ToolInvoked,
+                    "",
+                    &source,
+                    Some(&detail),
+                )
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|appended| appended);
+            if let Err(e) = appended {
+                tracing::warn!("Failed to audit {} call: {}", call.name, e);
+            }
+        }
+        if let Some(ref recorder) = self.tool_recorder
+            && let Err(e) = recorder.record(&call.name, &call.arguments, &result)
+        {
+            tracing::warn!(
+                "Failed to record tool call to {}: {}",
+                recorder.path().display(),
+                e
+            );
+        }
+        result
+    }
+
     async fn execute_tool(&self, call: &ToolCall) -> Result<(String, Vec<String>)> {
         for tool in &self.tools {
             if tool.name() == call.name {
-                let raw_output = tool.execute(&call.arguments).await?;
+                let raw_output = self.run_tool(tool.as_ref(), call).await?;
 
                 // Apply sanitization if configured
                 if self.app_config.tools.use_content_delimiters {
@@ -540,7 +711,12 @@ impl Agent {
                     } else {
                         None
                     };
-                    let result = sanitize::wrap_tool_output(&call.name, &raw_output, max_chars);
+                    let result = sanitize::wrap_tool_output(
+                        &call.name,
+                        &raw_output,
+                        max_chars,
+                        Some(&self.full_output),
+                    );
 
                     // Log warnings for suspicious patterns
                     if self.app_config.tools.log_injection_warnings && !result.warnings.is_empty() {
@@ -889,7 +1065,11 @@ impl Agent {
         let stats = self.memory.reindex(true)?;
 
         // Generate embeddings for new chunks (if embedding provider is configured)
-        let (_, embedded) = self.memory.generate_embeddings(50).await?;
+        let embedded = self
+            .memory
+            .generate_embeddings_with_progress(|_| {})
+            .await?
+            .embedded;
 
         Ok((stats.files_processed, stats.chunks_indexed, embedded))
     }
@@ -988,7 +1168,8 @@ impl Agent {
         for call in &tool_calls {
             debug!(
                 "Executing tool: {} with args: {}",
-                call.name, call.arguments
+                call.name,
+                self.loggable_args(call)
             );
 
             let result = self.execute_tool(call).await;
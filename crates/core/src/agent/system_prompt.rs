@@ -120,6 +120,13 @@ pub fn build_system_prompt(params: SystemPromptParams) -> String {
          Sessions are auto-saved to memory/ when starting a new session."
             .to_string(),
     );
+    if params.tool_names.contains(&"memory_write") {
+        lines.push(
+            "For dated notes, use memory_write: it appends to today's daily log and indexes \
+             the entry so memory_search finds it immediately."
+                .to_string(),
+        );
+    }
     lines.push(String::new());
 
     // Memory recall guidance
@@ -244,10 +251,19 @@ fn get_tool_summary(tool_name: &str) -> &'static str {
     match tool_name {
         "bash" => "Run shell commands",
         "read_file" => "Read file contents",
+        "read_glob" => "Read all files matching a glob pattern",
         "write_file" => "Create or overwrite files",
         "edit_file" => "Make precise edits to files",
+        "move_file" => "Move or rename files and directories",
+        "delete_file" => "Delete files and directories",
+        "hash_file" => "Compute file or directory checksums",
+        "grep" => "Search file contents with a regex",
+        "list_directory" => "List a directory tree with file sizes",
         "memory_search" => "Semantically search MEMORY.md + memory/*.md",
         "memory_get" => "Fetch specific lines from memory files (use after memory_search)",
+        "memory_write" => "Append a timestamped note to today's daily log (indexed immediately)",
+        "memory_reindex" => "Refresh the memory search index after editing memory files",
+        "scratchpad" => "Keep working notes for this run (not persisted)",
         "web_fetch" => "Fetch and extract content from a URL",
         _ => "Tool",
     }
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tracing::debug;
+
+use super::Tool;
+use crate::agent::providers::ToolSchema;
+
+/// Total size cap (keys + values) for one agent's scratchpad.
+pub const SCRATCHPAD_MAX_BYTES: usize = 64 * 1024;
+
+/// Ephemeral key/value notes for the current run.
+///
+/// Entries live in memory only and are dropped with the agent, so the model
+/// can track a plan or a list of files to touch without writing to
+/// `MEMORY.md` or repeating it in the conversation.
+pub struct ScratchpadTool {
+    entries: Mutex<BTreeMap<String, String>>,
+    max_bytes: usize,
+}
+
+impl ScratchpadTool {
+    pub fn new() -> Self {
+        Self::with_max_bytes(SCRATCHPAD_MAX_BYTES)
+    }
+
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self {
+            entries: Mutex::new(BTreeMap::new()),
+            max_bytes,
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<String> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        let used: usize = entries
+            .iter()
+            .filter(|(k, _)| k.as_str() != key)
+            .map(|(k, v)| k.len() + v.len())
+            .sum();
+        let needed = key.len() + value.len();
+        if used + needed > self.max_bytes {
+            anyhow::bail!(
+                "Scratchpad full: {} of {} bytes used, '{}' needs {}",
+                used,
+                self.max_bytes,
+                key,
+                needed
+            );
+        }
+
+        let replaced = entries.insert(key.to_string(), value.to_string()).is_some();
+        Ok(format!(
+            "{} '{}' ({} bytes)",
+            if replaced { "Updated" } else { "Set" },
+            key,
+            value.len()
+        ))
+    }
+
+    fn get(&self, key: &str) -> String {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some(value) => value.clone(),
+            None => format!("No scratchpad entry '{}'", key),
+        }
+    }
+
+    fn list(&self) -> String {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.is_empty() {
+            return "Scratchpad is empty".to_string();
+        }
+
+        let used: usize = entries.iter().map(|(k, v)| k.len() + v.len()).sum();
+        let mut out = format!(
+            "{} entries, {} of {} bytes used\n",
+            entries.len(),
+            used,
+            self.max_bytes
+        );
+        for (key, value) in entries.iter() {
+            out.push_str(&format!("- {} ({} bytes)\n", key, value.len()));
+        }
+        out
+    }
+}
+
+impl Default for ScratchpadTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for ScratchpadTool {
+    fn name(&self) -> &str {
+        "scratchpad"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "scratchpad".to_string(),
+            description: "Working notes for this run only (not persisted, not in MEMORY.md). Use to track a plan or intermediate state across tool calls. Actions: set (key, value), get (key), list.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["set", "get", "list"],
+                        "description": "Operation to perform"
+                    },
+                    "key": {
+                        "type": "string",
+                        "description": "Entry name (required for set and get)"
+                    },
+                    "value": {
+                        "type": "string",
+                        "description": "Entry content (required for set; replaces any existing value)"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let action = args["action"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing action"))?;
+
+        debug!("Scratchpad: {}", action);
+
+        match action {
+            "set" => {
+                let key = args["key"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Missing key"))?;
+                let value = args["value"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Missing value"))?;
+                self.set(key, value)
+            }
+            "get" => {
+                let key = args["key"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Missing key"))?;
+                Ok(self.get(key))
+            }
+            "list" => Ok(self.list()),
+            other => anyhow::bail!("Unknown scratchpad action: {}", other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_set_get_list() {
+        let tool = ScratchpadTool::new();
+        tool.execute(r#"{"action":"set","key":"plan","value":"1. read\n2. edit"}"#)
+            .await
+            .unwrap();
+
+        let value = tool
+            .execute(r#"{"action":"get","key":"plan"}"#)
+            .await
+            .unwrap();
+        assert_eq!(value, "1. read\n2. edit");
+
+        let list = tool.execute(r#"{"action":"list"}"#).await.unwrap();
+        assert!(list.starts_with("1 entries"));
+        assert!(list.contains("- plan"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_key() {
+        let tool = ScratchpadTool::new();
+        let out = tool
+            .execute(r#"{"action":"get","key":"nope"}"#)
+            .await
+            .unwrap();
+        assert!(out.contains("No scratchpad entry"));
+    }
+
+    #[tokio::test]
+    async fn test_size_cap() {
+        let tool = ScratchpadTool::with_max_bytes(16);
+        tool.execute(r#"{"action":"set","key":"a","value":"0123456789"}"#)
+            .await
+            .unwrap();
+        assert!(
+            tool.execute(r#"{"action":"set","key":"b","value":"0123456789"}"#)
+                .await
+                .is_err()
+        );
+        // Replacing an entry only counts its new size
+        tool.execute(r#"{"action":"set","key":"a","value":"012345678901234"}"#)
+            .await
+            .unwrap();
+    }
+}
@@ -1,15 +1,19 @@
 mod migrate;
+mod profile;
 mod schema;
 
 pub use migrate::check_openclaw_detected;
+pub use profile::PROFILE_ENV;
 pub use schema::*;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::paths::Paths;
+use crate::security::{EnvPolicy, RiskLevel};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
@@ -17,6 +21,25 @@ pub struct Config {
     #[serde(skip)]
     pub paths: Paths,
 
+    /// Explicit state directory (sessions, audit log, logs).
+    ///
+    /// Overrides the XDG default; `LOCALGPT_STATE_DIR` still takes precedence.
+    /// Must be an absolute (or `~`-relative) path to a writable directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_dir: Option<String>,
+
+    /// Profile activated by default (`LOCALGPT_CONFIG_PROFILE` takes precedence)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// Named partial configs merged over the base when selected
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, toml::Table>,
+
+    /// Profile this config was loaded with (not serialized)
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+
     #[serde(default)]
     pub agent: AgentConfig,
 
@@ -70,10 +93,49 @@ pub struct ToolsConfig {
     #[serde(default = "default_bash_timeout")]
     pub bash_timeout_ms: u64,
 
-    /// Maximum bytes to return from web_fetch
+    /// Stop reading bash output past this many bytes (stdout and stderr
+    /// together) and kill the command. 0 = unlimited. Sandboxed commands
+    /// use `sandbox.max_output_bytes` instead.
+    #[serde(default = "default_bash_max_output_bytes")]
+    pub bash_max_output_bytes: usize,
+
+    /// Shell the bash tool runs commands with: a name looked up on PATH
+    /// or an absolute path. Checked at startup.
+    #[serde(default = "default_shell")]
+    pub shell: String,
+
+    /// Flag passed to `shell` before the command
+    #[serde(default = "default_shell_flag")]
+    pub shell_flag: String,
+
+    /// read_file fails rather than read more than this many bytes of a
+    /// file to reach the requested lines. 0 = unlimited.
+    #[serde(default = "default_read_file_max_bytes")]
+    pub read_file_max_bytes: u64,
+
+    /// delete_file removes targets outright instead of moving them into
+    /// `<state_dir>/.localgpt_trash/` (default: false). Trashed entries
+    /// older than 30 days are removed on the next delete.
+    #[serde(default)]
+    pub hard_delete: bool,
+
+    /// Maximum characters of a page web_fetch returns; longer pages are
+    /// cut to a head and tail excerpt with the full body saved under
+    /// `<state_dir>/tmp/tool-output`
     #[serde(default = "default_web_fetch_max_bytes")]
     pub web_fetch_max_bytes: usize,
 
+    /// web_fetch refuses responses whose Content-Length exceeds this,
+    /// before downloading the body
+    #[serde(default = "default_web_fetch_max_download_bytes")]
+    pub web_fetch_max_download_bytes: u64,
+
+    /// Content types web_fetch may read, e.g. "text/*", "application/json",
+    /// "application/*+xml". Empty allows everything. Responses without a
+    /// Content-Type are checked as "application/octet-stream".
+    #[serde(default = "default_web_fetch_allowed_types")]
+    pub web_fetch_allowed_types: Vec<String>,
+
     /// Tools that require user approval before execution
     /// e.g., ["bash", "write_file", "edit_file"]
     #[serde(default)]
@@ -91,9 +153,27 @@ pub struct ToolsConfig {
     #[serde(default = "default_true")]
     pub use_content_delimiters: bool,
 
+    /// Tools whose arguments are logged verbatim. Arguments of all other
+    /// tools pass through secret redaction before reaching the log.
+    #[serde(default = "default_log_redaction_exempt")]
+    pub log_redaction_exempt: Vec<String>,
+
     /// Web search configuration (disabled by default)
     #[serde(default)]
     pub web_search: Option<WebSearchConfig>,
+
+    /// Append every tool call and its result to this JSONL file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_path: Option<String>,
+
+    /// Serve tool results from this recording instead of executing tools.
+    /// Calls that weren't recorded fail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_path: Option<String>,
+
+    /// Redact secrets from recorded arguments and results
+    #[serde(default = "default_true")]
+    pub record_redact: bool,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -170,6 +250,105 @@ pub struct SecurityConfig {
     /// Skip injecting the hardcoded security suffix (default: false)
     #[serde(default)]
     pub disable_suffix: bool,
+
+    /// Drop environment variables whose values look like secrets before
+    /// running bash commands (default: false)
+    ///
+    /// Catches credentials that name-based filtering misses, e.g. a token
+    /// exported from a sourced `.env` file. Dropped variables are logged by
+    /// name only.
+    #[serde(default)]
+    pub scrub_secret_env: bool,
+
+    /// How bash commands inherit the daemon's environment (default: `deny`)
+    ///
+    /// `inherit` passes everything through, `deny` drops names matching
+    /// `env_deny_patterns`, and `allow_only` drops everything except names
+    /// matching `env_allow_patterns` plus `PATH`, `HOME` and `LANG`. Only
+    /// the list for the chosen mode applies.
+    #[serde(default)]
+    pub env_policy: EnvPolicy,
+
+    /// Environment variable names to drop before running bash commands,
+    /// with `*` wildcards anywhere in the name (e.g. `AWS_*_KEY`,
+    /// `*TOKEN*`). Matching is case-sensitive.
+    #[serde(default)]
+    pub env_deny_patterns: Vec<String>,
+
+    /// Environment variable names passed to bash under
+    /// `env_policy = "allow_only"`, with the same wildcards
+    #[serde(default)]
+    pub env_allow_patterns: Vec<String>,
+
+    /// Run bash commands in a new, empty network namespace (default: false)
+    ///
+    /// Linux only: spawned commands (curl, nc, ...) have no network access
+    /// at all. Ignored with a warning on other platforms.
+    #[serde(default)]
+    pub isolate_bash_network: bool,
+
+    /// Run bash commands as this uid instead of the daemon's own (Unix,
+    /// default: unset)
+    ///
+    /// The daemon needs the right to switch users (root, or
+    /// `CAP_SETUID`/`CAP_SETGID` on Linux), and the user needs access to
+    /// the state directory's scratch space. If the switch fails the command
+    /// is refused rather than run as the daemon's user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bash_run_as_uid: Option<u32>,
+
+    /// Group for `bash_run_as_uid` (default: the same number as the uid)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bash_run_as_gid: Option<u32>,
+
+    /// Ask for approval only for bash commands at or above this risk level
+    /// (`low`, `medium`, `high`). Unset by default: bash approval then
+    /// follows `tools.require_approval` alone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm_bash_risk: Option<RiskLevel>,
+
+    /// Return a generic "Path not permitted" to the model when a file tool
+    /// hits a sandbox-denied path, instead of naming the path. The full
+    /// path still goes to the audit log. Defaults to `strict_policy`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generic_path_errors: Option<bool>,
+
+    /// Append an audit entry for every successful write_file, edit_file and
+    /// bash call, not just blocked ones. Defaults to `strict_policy`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_tool_mutations: Option<bool>,
+
+    /// Append an audit entry for every tool call, read-only ones included,
+    /// with its outcome. Defaults to `strict_policy`. Independent of
+    /// `audit_tool_mutations`: with both on, a write gets both entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_tool_invocations: Option<bool>,
+
+    /// Extra regex patterns that classify a bash command as high risk
+    #[serde(default)]
+    pub high_risk_patterns: Vec<String>,
+
+    /// Extra secret formats as `[kind, regex]` pairs, redacted from logs
+    /// and scrubbed from the bash environment like the built-in ones
+    #[serde(default)]
+    pub secret_patterns: Vec<(String, String)>,
+}
+
+impl SecurityConfig {
+    /// Whether denied-path errors should hide the path (see `generic_path_errors`).
+    pub fn generic_path_errors(&self) -> bool {
+        self.generic_path_errors.unwrap_or(self.strict_policy)
+    }
+
+    /// Whether successful tool mutations are audited (see `audit_tool_mutations`).
+    pub fn audit_tool_mutations(&self) -> bool {
+        self.audit_tool_mutations.unwrap_or(self.strict_policy)
+    }
+
+    /// Whether every tool call is audited (see `audit_tool_invocations`).
+    pub fn audit_tool_invocations(&self) -> bool {
+        self.audit_tool_invocations.unwrap_or(self.strict_policy)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -347,12 +526,44 @@ pub struct MemoryConfig {
     #[serde(default = "default_embedding_cache_dir")]
     pub embedding_cache_dir: String,
 
+    /// Chunks sent per embedding request. Keep within the model's batch
+    /// limit (local) or the API's per-request input limit (remote).
+    #[serde(default = "default_embedding_batch_size")]
+    pub embedding_batch_size: usize,
+
+    /// Embedding requests in flight at once during reindexing
+    #[serde(default = "default_embedding_concurrency")]
+    pub embedding_concurrency: usize,
+
+    /// Retries for a failed embedding call, with exponential backoff
+    #[serde(default = "default_embedding_retries")]
+    pub embedding_retries: u32,
+
+    /// Delay before the first retry in milliseconds, doubled each retry
+    #[serde(default = "default_embedding_retry_backoff_ms")]
+    pub embedding_retry_backoff_ms: u64,
+
+    /// Consecutive failed embedding calls before search falls back to
+    /// keywords (0 = never fall back)
+    #[serde(default = "default_embedding_failure_threshold")]
+    pub embedding_failure_threshold: u32,
+
+    /// Seconds to stay on keyword search before trying embeddings again
+    #[serde(default = "default_embedding_cooldown_secs")]
+    pub embedding_cooldown_secs: u64,
+
     #[serde(default = "default_chunk_size")]
     pub chunk_size: usize,
 
     #[serde(default = "default_chunk_overlap")]
     pub chunk_overlap: usize,
 
+    /// Globs (relative to the workspace) for the files that count as
+    /// memory: what the grep search fallback reads and what `memory_get`
+    /// may open. Non-`.md` matches are also indexed.
+    #[serde(default = "default_memory_roots")]
+    pub roots: Vec<String>,
+
     /// Additional paths to index (relative to workspace or absolute)
     /// Each path uses a glob pattern for file matching
     #[serde(default = "default_index_paths")]
@@ -376,6 +587,18 @@ pub struct MemoryIndexPath {
     pub pattern: String,
 }
 
+impl MemoryIndexPath {
+    /// Directory this entry points at: `~` and absolute paths as given,
+    /// anything else relative to the workspace.
+    pub fn base_path(&self, workspace: &Path) -> PathBuf {
+        if self.path.starts_with('~') || self.path.starts_with('/') {
+            PathBuf::from(shellexpand::tilde(&self.path).to_string())
+        } else {
+            workspace.join(&self.path)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     #[serde(default = "default_true")]
@@ -386,6 +609,11 @@ pub struct ServerConfig {
 
     #[serde(default = "default_bind")]
     pub bind: String,
+
+    /// Requests per minute allowed from each client IP on `/api/*` and
+    /// `/health` (0 = unlimited). Bursts up to the full minute's budget.
+    #[serde(default = "default_rate_limit_per_minute")]
+    pub rate_limit_per_minute: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -423,15 +651,46 @@ fn default_reserve_tokens() -> usize {
 fn default_max_tokens() -> usize {
     4096
 }
+fn default_shell() -> String {
+    "bash".to_string()
+}
+fn default_shell_flag() -> String {
+    "-c".to_string()
+}
 fn default_bash_timeout() -> u64 {
     30000 // 30 seconds
 }
+fn default_bash_max_output_bytes() -> usize {
+    1024 * 1024 // 1 MiB
+}
+fn default_read_file_max_bytes() -> u64 {
+    64 * 1024 * 1024 // 64 MiB
+}
 fn default_web_fetch_max_bytes() -> usize {
     10000
 }
+fn default_web_fetch_max_download_bytes() -> u64 {
+    10 * 1024 * 1024 // 10 MiB
+}
+fn default_web_fetch_allowed_types() -> Vec<String> {
+    [
+        "text/*",
+        "application/json",
+        "application/*+json",
+        "application/xml",
+        "application/*+xml",
+        "application/javascript",
+    ]
+    .iter()
+    .map(|t| t.to_string())
+    .collect()
+}
 fn default_tool_output_max_chars() -> usize {
     50000 // 50k characters max for tool output by default
 }
+fn default_log_redaction_exempt() -> Vec<String> {
+    vec!["memory_search".to_string(), "memory_get".to_string()]
+}
 fn default_openai_base_url() -> String {
     "https://api.openai.com/v1".to_string()
 }
@@ -471,12 +730,37 @@ fn default_embedding_model() -> String {
 fn default_embedding_cache_dir() -> String {
     "~/.cache/localgpt/embeddings".to_string()
 }
+fn default_embedding_batch_size() -> usize {
+    32
+}
+fn default_embedding_concurrency() -> usize {
+    2
+}
+fn default_embedding_retries() -> u32 {
+    2
+}
+fn default_embedding_retry_backoff_ms() -> u64 {
+    250
+}
+fn default_embedding_failure_threshold() -> u32 {
+    3
+}
+fn default_embedding_cooldown_secs() -> u64 {
+    60
+}
 fn default_chunk_size() -> usize {
     400
 }
 fn default_chunk_overlap() -> usize {
     80
 }
+fn default_memory_roots() -> Vec<String> {
+    vec![
+        "MEMORY.md".to_string(),
+        "HEARTBEAT.md".to_string(),
+        "memory/**/*.md".to_string(),
+    ]
+}
 fn default_index_paths() -> Vec<MemoryIndexPath> {
     vec![MemoryIndexPath {
         path: "knowledge".to_string(),
@@ -495,6 +779,9 @@ fn default_port() -> u16 {
 fn default_bind() -> String {
     "127.0.0.1".to_string()
 }
+fn default_rate_limit_per_minute() -> u32 {
+    600
+}
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -541,12 +828,23 @@ impl Default for ToolsConfig {
     fn default() -> Self {
         Self {
             bash_timeout_ms: default_bash_timeout(),
+            bash_max_output_bytes: default_bash_max_output_bytes(),
+            shell: default_shell(),
+            shell_flag: default_shell_flag(),
+            read_file_max_bytes: default_read_file_max_bytes(),
+            hard_delete: false,
             web_fetch_max_bytes: default_web_fetch_max_bytes(),
+            web_fetch_max_download_bytes: default_web_fetch_max_download_bytes(),
+            web_fetch_allowed_types: default_web_fetch_allowed_types(),
             require_approval: Vec::new(),
             tool_output_max_chars: default_tool_output_max_chars(),
             log_injection_warnings: default_true(),
             use_content_delimiters: default_true(),
+            log_redaction_exempt: default_log_redaction_exempt(),
             web_search: None,
+            record_path: None,
+            replay_path: None,
+            record_redact: default_true(),
         }
     }
 }
@@ -569,8 +867,15 @@ impl Default for MemoryConfig {
             embedding_provider: default_embedding_provider(),
             embedding_model: default_embedding_model(),
             embedding_cache_dir: default_embedding_cache_dir(),
+            embedding_batch_size: default_embedding_batch_size(),
+            embedding_concurrency: default_embedding_concurrency(),
+            embedding_retries: default_embedding_retries(),
+            embedding_retry_backoff_ms: default_embedding_retry_backoff_ms(),
+            embedding_failure_threshold: default_embedding_failure_threshold(),
+            embedding_cooldown_secs: default_embedding_cooldown_secs(),
             chunk_size: default_chunk_size(),
             chunk_overlap: default_chunk_overlap(),
+            roots: default_memory_roots(),
             paths: default_index_paths(),
             session_max_messages: default_session_max_messages(),
             session_max_chars: 0, // 0 = unlimited (preserve full content like OpenClaw)
@@ -584,6 +889,7 @@ impl Default for ServerConfig {
             enabled: default_true(),
             port: default_port(),
             bind: default_bind(),
+            rate_limit_per_minute: default_rate_limit_per_minute(),
         }
     }
 }
@@ -615,12 +921,23 @@ impl Config {
         }
 
         let content = fs::read_to_string(&path)?;
-        let mut config: Config = toml::from_str(&content)?;
+        let mut config = Self::parse(&content, true)?;
         config.paths = paths;
 
         // Expand environment variables in API keys
         config.expand_env_vars();
 
+        // Apply explicit state_dir if set and LOCALGPT_STATE_DIR not set
+        if let Some(ref state_dir) = config.state_dir
+            && std::env::var("LOCALGPT_STATE_DIR").is_err()
+        {
+            let expanded = shellexpand::tilde(state_dir);
+            config
+                .paths
+                .set_state_dir(PathBuf::from(expanded.to_string()))
+                .context("Invalid state_dir in config")?;
+        }
+
         // Apply deprecated memory.workspace override if set and LOCALGPT_WORKSPACE not set
         if config.memory.workspace != default_workspace()
             && std::env::var("LOCALGPT_WORKSPACE").is_err()
@@ -635,6 +952,41 @@ impl Config {
         Ok(config)
     }
 
+    /// Load the config file as written, without applying any profile.
+    ///
+    /// Use this when the config will be saved back, so profile overrides
+    /// aren't baked into the base config.
+    pub fn load_base() -> Result<Self> {
+        let paths = Paths::resolve()?;
+        let path = paths.config_file();
+        if !path.exists() {
+            return Self::load();
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let mut config = Self::parse(&content, false)?;
+        config.paths = paths;
+        Ok(config)
+    }
+
+    /// Parse config TOML, merging in the selected profile if `apply_profile`.
+    fn parse(content: &str, apply_profile: bool) -> Result<Self> {
+        let mut table: toml::Table = toml::from_str(content)?;
+
+        let active = if apply_profile {
+            profile::selected_profile(&table)
+        } else {
+            None
+        };
+        if let Some(ref name) = active {
+            profile::apply_profile(&mut table, name)?;
+        }
+
+        let mut config: Config = toml::Value::Table(table).try_into()?;
+        config.active_profile = active;
+        Ok(config)
+    }
+
     /// Load (or create default) config with all directories rooted under `data_dir`.
     ///
     /// Mobile apps use this instead of `load()` since they don't have XDG dirs.
@@ -653,7 +1005,7 @@ impl Config {
         }
 
         let content = fs::read_to_string(&path)?;
-        let mut config: Config = toml::from_str(&content)?;
+        let mut config = Self::parse(&content, true)?;
         config.paths = paths;
         config.expand_env_vars();
         Ok(config)
@@ -723,6 +1075,8 @@ impl Config {
             ["server", "port"] => Ok(self.server.port.to_string()),
             ["server", "bind"] => Ok(self.server.bind.clone()),
             ["memory", "workspace"] => Ok(self.memory.workspace.clone()),
+            ["state_dir"] => Ok(self.state_dir.clone().unwrap_or_default()),
+            ["profile"] => Ok(self.profile.clone().unwrap_or_default()),
             ["logging", "level"] => Ok(self.logging.level.clone()),
             _ => anyhow::bail!("Unknown config key: {}", key),
         }
@@ -741,6 +1095,13 @@ impl Config {
             ["server", "port"] => self.server.port = value.parse()?,
             ["server", "bind"] => self.server.bind = value.to_string(),
             ["memory", "workspace"] => self.memory.workspace = value.to_string(),
+            ["state_dir"] => self.state_dir = Some(value.to_string()),
+            ["profile"] => {
+                if !value.is_empty() && !self.profiles.contains_key(value) {
+                    anyhow::bail!("Unknown config profile '{}'", value);
+                }
+                self.profile = (!value.is_empty()).then(|| value.to_string());
+            }
             ["logging", "level"] => self.logging.level = value.to_string(),
             _ => anyhow::bail!("Unknown config key: {}", key),
         }
@@ -774,6 +1135,15 @@ fn expand_env(s: &str) -> String {
 const DEFAULT_CONFIG_TEMPLATE: &str = r#"# LocalGPT Configuration
 # Auto-created on first run. Edit as needed.
 
+# State directory for sessions, audit log and logs (must be writable).
+# Default: XDG state dir (~/.local/state/localgpt); LOCALGPT_STATE_DIR overrides.
+# state_dir = "/var/lib/localgpt"
+
+# Named profiles override any part of this file when selected with
+# `profile = "<name>"`, `--config-profile <name>` or LOCALGPT_CONFIG_PROFILE.
+# [profiles.review.tools]
+# require_approval = ["bash", "write_file", "edit_file"]
+
 [agent]
 # Default model: claude-cli/opus, anthropic/claude-sonnet-4-5, openai/gpt-4o, etc.
 default_model = "claude-cli/opus"
@@ -809,6 +1179,19 @@ interval = "30m"
 #   LOCALGPT_PROFILE=work                  - uses data_dir/workspace-work
 # workspace = "~/.local/share/localgpt/workspace"
 
+# Files that count as memory (globs relative to the workspace)
+# roots = ["MEMORY.md", "HEARTBEAT.md", "memory/**/*.md", "notes/**/*.md"]
+
+# Embedding generation during reindex
+# embedding_batch_size = 32    # Chunks per embedding request
+# embedding_concurrency = 2    # Requests in flight at once
+
+# Flaky embedding backends: retry, then fall back to keyword search
+# embedding_retries = 2                # Retries per call (backoff doubles)
+# embedding_retry_backoff_ms = 250     # Delay before the first retry
+# embedding_failure_threshold = 3      # Failed calls in a row before falling back
+# embedding_cooldown_secs = 60         # Time on keyword search before retrying
+
 # Session memory settings (for /new command)
 # session_max_messages = 15    # Max messages to save (0 = unlimited)
 # session_max_chars = 0        # Max chars per message (0 = unlimited, preserves full content)
@@ -817,6 +1200,7 @@ interval = "30m"
 enabled = true
 port = 31327
 bind = "127.0.0.1"
+# rate_limit_per_minute = 600   # Per client IP on /api/* and /health (0 = unlimited)
 
 [logging]
 level = "info"
@@ -835,6 +1219,33 @@ level = "info"
 # [sandbox.network]
 # policy = "deny"                       # deny | proxy
 
+# Security (optional)
+# [security]
+# strict_policy = false                 # abort startup on policy tamper
+# scrub_secret_env = false              # drop env vars whose values look like secrets from bash
+# env_policy = "deny"                  # inherit | deny | allow_only
+# env_deny_patterns = ["AWS_*_KEY", "*TOKEN*"]  # env var names never passed to bash (deny)
+# env_allow_patterns = ["CARGO_*", "RUST*"]     # only env vars passed besides PATH/HOME/LANG (allow_only)
+# isolate_bash_network = false          # Linux: run bash in an empty network namespace
+# bash_run_as_uid = 990                 # Unix: run bash as this user (needs root/CAP_SETUID)
+# bash_run_as_gid = 990                 # group for bash_run_as_uid (default: same as uid)
+# confirm_bash_risk = "high"            # only ask approval for bash commands at/above this risk
+# generic_path_errors = true            # hide denied paths from the model (default: strict_policy)
+# audit_tool_mutations = true           # audit successful writes/edits/bash too (default: strict_policy)
+# audit_tool_invocations = true         # audit every tool call and its outcome (default: strict_policy)
+# high_risk_patterns = ["terraform\\s+destroy"]  # extra regexes treated as high risk
+# secret_patterns = [["Acme Token", "acme_live_[A-Za-z0-9]{24}"]]  # extra secret formats to redact
+
+# Shell for the bash tool (optional)
+# [tools]
+# shell = "bash"                  # name on PATH or absolute path, e.g. "sh", "/bin/zsh"
+# shell_flag = "-c"
+# bash_max_output_bytes = 1048576 # kill unsandboxed commands past this much output (0 = unlimited)
+# read_file_max_bytes = 67108864  # read_file errors instead of reading past this (0 = unlimited)
+# hard_delete = false             # delete_file unlinks instead of moving to <state_dir>/.localgpt_trash/ (kept 30 days)
+# web_fetch_max_download_bytes = 10485760   # refuse larger Content-Length up front
+# web_fetch_allowed_types = ["text/*", "application/json", "application/*+json", "application/xml", "application/*+xml", "application/javascript"]
+
 # Web search (optional)
 # [tools.web_search]
 # provider = "searxng"            # searxng | brave | none
@@ -0,0 +1,165 @@
+//! Named config profiles.
+//!
+//! A profile is a partial config under `[profiles.<name>]` that is merged
+//! over the base config when selected:
+//!
+//! ```toml
+//! profile = "review"            # default profile (optional)
+//!
+//! [tools]
+//! require_approval = []
+//!
+//! [profiles.review.tools]
+//! require_approval = ["bash", "write_file", "edit_file"]
+//!
+//! [profiles.review.security]
+//! confirm_bash_risk = "medium"
+//! ```
+//!
+//! `LOCALGPT_CONFIG_PROFILE` (or `--config-profile`) takes precedence over
+//! `profile`. This is separate from `LOCALGPT_PROFILE`, which selects a
+//! workspace directory.
+
+use anyhow::Result;
+use toml::{Table, Value};
+
+/// Environment variable selecting the active profile.
+pub const PROFILE_ENV: &str = "LOCALGPT_CONFIG_PROFILE";
+
+/// Keys a profile may not override (profiles can't select or redefine profiles).
+const PROFILE_KEYS: &[&str] = &["profile", "profiles"];
+
+/// Name of the profile to activate: `LOCALGPT_CONFIG_PROFILE`, else the config's
+/// top-level `profile` key.
+pub fn selected_profile(table: &Table) -> Option<String> {
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .filter(|p| !p.is_empty())
+        .or_else(|| table.get("profile")?.as_str().map(str::to_string))
+}
+
+/// Merge profile `name` over the base config `table` in place.
+///
+/// Nested tables merge key by key; any other value (including arrays)
+/// replaces the base value outright.
+pub fn apply_profile(table: &mut Table, name: &str) -> Result<()> {
+    let Some(profile) = table.get("profiles").and_then(|p| p.get(name)).cloned() else {
+        let mut known: Vec<&String> = table
+            .get("profiles")
+            .and_then(Value::as_table)
+            .map(|p| p.keys().collect())
+            .unwrap_or_default();
+        known.sort();
+        anyhow::bail!(
+            "Unknown config profile '{}' (available: {})",
+            name,
+            if known.is_empty() {
+                "none".to_string()
+            } else {
+                known
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        );
+    };
+
+    let Value::Table(mut overlay) = profile else {
+        anyhow::bail!("Config profile '{}' must be a table", name);
+    };
+    for key in PROFILE_KEYS {
+        overlay.remove(*key);
+    }
+
+    merge_tables(table, overlay);
+    Ok(())
+}
+
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+profile = "dev"
+
+[agent]
+default_model = "claude-cli/opus"
+context_window = 128000
+
+[tools]
+require_approval = []
+
+[profiles.review.tools]
+require_approval = ["bash", "write_file"]
+
+[profiles.review.agent]
+default_model = "openai/gpt-4o"
+
+[profiles.dev.tools]
+bash_timeout_ms = 5000
+"#;
+
+    #[test]
+    fn test_profile_overrides_nested_keys() {
+        let mut table: Table = toml::from_str(CONFIG).unwrap();
+        apply_profile(&mut table, "review").unwrap();
+
+        let agent = table["agent"].as_table().unwrap();
+        assert_eq!(agent["default_model"].as_str(), Some("openai/gpt-4o"));
+        // Untouched sibling keys survive the merge
+        assert_eq!(agent["context_window"].as_integer(), Some(128000));
+
+        let approval = table["tools"]["require_approval"].as_array().unwrap();
+        assert_eq!(approval.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_profile_lists_available() {
+        let mut table: Table = toml::from_str(CONFIG).unwrap();
+        let err = apply_profile(&mut table, "prod").unwrap_err().to_string();
+        assert!(err.contains("prod"));
+        assert!(err.contains("dev, review"));
+    }
+
+    #[test]
+    fn test_profile_cannot_redefine_profiles() {
+        let mut table: Table = toml::from_str(
+            r#"
+[profiles.a]
+profile = "b"
+[profiles.a.profiles.b.tools]
+bash_timeout_ms = 1
+"#,
+        )
+        .unwrap();
+        apply_profile(&mut table, "a").unwrap();
+
+        assert!(table.get("profile").is_none());
+        assert!(table["profiles"].get("b").is_none());
+    }
+
+    #[test]
+    fn test_config_deserializes_with_profile() {
+        let mut table: Table = toml::from_str(CONFIG).unwrap();
+        apply_profile(&mut table, "dev").unwrap();
+
+        let config: crate::config::Config = Value::Table(table).try_into().unwrap();
+        assert_eq!(config.tools.bash_timeout_ms, 5000);
+        assert_eq!(config.profile.as_deref(), Some("dev"));
+        assert!(config.profiles.contains_key("review"));
+    }
+}
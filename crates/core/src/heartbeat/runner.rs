@@ -11,6 +11,7 @@ use tracing::{debug, info, warn};
 use super::events::{HeartbeatEvent, HeartbeatStatus, emit_heartbeat_event, now_ms};
 use crate::agent::{
     Agent, AgentConfig, HEARTBEAT_OK_TOKEN, SessionStore, build_heartbeat_prompt, is_heartbeat_ok,
+    truncate_at_char_boundary,
 };
 use crate::concurrency::{TurnGate, WorkspaceLock};
 use crate::config::{Config, parse_duration, parse_time};
@@ -265,7 +266,7 @@ impl HeartbeatRunner {
             {
                 debug!(
                     "Skipping duplicate heartbeat (same text within 24h): {}",
-                    &response[..response.len().min(100)]
+                    truncate_at_char_boundary(&response, 100)
                 );
                 return Ok((response, HeartbeatStatus::Skipped));
             }
@@ -0,0 +1,136 @@
+//! BM25 ranking for memory search without embeddings.
+//!
+//! Per-chunk term frequencies and per-term document frequencies are stored
+//! by `MemoryIndex` at index time. This module holds the tokenizer both
+//! sides share and the scoring formula. Unlike the FTS query, which needs
+//! every term to appear, BM25 ranks chunks matching any term, weighting
+//! rare terms higher, and light suffix stripping lets "deploying" match
+//! "deployed".
+
+use std::collections::HashMap;
+
+/// Term frequency saturation.
+const K1: f64 = 1.2;
+/// Document length normalization.
+const B: f64 = 0.75;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "he",
+    "her", "his", "i", "if", "in", "into", "is", "it", "its", "me", "my", "of", "on", "or", "our",
+    "she", "so", "that", "the", "their", "them", "then", "there", "these", "they", "this", "to",
+    "was", "we", "were", "what", "when", "where", "which", "who", "will", "with", "you", "your",
+];
+
+/// Split text into lowercase, stemmed terms with stopwords removed.
+pub fn tokenize(text: &str) -> Vec<String> {
+    tokenize_with_offsets(text)
+        .into_iter()
+        .map(|(term, _, _)| term)
+        .collect()
+}
+
+/// Like [`tokenize`], with the byte range of each term's source word.
+pub fn tokenize_with_offsets(text: &str) -> Vec<(String, usize, usize)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut terms = Vec::new();
+    let mut start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (is_word(c), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                start = None;
+                let word = text[s..i].to_lowercase();
+                if !STOPWORDS.contains(&word.as_str()) {
+                    terms.push((stem(&word), s, i));
+                }
+            }
+            _ => {}
+        }
+    }
+    terms
+}
+
+/// Term frequencies for a chunk, and its length in terms.
+pub fn term_frequencies(text: &str) -> (HashMap<String, u32>, u32) {
+    let terms = tokenize(text);
+    let length = terms.len() as u32;
+    let mut freqs = HashMap::new();
+    for term in terms {
+        *freqs.entry(term).or_insert(0) += 1;
+    }
+    (freqs, length)
+}
+
+/// Inverse document frequency of a term found in `df` of `n_docs` chunks.
+pub fn idf(n_docs: u64, df: u64) -> f64 {
+    let (n, df) = (n_docs as f64, df as f64);
+    ((n - df + 0.5) / (df + 0.5) + 1.0).ln()
+}
+
+/// One term's contribution to a chunk's score.
+pub fn term_score(idf: f64, tf: f64, doc_len: f64, avg_len: f64) -> f64 {
+    let norm = if avg_len > 0.0 {
+        1.0 - B + B * doc_len / avg_len
+    } else {
+        1.0
+    };
+    idf * tf * (K1 + 1.0) / (tf + K1 * norm)
+}
+
+/// Strip common English inflections. Not a real stemmer, but applied to
+/// both documents and queries, so it only has to be consistent.
+fn stem(word: &str) -> String {
+    let n = word.chars().count();
+    if n > 4 && word.ends_with("ies") {
+        return format!("{}y", &word[..word.len() - 3]);
+    }
+    for suffix in ["ing", "ed"] {
+        if n > suffix.len() + 3 && word.ends_with(suffix) {
+            return word[..word.len() - suffix.len()].to_string();
+        }
+    }
+    if n > 3 && word.ends_with('s') && !word.ends_with("ss") {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("The Deploying of deployed services, and queries"),
+            vec!["deploy", "deploy", "service", "query"]
+        );
+        assert_eq!(tokenize("class is_open"), vec!["class", "is_open"]);
+    }
+
+    #[test]
+    fn test_tokenize_with_offsets() {
+        let text = "Über the Queries!";
+        assert_eq!(
+            tokenize_with_offsets(text),
+            vec![("über".to_string(), 0, 5), ("query".to_string(), 10, 17)]
+        );
+        assert_eq!(&text[10..17], "Queries");
+    }
+
+    #[test]
+    fn test_rare_terms_score_higher() {
+        let common = idf(100, 90);
+        let rare = idf(100, 2);
+        assert!(rare > common);
+        assert!(common > 0.0);
+
+        // Shorter chunks with the same tf rank higher
+        let short = term_score(rare, 1.0, 10.0, 50.0);
+        let long = term_score(rare, 1.0, 200.0, 50.0);
+        assert!(short > long);
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{Result, anyhow};
 use rusqlite::{Connection, params};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
@@ -8,6 +9,7 @@ use std::time::Duration;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use super::bm25;
 use super::embeddings::{cosine_similarity, deserialize_embedding, serialize_embedding};
 use super::search::MemoryChunk;
 
@@ -24,10 +26,17 @@ pub struct MemoryIndex {
     chunk_overlap: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ReindexStats {
     pub files_processed: usize,
     pub files_updated: usize,
+    /// Files dropped from the index because they no longer exist
+    pub files_removed: usize,
+    /// Chunks written for new or changed files
+    pub chunks_added: usize,
+    /// Chunks deleted for changed or removed files
+    pub chunks_removed: usize,
+    /// Total chunks in the index afterwards
     pub chunks_indexed: usize,
     pub duration: Duration,
 }
@@ -93,8 +102,25 @@ impl MemoryIndex {
                 PRIMARY KEY (provider, model, provider_key, hash)
             );
 
+            -- BM25 term statistics, for ranked search without embeddings
+            CREATE TABLE IF NOT EXISTS chunk_terms (
+                term TEXT NOT NULL,
+                chunk_id TEXT NOT NULL,
+                tf INTEGER NOT NULL,
+                PRIMARY KEY (term, chunk_id)
+            );
+            CREATE TABLE IF NOT EXISTS chunk_lengths (
+                chunk_id TEXT PRIMARY KEY,
+                length INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS term_df (
+                term TEXT PRIMARY KEY,
+                df INTEGER NOT NULL
+            );
+
             -- Indexes
             CREATE INDEX IF NOT EXISTS idx_chunks_path ON chunks(path);
+            CREATE INDEX IF NOT EXISTS idx_chunk_terms_chunk ON chunk_terms(chunk_id);
             CREATE INDEX IF NOT EXISTS idx_chunks_source ON chunks(source);
             CREATE INDEX IF NOT EXISTS idx_embedding_cache_updated_at ON embedding_cache(updated_at);
             "#,
@@ -103,6 +129,9 @@ impl MemoryIndex {
         // Create FTS5 table (OpenClaw-compatible with UNINDEXED columns)
         Self::ensure_fts_table(&conn)?;
 
+        // Chunks indexed before term statistics existed (or migrated above)
+        Self::backfill_term_stats(&conn)?;
+
         // Ensure source column exists on older tables
         Self::ensure_column(&conn, "files", "source", "TEXT NOT NULL DEFAULT 'memory'")?;
         Self::ensure_column(&conn, "chunks", "source", "TEXT NOT NULL DEFAULT 'memory'")?;
@@ -185,10 +214,14 @@ impl MemoryIndex {
         Self::new_with_db_path(workspace, &db_path)
     }
 
-    /// Index a file, returning true if it was updated
+    /// Index a file, returning true if it was updated.
+    ///
+    /// Unless `force`, a file whose mtime and size match the index is
+    /// skipped without being read, and one whose content hash matches is
+    /// skipped after only refreshing its mtime. mtimes have one-second
+    /// resolution, so a file modified in the last two seconds is always
+    /// hashed.
     pub fn index_file(&self, path: &Path, force: bool) -> Result<bool> {
-        let content = fs::read_to_string(path)?;
-        let file_hash = hash_content(&content);
         let metadata = fs::metadata(path)?;
         let mtime = metadata
             .modified()?
@@ -202,33 +235,54 @@ impl MemoryIndex {
             .to_string_lossy()
             .to_string();
 
+        let existing: Option<(String, i64, i64)> = if force {
+            None
+        } else {
+            let conn = self
+                .conn
+                .lock()
+                .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+            conn.query_row(
+                "SELECT hash, mtime, size FROM files WHERE path = ?1",
+                params![&relative_path],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .ok()
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() as i64;
+        if let Some((_, old_mtime, old_size)) = &existing
+            && *old_mtime == mtime
+            && *old_size == size
+            && mtime < now - 1
+        {
+            debug!("File unchanged (mtime, size), skipping: {}", relative_path);
+            return Ok(false);
+        }
+
+        let content = fs::read_to_string(path)?;
+        let file_hash = hash_content(&content);
+
         let conn = self
             .conn
             .lock()
             .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
 
         // Check if file has changed
-        if !force {
-            let existing: Option<String> = conn
-                .query_row(
-                    "SELECT hash FROM files WHERE path = ?1",
-                    params![&relative_path],
-                    |row| row.get(0),
-                )
-                .ok();
-
-            if existing.as_deref() == Some(&file_hash) {
-                debug!("File unchanged, skipping: {}", relative_path);
-                return Ok(false);
-            }
+        if let Some((old_hash, _, _)) = &existing
+            && *old_hash == file_hash
+        {
+            debug!("File unchanged, skipping: {}", relative_path);
+            conn.execute(
+                "UPDATE files SET mtime = ?2, size = ?3 WHERE path = ?1",
+                params![&relative_path, mtime, size],
+            )?;
+            return Ok(false);
         }
 
         debug!("Indexing file: {}", relative_path);
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)?
-            .as_secs() as i64;
-
         // Update file record (OpenClaw-compatible columns)
         conn.execute(
             "INSERT OR REPLACE INTO files (path, source, hash, mtime, size) VALUES (?1, 'memory', ?2, ?3, ?4)",
@@ -262,6 +316,7 @@ impl MemoryIndex {
                 chunk.line_end,
                 &chunk.content,
             )?;
+            Self::insert_term_stats(&conn, &chunk_id, &chunk.content)?;
         }
 
         Ok(true)
@@ -278,7 +333,9 @@ impl MemoryIndex {
 
         for chunk_id in chunk_ids {
             let _ = conn.execute("DELETE FROM chunks_fts WHERE id = ?1", params![&chunk_id]);
+            Self::delete_term_stats(conn, &chunk_id)?;
         }
+        conn.execute("DELETE FROM term_df WHERE df <= 0", [])?;
 
         // Delete chunks
         conn.execute("DELETE FROM chunks WHERE path = ?1", params![path])?;
@@ -335,6 +392,154 @@ impl MemoryIndex {
         Ok(())
     }
 
+    /// Record a chunk's term frequencies and length, and count it toward
+    /// the document frequency of each of its terms
+    fn insert_term_stats(conn: &Connection, chunk_id: &str, text: &str) -> Result<()> {
+        let (freqs, length) = bm25::term_frequencies(text);
+        conn.execute(
+            "INSERT OR REPLACE INTO chunk_lengths (chunk_id, length) VALUES (?1, ?2)",
+            params![chunk_id, length],
+        )?;
+        for (term, tf) in freqs {
+            conn.execute(
+                "INSERT INTO chunk_terms (term, chunk_id, tf) VALUES (?1, ?2, ?3)",
+                params![&term, chunk_id, tf],
+            )?;
+            conn.execute(
+                "INSERT INTO term_df (term, df) VALUES (?1, 1) ON CONFLICT(term) DO UPDATE SET df = df + 1",
+                params![&term],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Undo `insert_term_stats` for a chunk. Terms whose document frequency
+    /// drops to zero are removed by the caller.
+    fn delete_term_stats(conn: &Connection, chunk_id: &str) -> Result<()> {
+        conn.execute(
+            "UPDATE term_df SET df = df - 1 WHERE term IN (SELECT term FROM chunk_terms WHERE chunk_id = ?1)",
+            params![chunk_id],
+        )?;
+        conn.execute(
+            "DELETE FROM chunk_terms WHERE chunk_id = ?1",
+            params![chunk_id],
+        )?;
+        conn.execute(
+            "DELETE FROM chunk_lengths WHERE chunk_id = ?1",
+            params![chunk_id],
+        )?;
+        Ok(())
+    }
+
+    /// Compute term statistics for chunks that don't have them yet
+    fn backfill_term_stats(conn: &Connection) -> Result<()> {
+        let mut stmt = conn.prepare(
+            "SELECT id, text FROM chunks WHERE id NOT IN (SELECT chunk_id FROM chunk_lengths)",
+        )?;
+        let missing: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Computing search term statistics for {} chunks",
+            missing.len()
+        );
+        conn.execute("BEGIN TRANSACTION", [])?;
+        for (chunk_id, text) in &missing {
+            Self::insert_term_stats(conn, chunk_id, text)?;
+        }
+        conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Search using BM25 over the stored term statistics. Any query term
+    /// can match; chunks are ranked by how many rare terms they contain.
+    pub fn search_bm25(&self, query: &str, limit: usize) -> Result<Vec<MemoryChunk>> {
+        let mut terms = bm25::tokenize(query);
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+
+        let (n_docs, avg_len): (i64, f64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(AVG(length), 0) FROM chunk_lengths",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if n_docs == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut df_stmt = conn.prepare("SELECT df FROM term_df WHERE term = ?1")?;
+        let mut postings_stmt = conn.prepare(
+            r#"
+            SELECT t.chunk_id, t.tf, l.length
+            FROM chunk_terms t JOIN chunk_lengths l ON l.chunk_id = t.chunk_id
+            WHERE t.term = ?1
+            "#,
+        )?;
+
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for term in &terms {
+            let df: i64 = df_stmt
+                .query_row(params![term], |row| row.get(0))
+                .ok()
+                .unwrap_or(0);
+            if df <= 0 {
+                continue;
+            }
+            let idf = bm25::idf(n_docs as u64, df as u64);
+
+            let postings = postings_stmt.query_map(params![term], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+            for posting in postings {
+                let (chunk_id, tf, length) = posting?;
+                *scores.entry(chunk_id).or_default() +=
+                    bm25::term_score(idf, tf as f64, length as f64, avg_len);
+            }
+        }
+
+        let mut ranked: Vec<(String, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+
+        let mut chunk_stmt =
+            conn.prepare("SELECT path, start_line, end_line, text FROM chunks WHERE id = ?1")?;
+        let mut results = Vec::with_capacity(ranked.len());
+        for (chunk_id, score) in ranked {
+            let chunk = chunk_stmt.query_row(params![&chunk_id], |row| {
+                Ok(MemoryChunk {
+                    file: row.get(0)?,
+                    line_start: row.get(1)?,
+                    line_end: row.get(2)?,
+                    content: row.get(3)?,
+                    score,
+                    highlight: None,
+                })
+            });
+            if let Ok(chunk) = chunk {
+                results.push(chunk);
+            }
+        }
+
+        Ok(results)
+    }
+
     /// Search using FTS5
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryChunk>> {
         let fts_query = match build_fts_query(query) {
@@ -365,6 +570,7 @@ impl MemoryIndex {
                 line_end: row.get(2)?,
                 content: row.get(3)?,
                 score: row.get::<_, f64>(4)?.abs(), // BM25 returns negative scores
+                highlight: None,
             })
         })?;
 
@@ -639,6 +845,20 @@ impl MemoryIndex {
         Ok(results)
     }
 
+    /// Count chunks that still need an embedding
+    pub fn pending_embedding_count(&self) -> Result<usize> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow!("Lock poisoned: {}", e))?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM chunks WHERE embedding = '' OR embedding IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
     /// Store embedding for a chunk (OpenClaw-compatible: id is TEXT, model column)
     pub fn store_embedding(&self, chunk_id: &str, embedding: &[f32], model: &str) -> Result<()> {
         let conn = self
@@ -784,6 +1004,7 @@ impl MemoryIndex {
                 line_end: row.get(2)?,
                 content: row.get(3)?,
                 score: row.get(4)?,
+                highlight: None,
             })
         })?;
 
@@ -836,6 +1057,7 @@ impl MemoryIndex {
                         line_end: end_line,
                         content: text,
                         score: similarity as f64,
+                        highlight: None,
                     },
                 ));
             }
@@ -852,7 +1074,8 @@ impl MemoryIndex {
             .collect())
     }
 
-    /// Hybrid search: combine FTS and vector results
+    /// Hybrid search: combine FTS and vector results. A side with zero
+    /// weight isn't queried, so it can't pad the results with zero scores.
     pub fn search_hybrid(
         &self,
         query: &str,
@@ -863,10 +1086,16 @@ impl MemoryIndex {
         vector_weight: f32,
     ) -> Result<Vec<MemoryChunk>> {
         // Get FTS results
-        let fts_results = self.search(query, limit * 2)?;
+        let fts_results = if text_weight > 0.0 {
+            self.search(query, limit * 2)?
+        } else {
+            Vec::new()
+        };
 
         // Get vector results if embedding provided
-        let vector_results = if let Some(embedding) = query_embedding {
+        let vector_results = if vector_weight > 0.0
+            && let Some(embedding) = query_embedding
+        {
             self.search_vector(embedding, model, limit * 2)?
         } else {
             Vec::new()
@@ -882,7 +1111,9 @@ impl MemoryIndex {
             let key = format!("{}:{}:{}", result.file, result.line_start, result.line_end);
             let rank_score = 1.0 / (1.0 + rank as f32); // rank 0 → 1.0, rank 1 → 0.5, rank 9 → 0.1
             let weighted_score = rank_score * text_weight;
-            merged.insert(key, (weighted_score, result));
+            let mut chunk = result;
+            chunk.score = weighted_score as f64;
+            merged.insert(key, (weighted_score, chunk));
         }
 
         // Add/merge vector results using rank-based scoring
@@ -1064,4 +1295,103 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_search_bm25() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let workspace = temp_dir.path();
+
+        let deploy = workspace.join("deploy.md");
+        let cooking = workspace.join("cooking.md");
+        fs::write(&deploy, "We deployed the staging services on Friday.")?;
+        fs::write(&cooking, "Friday dinner: pasta with tomato sauce.")?;
+
+        let index = MemoryIndex::new(workspace)?;
+        index.index_file(&deploy, false)?;
+        index.index_file(&cooking, false)?;
+
+        // FTS needs every term; BM25 matches any and stems "deploying"
+        assert!(index.search("deploying services rollback", 10)?.is_empty());
+        let results = index.search_bm25("deploying services rollback", 10)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file, "deploy.md");
+
+        // The rarer term outweighs the shared one
+        let results = index.search_bm25("friday pasta", 10)?;
+        assert_eq!(results[0].file, "cooking.md");
+
+        // Reindexing a file replaces its statistics
+        fs::write(&deploy, "Nothing about releases here.")?;
+        index.index_file(&deploy, false)?;
+        assert!(index.search_bm25("deploying", 10)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_file_detects_same_size_rewrite() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let workspace = temp_dir.path();
+
+        let notes = workspace.join("notes.md");
+        fs::write(&notes, "alpha beta")?;
+
+        let index = MemoryIndex::new(workspace)?;
+        assert!(index.index_file(&notes, false)?);
+        assert!(!index.index_file(&notes, false)?);
+
+        // Same size, likely the same mtime second: still re-chunked
+        fs::write(&notes, "gamma beta")?;
+        assert!(index.index_file(&notes, false)?);
+        assert_eq!(index.search_bm25("gamma", 10)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_hybrid_skips_zero_weight_side() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let workspace = temp_dir.path();
+
+        let notes = workspace.join("notes.md");
+        fs::write(&notes, "The release train leaves on Tuesday.")?;
+
+        let index = MemoryIndex::new(workspace)?;
+        index.index_file(&notes, false)?;
+
+        let keyword_only = index.search_hybrid("release", None, "test", 5, 1.0, 0.0)?;
+        assert_eq!(keyword_only.len(), 1);
+        assert!((keyword_only[0].score - 1.0).abs() < 1e-6);
+
+        let no_keywords = index.search_hybrid("release", None, "test", 5, 0.0, 1.0)?;
+        assert!(no_keywords.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pending_embedding_count() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let workspace = temp_dir.path();
+
+        let test_file = workspace.join("notes.md");
+        fs::write(
+            &test_file,
+            "# Notes
+
+Something worth embedding.",
+        )?;
+
+        let index = MemoryIndex::new(workspace)?;
+        index.index_file(&test_file, false)?;
+
+        let total = index.chunk_count()?;
+        assert_eq!(index.pending_embedding_count()?, total);
+
+        let (chunk_id, _) = index.chunks_without_embeddings(1)?.remove(0);
+        index.store_embedding(&chunk_id, &[0.1, 0.2, 0.3], "test-model")?;
+        assert_eq!(index.pending_embedding_count()?, total - 1);
+
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
+mod bm25;
 mod embeddings;
 mod index;
+mod resilient;
+mod roots;
 mod search;
 mod watcher;
 mod workspace;
@@ -10,16 +13,21 @@ pub use embeddings::FastEmbedProvider;
 pub use embeddings::LlamaCppProvider;
 pub use embeddings::{EmbeddingProvider, OpenAIEmbeddingProvider, hash_text};
 pub use index::{MemoryIndex, ReindexStats};
-pub use search::MemoryChunk;
+pub use resilient::{ResilientEmbeddingProvider, RetryPolicy};
+pub use roots::MemoryRoots;
+pub use search::{
+    DEFAULT_HYBRID_ALPHA, MemoryChunk, MergedChunk, SearchOptions, SearchOutcome,
+    merge_overlapping_chunks,
+};
 pub use watcher::MemoryWatcher;
 pub use workspace::{init_state_dir, init_workspace};
 
 use anyhow::Result;
 use chrono::Local;
+use futures::StreamExt;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
 use tokio::runtime::Handle;
 use tracing::{debug, info, warn};
 
@@ -32,7 +40,7 @@ pub struct MemoryManager {
     index: MemoryIndex,
     config: MemoryConfig,
     /// Optional embedding provider for semantic search
-    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+    embedding_provider: Option<Arc<ResilientEmbeddingProvider>>,
     /// True if this was a brand new workspace (first run)
     is_brand_new: bool,
 }
@@ -53,6 +61,19 @@ pub struct FileStats {
     pub lines: usize,
 }
 
+/// Progress of an embedding run, reported after each batch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddingProgress {
+    /// Chunks that needed an embedding when the run started
+    pub total: usize,
+    /// Chunks picked up so far
+    pub processed: usize,
+    /// Chunks that now have an embedding
+    pub embedded: usize,
+    /// Embeddings reused from the cache instead of generated
+    pub cache_hits: usize,
+}
+
 #[derive(Debug)]
 pub struct RecentEntry {
     pub timestamp: String,
@@ -125,7 +146,7 @@ impl MemoryManager {
                         }
                         Err(e) => {
                             warn!(
-                                "Failed to initialize local embeddings: {}. Falling back to FTS-only search.",
+                                "Failed to initialize local embeddings: {}. Falling back to keyword search.",
                                 e
                             );
                             None
@@ -135,7 +156,7 @@ impl MemoryManager {
                 #[cfg(not(feature = "embeddings-local"))]
                 {
                     warn!(
-                        "Local embeddings requested but `embeddings-local` feature is disabled. Falling back to FTS-only search."
+                        "Local embeddings requested but `embeddings-local` feature is disabled. Falling back to keyword search."
                     );
                     None
                 }
@@ -155,7 +176,7 @@ impl MemoryManager {
                             }
                             Err(e) => {
                                 warn!(
-                                    "Failed to initialize OpenAI embeddings: {}. Falling back to FTS-only search.",
+                                    "Failed to initialize OpenAI embeddings: {}. Falling back to keyword search.",
                                     e
                                 );
                                 None
@@ -163,13 +184,13 @@ impl MemoryManager {
                         }
                     } else {
                         warn!(
-                            "OpenAI embedding provider requested but no OpenAI config found. Falling back to FTS-only search."
+                            "OpenAI embedding provider requested but no OpenAI config found. Falling back to keyword search."
                         );
                         None
                     }
                 } else {
                     warn!(
-                        "OpenAI embedding provider requested but no app config provided. Falling back to FTS-only search."
+                        "OpenAI embedding provider requested but no app config provided. Falling back to keyword search."
                     );
                     None
                 }
@@ -188,7 +209,7 @@ impl MemoryManager {
                     }
                     Err(e) => {
                         warn!(
-                            "Failed to initialize GGUF embeddings: {}. Falling back to FTS-only search.",
+                            "Failed to initialize GGUF embeddings: {}. Falling back to keyword search.",
                             e
                         );
                         None
@@ -198,23 +219,27 @@ impl MemoryManager {
             #[cfg(not(feature = "gguf"))]
             "gguf" => {
                 warn!(
-                    "GGUF embedding provider requested but 'gguf' feature is not enabled. Build with --features gguf. Falling back to FTS-only search."
+                    "GGUF embedding provider requested but 'gguf' feature is not enabled. Build with --features gguf. Falling back to keyword search."
                 );
                 None
             }
             "none" => {
-                debug!("Embeddings disabled, using FTS-only search");
+                debug!("Embeddings disabled, using keyword search");
                 None
             }
             other => {
                 warn!(
-                    "Unknown embedding provider '{}'. Falling back to FTS-only search.",
+                    "Unknown embedding provider '{}'. Falling back to keyword search.",
                     other
                 );
                 None
             }
         };
 
+        let policy = RetryPolicy::from_config(memory_config);
+        let embedding_provider =
+            embedding_provider.map(|p| Arc::new(ResilientEmbeddingProvider::new(p, policy)));
+
         Ok(Self {
             workspace,
             db_path,
@@ -227,19 +252,35 @@ impl MemoryManager {
 
     /// Set embedding provider for semantic search (requires OpenAI API key)
     pub fn with_embedding_provider(mut self, provider: Arc<dyn EmbeddingProvider>) -> Self {
-        self.embedding_provider = Some(provider);
+        let policy = RetryPolicy::from_config(&self.config);
+        self.embedding_provider = Some(Arc::new(ResilientEmbeddingProvider::new(provider, policy)));
         self
     }
 
-    /// Check if semantic search is available
+    /// Check if semantic search is available (false while the embedding
+    /// backend is failing and search has fallen back to keywords)
     pub fn has_embeddings(&self) -> bool {
-        self.embedding_provider.is_some()
+        self.embedding_provider
+            .as_ref()
+            .is_some_and(|p| !p.is_open())
+    }
+
+    /// Whether an embedding provider is configured but currently failing
+    pub fn embeddings_degraded(&self) -> bool {
+        self.embedding_provider
+            .as_ref()
+            .is_some_and(|p| p.is_open())
     }
 
     pub fn workspace(&self) -> &PathBuf {
         &self.workspace
     }
 
+    /// The configured memory roots for this workspace.
+    pub fn roots(&self) -> MemoryRoots {
+        MemoryRoots::new(&self.workspace, &self.config)
+    }
+
     /// Read the main MEMORY.md file
     pub fn read_memory_file(&self) -> Result<String> {
         let path = self.workspace.join("MEMORY.md");
@@ -344,10 +385,49 @@ impl MemoryManager {
         Ok(content)
     }
 
-    /// Search memory using hybrid search (FTS + semantic if available)
+    /// Search memory using hybrid search (FTS + semantic) if available,
+    /// otherwise BM25 keyword ranking
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryChunk>> {
-        // If we have an embedding provider, try hybrid search
-        if let Some(ref provider) = self.embedding_provider {
+        Ok(self
+            .search_with_opts(query, limit, &SearchOptions::default())?
+            .chunks)
+    }
+
+    /// [`search`](Self::search) with a keyword/vector balance and a score
+    /// floor. `alpha` 0.0 skips the query embedding entirely.
+    pub fn search_with_opts(
+        &self,
+        query: &str,
+        limit: usize,
+        opts: &SearchOptions,
+    ) -> Result<SearchOutcome> {
+        let alpha = opts.alpha.unwrap_or(DEFAULT_HYBRID_ALPHA);
+        if !(0.0..=1.0).contains(&alpha) {
+            anyhow::bail!("alpha must be between 0.0 and 1.0, got {}", alpha);
+        }
+
+        let (mut chunks, hybrid) = self.search_ranked(query, limit, alpha)?;
+        if let Some(min_score) = opts.min_score {
+            chunks.retain(|c| c.score >= min_score);
+        }
+        for chunk in &mut chunks {
+            chunk.highlight = search::best_match_span(&chunk.content, query);
+        }
+        Ok(SearchOutcome { chunks, hybrid })
+    }
+
+    /// Ranked results, and whether they came from hybrid search.
+    fn search_ranked(
+        &self,
+        query: &str,
+        limit: usize,
+        alpha: f32,
+    ) -> Result<(Vec<MemoryChunk>, bool)> {
+        // If we have a working embedding provider, try hybrid search
+        if alpha > 0.0
+            && let Some(ref provider) = self.embedding_provider
+            && !provider.is_open()
+        {
             // Try to get query embedding (may fail if no API key, rate limited, etc.)
             if let Ok(handle) = Handle::try_current() {
                 let provider = provider.clone();
@@ -362,21 +442,31 @@ impl MemoryManager {
                 .map_err(|_| anyhow::anyhow!("Thread panicked"))?;
 
                 if let Ok(embedding) = embedding_result {
-                    debug!("Using hybrid search with {} dimensions", embedding.len());
-                    return self.index.search_hybrid(
+                    debug!(
+                        "Using hybrid search with {} dimensions (alpha {})",
+                        embedding.len(),
+                        alpha
+                    );
+                    let results = self.index.search_hybrid(
                         query,
                         Some(&embedding),
                         &model,
                         limit,
-                        0.3, // FTS weight
-                        0.7, // Vector weight
-                    );
+                        1.0 - alpha, // FTS weight
+                        alpha,       // Vector weight
+                    )?;
+                    return Ok((results, true));
                 }
             }
         }
 
-        // Fallback to FTS-only search
-        self.index.search(query, limit)
+        // Without embeddings, rank by BM25; plain FTS catches anything the
+        // tokenizer drops (e.g. a query made only of stopwords)
+        let results = self.index.search_bm25(query, limit)?;
+        if !results.is_empty() {
+            return Ok((results, false));
+        }
+        Ok((self.index.search(query, limit)?, false))
     }
 
     /// Search memory using FTS only (faster, no API calls)
@@ -392,17 +482,12 @@ impl MemoryManager {
     /// Reindex all memory files
     pub fn reindex(&self, force: bool) -> Result<ReindexStats> {
         let start = std::time::Instant::now();
-        let mut stats = ReindexStats {
-            files_processed: 0,
-            files_updated: 0,
-            chunks_indexed: 0,
-            duration: Duration::default(),
-        };
+        let mut stats = ReindexStats::default();
 
         // First, clean up deleted files from the index
-        let files_removed = self.cleanup_deleted_files()?;
-        if files_removed > 0 {
-            info!("Removed {} deleted files from index", files_removed);
+        self.cleanup_deleted_files(&mut stats)?;
+        if stats.files_removed > 0 {
+            info!("Removed {} deleted files from index", stats.files_removed);
         }
 
         // Index all .md files recursively under workspace
@@ -413,21 +498,21 @@ impl MemoryManager {
             .filter_map(|r| r.ok())
         {
             if entry.is_file() {
-                stats.files_processed += 1;
-                if self.index.index_file(&entry, force)? {
-                    stats.files_updated += 1;
-                }
+                self.reindex_path(&entry, force, &mut stats)?;
+            }
+        }
+
+        // Memory roots outside the .md glob above (e.g. "journal/*.txt")
+        for entry in self.roots().files() {
+            if entry.extension().is_some_and(|e| e == "md") {
+                continue;
             }
+            self.reindex_path(&entry, force, &mut stats)?;
         }
 
         // Index configured external paths (outside workspace)
         for index_path in &self.config.paths {
-            let base_path = if index_path.path.starts_with('~') || index_path.path.starts_with('/')
-            {
-                PathBuf::from(shellexpand::tilde(&index_path.path).to_string())
-            } else {
-                self.workspace.join(&index_path.path)
-            };
+            let base_path = index_path.base_path(&self.workspace);
 
             // Skip paths inside workspace (already covered by recursive glob above)
             if base_path.starts_with(&self.workspace) {
@@ -448,10 +533,7 @@ impl MemoryManager {
                 .filter_map(|r| r.ok())
             {
                 if entry.is_file() {
-                    stats.files_processed += 1;
-                    if self.index.index_file(&entry, force)? {
-                        stats.files_updated += 1;
-                    }
+                    self.reindex_path(&entry, force, &mut stats)?;
                 }
             }
         }
@@ -463,21 +545,139 @@ impl MemoryManager {
         Ok(stats)
     }
 
-    /// Remove files from index that no longer exist on disk
-    fn cleanup_deleted_files(&self) -> Result<usize> {
-        let indexed_files = self.index.indexed_files()?;
-        let mut removed = 0;
+    /// Append a timestamped entry to today's `memory/YYYY-MM-DD.md`,
+    /// creating `memory/` and the file as needed, then reindex the file so
+    /// the entry is searchable in the same session. Returns the file path
+    /// and the reindex stats.
+    pub fn append_daily_entry(
+        &self,
+        content: &str,
+        tags: &[String],
+    ) -> Result<(PathBuf, ReindexStats)> {
+        use fs2::FileExt;
+        use std::io::Write;
+
+        let content = content.trim();
+        if content.is_empty() {
+            anyhow::bail!("Memory entry is empty");
+        }
+
+        let now = Local::now();
+        let memory_dir = self.workspace.join("memory");
+        fs::create_dir_all(&memory_dir)?;
+        let path = memory_dir.join(format!("{}.md", now.format("%Y-%m-%d")));
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        // Under the lock an empty file means the day's first entry, even when
+        // another writer created the file at the same moment
+        file.lock_exclusive()?;
+
+        let mut entry = String::new();
+        if file.metadata()?.len() == 0 {
+            entry.push_str(&format!("# {}\n\n", now.format("%Y-%m-%d")));
+        }
+        entry.push_str(&format_daily_entry(
+            &now.format("%H:%M:%S").to_string(),
+            content,
+            tags,
+        ));
+        file.write_all(entry.as_bytes())?;
+        drop(file);
+        debug!("Appended {} bytes to {}", entry.len(), path.display());
+
+        let stats = self.reindex_file(&path)?;
+        Ok((path, stats))
+    }
 
-        for relative_path in indexed_files {
+    /// Refresh only what changed: same as `reindex(false)`.
+    pub fn reindex_all(&self) -> Result<ReindexStats> {
+        self.reindex(false)
+    }
+
+    /// Refresh a single file: re-chunk it if its content changed, or purge
+    /// its chunks if it no longer exists. Relative paths are taken from the
+    /// workspace. Only memory files are accepted: memory roots and
+    /// `memory.paths`, after symlinks are resolved.
+    pub fn reindex_file(&self, path: &Path) -> Result<ReindexStats> {
+        let start = std::time::Instant::now();
+        let full = if path.is_relative() {
+            self.workspace.join(path)
+        } else {
+            path.to_path_buf()
+        };
+        if full
+            .components()
+            .any(|c| c == std::path::Component::ParentDir)
+        {
+            anyhow::bail!("Path must not contain '..': {}", path.display());
+        }
+        // Resolve symlinks first, so a link under a memory root can't pull
+        // an outside file into the index
+        let canonical = canonicalize_memory_path(&full)?;
+        let workspace =
+            fs::canonicalize(&self.workspace).unwrap_or_else(|_| self.workspace.clone());
+        if !MemoryRoots::new(&workspace, &self.config).contains(&canonical) {
+            anyhow::bail!("Not an indexed memory path: {}", path.display());
+        }
+        // Index entries are keyed relative to the workspace as configured
+        let full = match canonical.strip_prefix(&workspace) {
+            Ok(relative) => self.workspace.join(relative),
+            Err(_) => canonical,
+        };
+
+        let mut stats = ReindexStats::default();
+        if full.is_file() {
+            self.reindex_path(&full, false, &mut stats)?;
+        } else {
+            let relative_path = full
+                .strip_prefix(&self.workspace)
+                .unwrap_or(&full)
+                .to_string_lossy()
+                .to_string();
+            if self.index.indexed_files()?.contains(&relative_path) {
+                self.remove_indexed_file(&relative_path, &mut stats)?;
+            }
+        }
+
+        stats.chunks_indexed = self.index.chunk_count()?;
+        stats.duration = start.elapsed();
+        debug!("Reindexed {}: {:?}", full.display(), stats);
+        Ok(stats)
+    }
+
+    /// Index one file, counting it and its chunk churn in `stats`.
+    fn reindex_path(&self, path: &Path, force: bool, stats: &mut ReindexStats) -> Result<()> {
+        stats.files_processed += 1;
+        let before = self.index.file_chunk_count(path)?;
+        if self.index.index_file(path, force)? {
+            stats.files_updated += 1;
+            stats.chunks_removed += before;
+            stats.chunks_added += self.index.file_chunk_count(path)?;
+        }
+        Ok(())
+    }
+
+    /// Drop a file and its chunks from the index, counting them in `stats`.
+    fn remove_indexed_file(&self, relative_path: &str, stats: &mut ReindexStats) -> Result<()> {
+        debug!("Cleaning up deleted file: {}", relative_path);
+        stats.chunks_removed += self.index.file_chunk_count(Path::new(relative_path))?;
+        self.index.remove_file(relative_path)?;
+        stats.files_removed += 1;
+        Ok(())
+    }
+
+    /// Remove files from index that no longer exist on disk
+    fn cleanup_deleted_files(&self, stats: &mut ReindexStats) -> Result<()> {
+        for relative_path in self.index.indexed_files()? {
             let full_path = self.workspace.join(&relative_path);
             if !full_path.exists() {
-                debug!("Cleaning up deleted file: {}", relative_path);
-                self.index.remove_file(&relative_path)?;
-                removed += 1;
+                self.remove_indexed_file(&relative_path, stats)?;
             }
         }
-
-        Ok(removed)
+        Ok(())
     }
 
     /// Get memory statistics
@@ -513,12 +713,7 @@ impl MemoryManager {
 
         // Configured external paths (outside workspace)
         for index_path in &self.config.paths {
-            let base_path = if index_path.path.starts_with('~') || index_path.path.starts_with('/')
-            {
-                PathBuf::from(shellexpand::tilde(&index_path.path).to_string())
-            } else {
-                self.workspace.join(&index_path.path)
-            };
+            let base_path = index_path.base_path(&self.workspace);
 
             // Skip paths inside workspace (already covered above)
             if base_path.starts_with(&self.workspace) {
@@ -624,28 +819,64 @@ impl MemoryManager {
     /// Returns (chunks_processed, chunks_embedded)
     /// Uses embedding cache to avoid regenerating identical content
     pub async fn generate_embeddings(&self, batch_size: usize) -> Result<(usize, usize)> {
+        let progress = self
+            .generate_embeddings_with(batch_size, self.config.embedding_concurrency, |_| {})
+            .await?;
+        Ok((progress.processed, progress.embedded))
+    }
+
+    /// Generate embeddings using the configured batch size and concurrency,
+    /// calling `on_progress` after every batch.
+    pub async fn generate_embeddings_with_progress(
+        &self,
+        on_progress: impl FnMut(EmbeddingProgress),
+    ) -> Result<EmbeddingProgress> {
+        self.generate_embeddings_with(
+            self.config.embedding_batch_size,
+            self.config.embedding_concurrency,
+            on_progress,
+        )
+        .await
+    }
+
+    /// Embed pending chunks in batches of `batch_size`, with up to
+    /// `concurrency` requests in flight. Stops at the first failed round
+    /// (e.g. rate limiting) so a broken provider isn't retried in a loop.
+    async fn generate_embeddings_with(
+        &self,
+        batch_size: usize,
+        concurrency: usize,
+        mut on_progress: impl FnMut(EmbeddingProgress),
+    ) -> Result<EmbeddingProgress> {
+        let mut progress = EmbeddingProgress::default();
         let provider = match &self.embedding_provider {
-            Some(p) => p,
+            Some(p) => Arc::clone(p),
             None => {
                 debug!("No embedding provider configured, skipping embedding generation");
-                return Ok((0, 0));
+                return Ok(progress);
             }
         };
+        if provider.is_open() {
+            info!("Embedding backend unavailable, skipping embedding generation");
+            return Ok(progress);
+        }
 
+        let batch_size = batch_size.max(1);
+        let concurrency = concurrency.max(1);
         let provider_id = provider.id().to_string();
         let model = provider.model().to_string();
-        let mut total_processed = 0;
-        let mut total_embedded = 0;
-        let mut cache_hits = 0;
+        progress.total = self.index.pending_embedding_count()?;
 
         loop {
-            // Get chunks without embeddings
-            let chunks = self.index.chunks_without_embeddings(batch_size)?;
+            // Fetch enough chunks to keep every request slot busy
+            let round_size = batch_size * concurrency;
+            let chunks = self.index.chunks_without_embeddings(round_size)?;
             if chunks.is_empty() {
                 break;
             }
 
-            total_processed += chunks.len();
+            progress.processed += chunks.len();
+            let embedded_before = progress.embedded;
 
             // Separate chunks into cached and uncached
             let mut to_embed: Vec<(String, String, String)> = Vec::new(); // (id, text, hash)
@@ -660,7 +891,7 @@ impl MemoryManager {
                         .get_cached_embedding(&provider_id, &model, &text_hash)
                 {
                     from_cache.push((chunk_id.clone(), cached));
-                    cache_hits += 1;
+                    progress.cache_hits += 1;
                 } else {
                     to_embed.push((chunk_id.clone(), text.clone(), text_hash));
                 }
@@ -674,25 +905,40 @@ impl MemoryManager {
                         chunk_id, e
                     );
                 } else {
-                    total_embedded += 1;
+                    progress.embedded += 1;
                 }
             }
+            if progress.cache_hits > 0 {
+                on_progress(progress);
+            }
 
-            // Generate new embeddings for uncached chunks
-            if !to_embed.is_empty() {
-                let texts: Vec<String> = to_embed.iter().map(|(_, text, _)| text.clone()).collect();
+            // Generate new embeddings for uncached chunks, `concurrency` batches at a time
+            // Owned batches keep the request futures `Send` for any caller
+            let batches: Vec<Vec<(String, String, String)>> =
+                to_embed.chunks(batch_size).map(<[_]>::to_vec).collect();
+            let requests = batches.into_iter().map(|batch| {
+                let provider = Arc::clone(&provider);
+                async move {
+                    let texts: Vec<String> =
+                        batch.iter().map(|(_, text, _)| text.clone()).collect();
+                    (batch, provider.embed_batch(&texts).await)
+                }
+            });
+            let mut results = futures::stream::iter(requests).buffer_unordered(concurrency);
 
-                match provider.embed_batch(&texts).await {
+            let mut failed = false;
+            while let Some((batch, result)) = results.next().await {
+                match result {
                     Ok(embeddings) => {
                         for ((chunk_id, _text, text_hash), embedding) in
-                            to_embed.iter().zip(embeddings.iter())
+                            batch.iter().zip(embeddings.iter())
                         {
                             // Store in chunk
                             if let Err(e) = self.index.store_embedding(chunk_id, embedding, &model)
                             {
                                 warn!("Failed to store embedding for chunk {}: {}", chunk_id, e);
                             } else {
-                                total_embedded += 1;
+                                progress.embedded += 1;
                             }
 
                             // Store in cache for future reuse
@@ -709,28 +955,30 @@ impl MemoryManager {
                     }
                     Err(e) => {
                         warn!("Failed to generate embeddings: {}", e);
-                        break;
+                        failed = true;
                     }
                 }
+                on_progress(progress);
             }
 
             debug!(
                 "Generated embeddings: {}/{} chunks ({} from cache)",
-                total_embedded, total_processed, cache_hits
+                progress.embedded, progress.processed, progress.cache_hits
             );
 
-            // Break if we processed fewer than batch_size (last batch)
-            if chunks.len() < batch_size {
+            // Stop on failure, on a round that stored nothing (avoids
+            // refetching the same chunks forever), or after the last round
+            if failed || progress.embedded == embedded_before || chunks.len() < round_size {
                 break;
             }
         }
 
         info!(
             "Embedding generation complete: {} chunks, {} embedded, {} cache hits",
-            total_processed, total_embedded, cache_hits
+            progress.processed, progress.embedded, progress.cache_hits
         );
 
-        Ok((total_processed, total_embedded))
+        Ok(progress)
     }
 
     /// Get count of chunks with embeddings
@@ -743,3 +991,87 @@ impl MemoryManager {
         self.index.embedded_chunk_count(&model)
     }
 }
+
+/// One daily-log entry: a `## HH:MM:SS` heading with any tags as
+/// `#hashtags`, then the content. Tags are trimmed, lose a leading `#`,
+/// and have inner whitespace replaced with `-`; empty tags are dropped.
+fn format_daily_entry(time: &str, content: &str, tags: &[String]) -> String {
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|t| {
+            t.trim()
+                .trim_start_matches('#')
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("#{}", t))
+        .collect();
+
+    let mut heading = format!("## {}", time);
+    if !tags.is_empty() {
+        heading.push(' ');
+        heading.push_str(&tags.join(" "));
+    }
+    format!("{}\n\n{}\n\n", heading, content)
+}
+
+/// Resolve symlinks in `path`. A file that no longer exists is resolved
+/// through its parent, so its chunks can still be purged.
+fn canonicalize_memory_path(path: &Path) -> Result<PathBuf> {
+    if let Ok(canonical) = fs::canonicalize(path) {
+        return Ok(canonical);
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => Ok(fs::canonicalize(parent)?.join(name)),
+        _ => anyhow::bail!("Not an indexed memory path: {}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_daily_entry() {
+        assert_eq!(
+            format_daily_entry("09:15:00", "Deployed v2.", &[]),
+            "## 09:15:00\n\nDeployed v2.\n\n"
+        );
+        let tags = vec![
+            "#deploy".to_string(),
+            " release notes ".to_string(),
+            "  ".to_string(),
+        ];
+        assert_eq!(
+            format_daily_entry("09:15:00", "Deployed v2.", &tags),
+            "## 09:15:00 #deploy #release-notes\n\nDeployed v2.\n\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reindex_file_rejects_symlink_escape() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.paths = crate::paths::Paths::from_root(tmp.path());
+        config.memory.embedding_provider = "none".to_string();
+        let memory =
+            MemoryManager::new_with_full_config(&config.memory, Some(&config), "test").unwrap();
+
+        let outside = tmp.path().join("outside.md");
+        fs::write(&outside, "# Not memory\n").unwrap();
+        let memory_dir = config.paths.workspace.join("memory");
+        fs::create_dir_all(&memory_dir).unwrap();
+        std::os::unix::fs::symlink(&outside, memory_dir.join("escape.md")).unwrap();
+
+        let err = memory
+            .reindex_file(Path::new("memory/escape.md"))
+            .unwrap_err();
+        assert!(err.to_string().contains("Not an indexed memory path"));
+
+        fs::write(memory_dir.join("notes.md"), "# Notes\n").unwrap();
+        assert!(memory.reindex_file(Path::new("memory/notes.md")).is_ok());
+    }
+}
@@ -0,0 +1,254 @@
+//! Retry and circuit breaking around an embedding provider.
+//!
+//! Each embedding call is retried with exponential backoff. After
+//! `failure_threshold` calls in a row fail even with retries, the circuit
+//! opens: calls fail immediately and memory search falls back to keyword
+//! ranking until the cooldown passes. The next call after the cooldown is
+//! a trial; success closes the circuit, failure reopens it.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use super::embeddings::EmbeddingProvider;
+use crate::config::MemoryConfig;
+
+/// Retry and circuit breaker settings for embedding calls.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Extra attempts after the first failure
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub backoff: Duration,
+    /// Consecutive failed calls that open the circuit (0 = never open)
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a trial call
+    pub cooldown: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &MemoryConfig) -> Self {
+        Self {
+            retries: config.embedding_retries,
+            backoff: Duration::from_millis(config.embedding_retry_backoff_ms),
+            failure_threshold: config.embedding_failure_threshold,
+            cooldown: Duration::from_secs(config.embedding_cooldown_secs),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// An `EmbeddingProvider` that retries transient failures and stops
+/// calling a backend that keeps failing.
+pub struct ResilientEmbeddingProvider {
+    inner: Arc<dyn EmbeddingProvider>,
+    policy: RetryPolicy,
+    state: Mutex<CircuitState>,
+}
+
+impl ResilientEmbeddingProvider {
+    pub fn new(inner: Arc<dyn EmbeddingProvider>, policy: RetryPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            state: Mutex::new(CircuitState::default()),
+        }
+    }
+
+    /// Whether the circuit is open, i.e. calls are currently being refused.
+    pub fn is_open(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.open_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    async fn call<T, F, Fut>(&self, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if self.is_open() {
+            anyhow::bail!(
+                "Embedding backend '{}' unavailable after repeated failures",
+                self.inner.id()
+            );
+        }
+
+        let mut delay = self.policy.backoff;
+        let mut retries_left = self.policy.retries;
+        loop {
+            match attempt().await {
+                Ok(value) => {
+                    self.record_success();
+                    return Ok(value);
+                }
+                Err(e) if retries_left > 0 => {
+                    debug!(
+                        "Embedding call failed, retrying in {}ms: {}",
+                        delay.as_millis(),
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = delay.saturating_mul(2);
+                    retries_left -= 1;
+                }
+                Err(e) => {
+                    self.record_failure(&e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.open_until.take().is_some() {
+            info!(
+                "Embedding backend '{}' recovered, semantic search re-enabled",
+                self.inner.id()
+            );
+        }
+        state.consecutive_failures = 0;
+    }
+
+    fn record_failure(&self, error: &anyhow::Error) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if self.policy.failure_threshold == 0
+            || state.consecutive_failures < self.policy.failure_threshold
+        {
+            return;
+        }
+        // Opening for the first time, or a trial call after the cooldown failed
+        if state.open_until.is_none_or(|until| Instant::now() >= until) {
+            warn!(
+                "Embedding backend '{}' failed {} times in a row ({}). Falling back to keyword search for {}s.",
+                self.inner.id(),
+                state.consecutive_failures,
+                error,
+                self.policy.cooldown.as_secs()
+            );
+            state.open_until = Some(Instant::now() + self.policy.cooldown);
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ResilientEmbeddingProvider {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.call(|| self.inner.embed(text)).await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.call(|| self.inner.embed_batch(texts)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails the first `failures` calls, then succeeds.
+    struct FlakyProvider {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for FlakyProvider {
+        fn id(&self) -> &str {
+            "flaky"
+        }
+
+        fn model(&self) -> &str {
+            "flaky-model"
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                anyhow::bail!("transient failure {}", call);
+            }
+            Ok(vec![1.0, 0.0])
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::new();
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+    }
+
+    fn policy(retries: u32, failure_threshold: u32, cooldown: Duration) -> RetryPolicy {
+        RetryPolicy {
+            retries,
+            backoff: Duration::from_millis(1),
+            failure_threshold,
+            cooldown,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let inner = Arc::new(FlakyProvider {
+            failures: 2,
+            calls: AtomicU32::new(0),
+        });
+        let provider =
+            ResilientEmbeddingProvider::new(inner.clone(), policy(2, 3, Duration::from_secs(60)));
+
+        assert!(provider.embed("hello").await.is_ok());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+        assert!(!provider.is_open());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_and_recovers() {
+        let inner = Arc::new(FlakyProvider {
+            failures: 2,
+            calls: AtomicU32::new(0),
+        });
+        let provider =
+            ResilientEmbeddingProvider::new(inner.clone(), policy(0, 2, Duration::from_millis(20)));
+
+        assert!(provider.embed("a").await.is_err());
+        assert!(!provider.is_open());
+        assert!(provider.embed("b").await.is_err());
+        assert!(provider.is_open());
+
+        // Refused without reaching the backend while open
+        assert!(provider.embed("c").await.is_err());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+
+        // Trial call after the cooldown closes the circuit
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(provider.embed("d").await.is_ok());
+        assert!(!provider.is_open());
+    }
+}
@@ -0,0 +1,170 @@
+//! Memory roots — which workspace files count as memory.
+//!
+//! `memory.roots` is a list of globs relative to the workspace (default:
+//! `MEMORY.md`, `HEARTBEAT.md`, `memory/**/*.md`). The grep-based search
+//! fallback reads exactly these files, and `memory_get` only serves files
+//! that match a root or live under a configured `memory.paths` entry.
+
+use glob::{MatchOptions, Pattern};
+use std::path::{Component, Path, PathBuf};
+use tracing::warn;
+
+use crate::config::MemoryConfig;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, Clone)]
+pub struct MemoryRoots {
+    workspace: PathBuf,
+    roots: Vec<(String, Pattern)>,
+    /// Base directories of `memory.paths` entries
+    index_paths: Vec<PathBuf>,
+}
+
+impl MemoryRoots {
+    pub fn new(workspace: &Path, config: &MemoryConfig) -> Self {
+        let roots = config
+            .roots
+            .iter()
+            .filter_map(|root| match Pattern::new(root) {
+                Ok(pattern) => Some((root.clone(), pattern)),
+                Err(e) => {
+                    warn!("Ignoring invalid memory root '{}': {}", root, e);
+                    None
+                }
+            })
+            .collect();
+
+        let index_paths = config
+            .paths
+            .iter()
+            .map(|p| p.base_path(workspace))
+            .collect();
+
+        Self {
+            workspace: workspace.to_path_buf(),
+            roots,
+            index_paths,
+        }
+    }
+
+    /// Existing files matching any root, as absolute paths, sorted.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .flat_map(|(root, _)| {
+                let pattern = format!("{}/{}", self.workspace.display(), root);
+                glob::glob_with(&pattern, MATCH_OPTIONS)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|r| r.ok())
+            })
+            .filter(|p| p.is_file())
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Whether an absolute path is a memory file: it matches a root, or
+    /// lies under a `memory.paths` directory.
+    pub fn contains(&self, path: &Path) -> bool {
+        let in_roots = path.strip_prefix(&self.workspace).is_ok_and(|relative| {
+            self.roots
+                .iter()
+                .any(|(_, pattern)| pattern.matches_path_with(relative, MATCH_OPTIONS))
+        });
+        in_roots || self.index_paths.iter().any(|base| path.starts_with(base))
+    }
+
+    /// Resolve a path given by the model (workspace-relative, `~/...`, or
+    /// absolute) to a memory file, or `None` if it is outside the roots.
+    /// Paths containing `..` are never resolved.
+    pub fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let expanded = PathBuf::from(shellexpand::tilde(path).to_string());
+        if expanded.components().any(|c| c == Component::ParentDir) {
+            return None;
+        }
+        let full = if expanded.is_absolute() {
+            expanded
+        } else {
+            self.workspace.join(expanded)
+        };
+        self.contains(&full).then_some(full)
+    }
+
+    /// Display name for a memory file: workspace-relative when possible.
+    pub fn display_name(&self, path: &Path) -> String {
+        path.strip_prefix(&self.workspace)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MemoryIndexPath;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn config(roots: &[&str]) -> MemoryConfig {
+        MemoryConfig {
+            roots: roots.iter().map(|r| r.to_string()).collect(),
+            paths: vec![MemoryIndexPath {
+                path: "/srv/team-notes".to_string(),
+                pattern: "**/*.md".to_string(),
+            }],
+            ..MemoryConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_default_roots() {
+        let tmp = TempDir::new().unwrap();
+        let ws = tmp.path();
+        fs::create_dir_all(ws.join("memory/2024")).unwrap();
+        fs::create_dir_all(ws.join("notes")).unwrap();
+        fs::write(ws.join("MEMORY.md"), "m").unwrap();
+        fs::write(ws.join("memory/2024/01-15.md"), "d").unwrap();
+        fs::write(ws.join("notes/idea.md"), "n").unwrap();
+
+        let roots = MemoryRoots::new(ws, &MemoryConfig::default());
+        let names: Vec<String> = roots
+            .files()
+            .iter()
+            .map(|f| roots.display_name(f))
+            .collect();
+        assert_eq!(names, vec!["MEMORY.md", "memory/2024/01-15.md"]);
+
+        assert!(roots.resolve("MEMORY.md").is_some());
+        assert!(roots.resolve("memory/2024/01-15.md").is_some());
+        assert!(roots.resolve("notes/idea.md").is_none());
+        // Default memory.paths entry
+        assert!(roots.resolve("knowledge/topic.md").is_some());
+        assert!(roots.resolve("memory/../notes/idea.md").is_none());
+        assert!(roots.resolve("/etc/passwd").is_none());
+    }
+
+    #[test]
+    fn test_custom_roots_and_external_paths() {
+        let tmp = TempDir::new().unwrap();
+        let ws = tmp.path();
+        fs::create_dir_all(ws.join("journal")).unwrap();
+        fs::write(ws.join("journal/today.txt"), "j").unwrap();
+
+        let roots = MemoryRoots::new(ws, &config(&["journal/*.txt"]));
+        assert_eq!(roots.files().len(), 1);
+        assert!(roots.resolve("journal/today.txt").is_some());
+        assert!(roots.resolve("MEMORY.md").is_none());
+        // `*` does not cross directories
+        assert!(roots.resolve("journal/old/today.txt").is_none());
+        assert!(roots.resolve("/srv/team-notes/plan.md").is_some());
+    }
+}
@@ -19,6 +19,11 @@ pub struct MemoryChunk {
 
     /// Relevance score (higher is better)
     pub score: f64,
+
+    /// Byte range in `content` of the passage that best matches the query
+    /// terms, if any of them appear in it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<(usize, usize)>,
 }
 
 impl MemoryChunk {
@@ -30,6 +35,7 @@ impl MemoryChunk {
             line_end,
             content,
             score,
+            highlight: None,
         }
     }
 
@@ -38,10 +44,51 @@ impl MemoryChunk {
         if self.content.len() <= max_len {
             self.content.clone()
         } else {
-            format!("{}...", &self.content[..max_len])
+            format!(
+                "{}...",
+                crate::agent::truncate_at_char_boundary(&self.content, max_len)
+            )
         }
     }
 
+    /// A preview of about `max_len` characters centered on the highlighted
+    /// span, which is wrapped in `«»`. `None` without a highlight.
+    pub fn highlighted_preview(&self, max_len: usize) -> Option<String> {
+        let (start, end) = self.highlight?;
+        let content = &self.content;
+        if start >= end
+            || end > content.len()
+            || !content.is_char_boundary(start)
+            || !content.is_char_boundary(end)
+        {
+            return None;
+        }
+
+        let hit = &content[start..end];
+        let budget = max_len.saturating_sub(hit.chars().count());
+        let before_chars = content[..start].chars().count();
+        let after_chars = content[end..].chars().count();
+        // Split the remaining budget evenly, giving any unused share of one
+        // side to the other
+        let mut before = (budget / 2).min(before_chars);
+        let after = (budget - before).min(after_chars);
+        before = (budget - after).min(before_chars);
+
+        let before_text: String = content[..start]
+            .chars()
+            .skip(before_chars - before)
+            .collect();
+        let after_text: String = content[end..].chars().take(after).collect();
+        Some(format!(
+            "{}{}«{}»{}{}",
+            if before < before_chars { "..." } else { "" },
+            before_text,
+            hit,
+            after_text,
+            if after < after_chars { "..." } else { "" }
+        ))
+    }
+
     /// Get the location string (file:line)
     pub fn location(&self) -> String {
         if self.line_start == self.line_end {
@@ -52,6 +99,139 @@ impl MemoryChunk {
     }
 }
 
+/// Default vector weight for hybrid search; keyword results get the rest.
+pub const DEFAULT_HYBRID_ALPHA: f32 = 0.7;
+
+/// Per-query tuning for [`MemoryManager::search_with_opts`](super::MemoryManager::search_with_opts).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// Vector weight in hybrid search, from 0.0 (keyword only) to 1.0
+    /// (vector only). Defaults to [`DEFAULT_HYBRID_ALPHA`]. Has no effect
+    /// when embeddings are unavailable.
+    pub alpha: Option<f32>,
+    /// Drop results scoring below this.
+    pub min_score: Option<f64>,
+}
+
+/// Results of [`MemoryManager::search_with_opts`](super::MemoryManager::search_with_opts).
+#[derive(Debug, Clone)]
+pub struct SearchOutcome {
+    pub chunks: Vec<MemoryChunk>,
+    /// Whether vector similarity contributed, i.e. `alpha` took effect
+    pub hybrid: bool,
+}
+
+/// Longest highlighted span, in bytes, that [`best_match_span`] returns.
+const HIGHLIGHT_SPAN_MAX: usize = 120;
+
+/// Byte range of the passage in `content` that covers the most distinct
+/// query terms, comparing stemmed terms as keyword search does. Spans are
+/// capped at [`HIGHLIGHT_SPAN_MAX`] bytes unless a single word is longer;
+/// ties go to the shortest span, then the earliest. `None` if no query term occurs in `content`.
+pub fn best_match_span(content: &str, query: &str) -> Option<(usize, usize)> {
+    let query_terms: std::collections::HashSet<String> =
+        super::bm25::tokenize(query).into_iter().collect();
+    let hits: Vec<_> = super::bm25::tokenize_with_offsets(content)
+        .into_iter()
+        .filter(|(term, _, _)| query_terms.contains(term))
+        .collect();
+
+    let mut best: Option<(usize, (usize, usize))> = None;
+    for (i, (_, start, _)) in hits.iter().enumerate() {
+        let mut distinct = std::collections::HashSet::new();
+        let mut end = *start;
+        for (term, _, term_end) in &hits[i..] {
+            if term_end - start > HIGHLIGHT_SPAN_MAX && !distinct.is_empty() {
+                break;
+            }
+            // Stop the span at the last term that added coverage
+            if distinct.insert(term.as_str()) {
+                end = *term_end;
+            }
+        }
+        let better = best.is_none_or(|(count, (s, e))| {
+            distinct.len() > count || (distinct.len() == count && end - start < e - s)
+        });
+        if better {
+            best = Some((distinct.len(), (*start, end)));
+        }
+    }
+    best.map(|(_, span)| span)
+}
+
+/// A search result after de-duplication, with the number of raw chunks
+/// that were folded into it.
+#[derive(Debug, Clone)]
+pub struct MergedChunk {
+    pub chunk: MemoryChunk,
+    pub merged: usize,
+}
+
+/// Merge results from the same file whose line ranges overlap or touch.
+///
+/// Chunking uses overlap, so a single passage often comes back as several
+/// near-identical hits. Each merged result keeps the best score of its
+/// members and spans the union of their line ranges. The output is sorted
+/// by score, highest first.
+pub fn merge_overlapping_chunks(chunks: Vec<MemoryChunk>) -> Vec<MergedChunk> {
+    let mut sorted = chunks;
+    sorted.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.line_start.cmp(&b.line_start))
+            .then(b.line_end.cmp(&a.line_end))
+    });
+
+    let mut merged: Vec<MergedChunk> = Vec::new();
+    for chunk in sorted {
+        match merged.last_mut() {
+            Some(last)
+                if last.chunk.file == chunk.file && chunk.line_start <= last.chunk.line_end + 1 =>
+            {
+                extend_chunk(&mut last.chunk, chunk);
+                last.merged += 1;
+            }
+            _ => merged.push(MergedChunk { chunk, merged: 1 }),
+        }
+    }
+
+    merged.sort_by(|a, b| {
+        b.chunk
+            .score
+            .partial_cmp(&a.chunk.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    merged
+}
+
+/// Fold `other` (which starts at or after `target`) into `target`.
+fn extend_chunk(target: &mut MemoryChunk, other: MemoryChunk) {
+    if other.line_end > target.line_end {
+        // Append only the lines of `other` that lie past the current end
+        let skip = (target.line_end + 1 - other.line_start).max(0) as usize;
+        let appended_at = target.content.len();
+        for line in other.content.lines().skip(skip) {
+            if !target.content.is_empty() && !target.content.ends_with('\n') {
+                target.content.push('\n');
+            }
+            target.content.push_str(line);
+        }
+        target.line_end = other.line_end;
+
+        // The existing content is unchanged, so only a highlight from the
+        // appended lines needs relocating
+        if target.highlight.is_none()
+            && let Some((start, end)) = other.highlight
+            && let Some(hit) = other.content.get(start..end)
+            && let Some(pos) = target.content[appended_at..].find(hit)
+        {
+            let start = appended_at + pos;
+            target.highlight = Some((start, start + hit.len()));
+        }
+    }
+    target.score = target.score.max(other.score);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,6 +250,49 @@ mod tests {
         assert_eq!(chunk.location(), "test.md:1-5");
     }
 
+    #[test]
+    fn test_highlighted_preview() {
+        let content = format!("{} deploy script {}", "a".repeat(50), "b".repeat(50));
+        let mut chunk = MemoryChunk::new("test.md".to_string(), 1, 1, content.clone(), 0.5);
+        assert_eq!(chunk.highlighted_preview(20), None);
+
+        let start = content.find("deploy").unwrap();
+        chunk.highlight = Some((start, start + "deploy script".len()));
+        assert_eq!(
+            chunk.highlighted_preview(23).unwrap(),
+            "...aaaa «deploy script» bbbb..."
+        );
+        // Room to spare on both sides shows everything
+        assert_eq!(
+            chunk.highlighted_preview(500).unwrap(),
+            content.replace("deploy script", "«deploy script»")
+        );
+
+        // Budget unused at the start goes to the end
+        let mut head = MemoryChunk::new("t.md".to_string(), 1, 1, "deploy it now".into(), 0.5);
+        head.highlight = Some((0, 6));
+        assert_eq!(head.highlighted_preview(10).unwrap(), "«deploy» it ...");
+    }
+
+    #[test]
+    fn test_best_match_span() {
+        let content =
+            "Deploys happen weekly.\nThe deploy script lives in ops/ and the script is tested.";
+        let (start, end) = best_match_span(content, "deploying scripts").unwrap();
+        // The tightest window covering both terms wins
+        assert_eq!(&content[start..end], "deploy script");
+
+        assert_eq!(best_match_span(content, "kubernetes"), None);
+        assert_eq!(best_match_span(content, "the and"), None);
+    }
+
+    #[test]
+    fn test_best_match_span_caps_length() {
+        let content = format!("alpha {} delta", "x ".repeat(100));
+        let (start, end) = best_match_span(&content, "alpha delta").unwrap();
+        assert_eq!(&content[start..end], "alpha");
+    }
+
     #[test]
     fn test_memory_chunk_single_line_location() {
         let chunk = MemoryChunk::new(
@@ -82,4 +305,70 @@ mod tests {
 
         assert_eq!(chunk.location(), "test.md:10");
     }
+
+    fn chunk(file: &str, start: i32, end: i32, score: f64) -> MemoryChunk {
+        let content = (start..=end)
+            .map(|n| format!("line {}", n))
+            .collect::<Vec<_>>()
+            .join("\n");
+        MemoryChunk::new(file.to_string(), start, end, content, score)
+    }
+
+    #[test]
+    fn test_merge_overlapping_chunks() {
+        let merged = merge_overlapping_chunks(vec![
+            chunk("a.md", 1, 4, 0.5),
+            chunk("a.md", 3, 6, 0.9),
+            chunk("b.md", 1, 2, 0.7),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].chunk.location(), "a.md:1-6");
+        assert_eq!(merged[0].merged, 2);
+        assert_eq!(merged[0].chunk.score, 0.9);
+        assert_eq!(
+            merged[0].chunk.content,
+            "line 1\nline 2\nline 3\nline 4\nline 5\nline 6"
+        );
+        assert_eq!(merged[1].chunk.location(), "b.md:1-2");
+        assert_eq!(merged[1].merged, 1);
+    }
+
+    #[test]
+    fn test_merge_relocates_highlight() {
+        let first = chunk("a.md", 1, 3, 0.5);
+        let mut second = chunk("a.md", 3, 5, 0.9);
+        let start = second.content.find("line 5").unwrap();
+        second.highlight = Some((start, start + "line 5".len()));
+
+        let merged = merge_overlapping_chunks(vec![first, second]);
+        let chunk = &merged[0].chunk;
+        let (start, end) = chunk.highlight.unwrap();
+        assert_eq!(&chunk.content[start..end], "line 5");
+    }
+
+    #[test]
+    fn test_merge_adjacent_and_contained_chunks() {
+        let merged = merge_overlapping_chunks(vec![
+            chunk("a.md", 5, 8, 0.4),
+            chunk("a.md", 1, 4, 0.3),
+            chunk("a.md", 2, 3, 0.8),
+        ]);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].chunk.location(), "a.md:1-8");
+        assert_eq!(merged[0].merged, 3);
+        assert_eq!(merged[0].chunk.score, 0.8);
+        assert_eq!(merged[0].chunk.content.lines().count(), 8);
+    }
+
+    #[test]
+    fn test_merge_keeps_distant_chunks_separate() {
+        let merged =
+            merge_overlapping_chunks(vec![chunk("a.md", 1, 3, 0.2), chunk("a.md", 10, 12, 0.6)]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].chunk.location(), "a.md:10-12");
+        assert_eq!(merged[1].chunk.location(), "a.md:1-3");
+    }
 }
@@ -53,12 +53,7 @@ impl MemoryWatcher {
         // Watch configured paths
         let mut watched_paths = vec![workspace.clone()];
         for index_path in &config.paths {
-            let base_path = if index_path.path.starts_with('~') || index_path.path.starts_with('/')
-            {
-                PathBuf::from(shellexpand::tilde(&index_path.path).to_string())
-            } else {
-                workspace.join(&index_path.path)
-            };
+            let base_path = index_path.base_path(&workspace);
 
             // Skip if already watching (subdirectory of workspace)
             if base_path.starts_with(&workspace) {
@@ -193,6 +193,22 @@ impl Paths {
 
         Ok(())
     }
+
+    /// Relocate the state directory (sessions, audit log, logs, PID fallback).
+    ///
+    /// The directory is created if needed and must be writable.
+    pub fn set_state_dir(&mut self, state_dir: PathBuf) -> Result<()> {
+        if !state_dir.is_absolute() {
+            anyhow::bail!(
+                "state_dir must be an absolute path: {}",
+                state_dir.display()
+            );
+        }
+        create_dir_with_mode(&state_dir)?;
+        ensure_writable(&state_dir)?;
+        self.state_dir = state_dir;
+        Ok(())
+    }
 }
 
 impl Default for Paths {
@@ -304,6 +320,15 @@ fn create_dir_with_mode(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Verify a directory is writable by creating and removing a probe file.
+fn ensure_writable(dir: &Path) -> Result<()> {
+    let probe = dir.join(format!(".write-test-{}", std::process::id()));
+    std::fs::write(&probe, b"")
+        .with_context(|| format!("Directory is not writable: {}", dir.display()))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,4 +451,27 @@ mod tests {
         assert!(paths.config_dir.is_absolute());
         assert!(paths.config_dir.ends_with("localgpt"));
     }
+
+    #[test]
+    fn set_state_dir_relocates_and_validates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut paths = Paths::from_root(tmp.path());
+        let state = tmp.path().join("elsewhere").join("state");
+
+        paths.set_state_dir(state.clone()).unwrap();
+        assert_eq!(paths.state_dir, state);
+        assert!(state.is_dir());
+        assert_eq!(paths.audit_log(), state.join("localgpt.audit.jsonl"));
+    }
+
+    #[test]
+    fn set_state_dir_rejects_relative_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut paths = Paths::from_root(tmp.path());
+        assert!(
+            paths
+                .set_state_dir(PathBuf::from("relative/state"))
+                .is_err()
+        );
+    }
 }
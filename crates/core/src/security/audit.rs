@@ -11,6 +11,7 @@
 //! | Field | Description |
 //! |-------|-------------|
 //! | `ts` | ISO 8601 timestamp |
+//! | `seq` | Monotonically increasing sequence number (gaps mean lost entries) |
 //! | `action` | What happened: `signed`, `verified`, `tamper_detected`, etc. |
 //! | `content_sha256` | SHA-256 of the policy content at the time |
 //! | `prev_entry_sha256` | SHA-256 of the previous JSONL line (chain link) |
@@ -21,15 +22,28 @@
 //! The first entry uses `000...000` (64 zeros) as `prev_entry_sha256`.
 //! Every subsequent entry hashes the raw bytes of the previous line.
 //! A broken chain indicates the log file was tampered with.
+//!
+//! # Concurrency
+//!
+//! Appends hold an exclusive advisory lock on `localgpt.audit.lock` while
+//! they read the tail, assign the next sequence number, and write. Concurrent
+//! writers (threads or processes) therefore never interleave lines, fork the
+//! chain, or reuse a sequence number.
 
 use anyhow::{Context, Result};
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
 const AUDIT_FILENAME: &str = "localgpt.audit.jsonl";
+const AUDIT_LOCK_FILENAME: &str = "localgpt.audit.lock";
+
+/// How much of the log's end an append reads at first to find the previous
+/// entry; doubled until a whole, parseable entry is in range.
+const AUDIT_TAIL_BYTES: u64 = 16 * 1024;
 
 /// The hash used for the first entry in the chain (no predecessor).
 const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
@@ -39,6 +53,11 @@ const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000
 pub struct AuditEntry {
     /// ISO 8601 timestamp of the event.
     pub ts: String,
+    /// Sequence number, one greater than the previous entry's.
+    ///
+    /// Entries written before sequence numbers existed read as `0`.
+    #[serde(default)]
+    pub seq: u64,
     /// What security action occurred.
     pub action: AuditAction,
     /// SHA-256 of the policy content at the time (hex-encoded).
@@ -78,6 +97,17 @@ pub enum AuditAction {
     WriteBlocked,
     /// Previous audit entry corrupted, new chain segment started.
     ChainRecovery,
+    /// A named config profile was applied. Profile name in `detail`.
+    ProfileActivated,
+    /// A file tool was refused a sandbox-denied path. Resolved path in `detail`.
+    PathDenied,
+    /// An agent tool changed something: a file written or edited, a bash
+    /// command run. Target and outcome (bytes, occurrences, exit code) in
+    /// `detail`, with secrets redacted.
+    ToolMutation,
+    /// An agent tool was called. Outcome and argument summary in `detail`,
+    /// with secrets redacted.
+    ToolInvoked,
 }
 
 /// Append a new entry to the audit log.
@@ -112,9 +142,14 @@ pub fn append_audit_entry_with_detail(
 ) -> Result<()> {
     let path = audit_file_path(state_dir);
 
+    // Hold the lock across read-tail → write so concurrent appends serialize
+    let _lock = lock_audit_log(state_dir)?;
+
     // Read the last line to compute the chain hash, with corruption recovery
+    let mut last_seq = 0;
     let prev_hash = if path.exists() {
-        let content = fs::read_to_string(&path).context("Failed to read audit log")?;
+        let content = read_log_tail(&path).context("Failed to read audit log")?;
+        last_seq = last_sequence(&content);
         match content.lines().last() {
             Some(last_line) if !last_line.is_empty() => {
                 // Attempt to parse as JSON to detect corruption
@@ -123,8 +158,10 @@ pub fn append_audit_entry_with_detail(
                 } else {
                     // Corrupted last line — write a ChainRecovery entry first
                     let raw_hash = sha256_hex(last_line.as_bytes());
+                    last_seq += 1;
                     let recovery = AuditEntry {
                         ts: chrono::Utc::now().to_rfc3339(),
+                        seq: last_seq,
                         action: AuditAction::ChainRecovery,
                         content_sha256: String::new(),
                         prev_entry_sha256: raw_hash,
@@ -148,6 +185,7 @@ pub fn append_audit_entry_with_detail(
 
     let entry = AuditEntry {
         ts: chrono::Utc::now().to_rfc3339(),
+        seq: last_seq + 1,
         action,
         content_sha256: content_sha256.to_string(),
         prev_entry_sha256: prev_hash,
@@ -161,6 +199,68 @@ pub fn append_audit_entry_with_detail(
     Ok(())
 }
 
+/// Guard holding the exclusive audit log lock; released on drop.
+struct AuditLockGuard {
+    file: fs::File,
+}
+
+impl Drop for AuditLockGuard {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// Block until this caller holds the exclusive audit log lock.
+fn lock_audit_log(state_dir: &Path) -> Result<AuditLockGuard> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(state_dir.join(AUDIT_LOCK_FILENAME))
+        .context("Failed to open audit lock file")?;
+    file.lock_exclusive().context("Failed to lock audit log")?;
+    Ok(AuditLockGuard { file })
+}
+
+/// Sequence number of the last parseable entry in `content` (0 if none).
+fn last_sequence(content: &str) -> u64 {
+    content
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .map(|entry| entry.seq)
+        .unwrap_or(0)
+}
+
+/// The end of the audit log from a line boundary, far enough back to
+/// include the last parseable entry (or the whole file if there is none),
+/// so appends don't read the entire log.
+fn read_log_tail(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut window = AUDIT_TAIL_BYTES;
+    loop {
+        let start = len.saturating_sub(window);
+        file.seek(SeekFrom::Start(start))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let mut text = String::from_utf8_lossy(&bytes).into_owned();
+        if start > 0 {
+            // Drop the partial line the window starts in
+            let cut = text.find('\n').map_or(text.len(), |i| i + 1);
+            text.drain(..cut);
+        }
+
+        let has_entry = text
+            .lines()
+            .any(|line| serde_json::from_str::<AuditEntry>(line).is_ok());
+        if start == 0 || has_entry {
+            return Ok(text);
+        }
+        window *= 2;
+    }
+}
+
 /// Append a single line to a file.
 fn append_line(path: &Path, line: &str) -> Result<()> {
     let mut file = fs::OpenOptions::new()
@@ -199,6 +299,100 @@ pub fn read_audit_log(state_dir: &Path) -> Result<Vec<AuditEntry>> {
     Ok(entries)
 }
 
+/// Filters and position for reading part of the audit log.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    /// Only entries at or after this time.
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only entries before this time.
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only these actions; empty means all.
+    pub actions: Vec<AuditAction>,
+    /// Where to resume, as returned in [`AuditPage::next_cursor`]; 0 reads
+    /// from the start.
+    pub cursor: u64,
+    /// Maximum entries to return (0 = no limit).
+    pub limit: usize,
+}
+
+/// One page of audit entries from [`read_audit_entries`].
+#[derive(Debug, Clone)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    /// Cursor for the next page. Also the position to tail from: entries
+    /// appended later are read by querying again with this cursor.
+    pub next_cursor: u64,
+    /// Whether lines past `next_cursor` were left unread.
+    pub has_more: bool,
+}
+
+/// Read the entries matching `query`, starting at its cursor.
+///
+/// The cursor is a byte offset into the log, so resuming a page or tailing
+/// a large log doesn't re-read what came before. A trailing line without a
+/// newline is still being written and is left for the next read.
+/// Corrupted lines are skipped, as in [`read_audit_log`].
+pub fn read_audit_entries(state_dir: &Path, query: &AuditQuery) -> Result<AuditPage> {
+    let path = audit_file_path(state_dir);
+    let mut page = AuditPage {
+        entries: Vec::new(),
+        next_cursor: query.cursor,
+        has_more: false,
+    };
+    if !path.exists() {
+        return Ok(page);
+    }
+
+    let mut file = fs::File::open(&path).context("Failed to open audit log")?;
+    let len = file.metadata()?.len();
+    if query.cursor > len {
+        anyhow::bail!("Audit cursor {} is past the end of the log", query.cursor);
+    }
+    file.seek(SeekFrom::Start(query.cursor))?;
+
+    // Line by line, so a page costs what it returns rather than the rest
+    // of the log. Once the page is full, one more complete line is read
+    // (and not consumed) to tell whether there is more.
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .context("Failed to read audit log")?;
+        if line.last() != Some(&b'\n') {
+            break;
+        }
+        if query.limit > 0 && page.entries.len() >= query.limit {
+            page.has_more = true;
+            break;
+        }
+        page.next_cursor += read as u64;
+
+        let Ok(entry) = serde_json::from_slice::<AuditEntry>(&line[..read - 1]) else {
+            continue;
+        };
+        if audit_entry_matches(&entry, query) {
+            page.entries.push(entry);
+        }
+    }
+
+    Ok(page)
+}
+
+fn audit_entry_matches(entry: &AuditEntry, query: &AuditQuery) -> bool {
+    if !query.actions.is_empty() && !query.actions.contains(&entry.action) {
+        return false;
+    }
+    if query.since.is_none() && query.until.is_none() {
+        return true;
+    }
+    let Ok(ts) = chrono::DateTime::parse_from_rfc3339(&entry.ts) else {
+        return false;
+    };
+    query.since.is_none_or(|since| ts >= since) && query.until.is_none_or(|until| ts < until)
+}
+
 /// Verify the integrity of the audit log hash chain.
 ///
 /// Returns a list of indices where the chain is broken (i.e., the
@@ -252,6 +446,32 @@ pub fn verify_audit_chain(state_dir: &Path) -> Result<Vec<usize>> {
     Ok(broken)
 }
 
+/// Check that sequence numbers increase by exactly one.
+///
+/// Returns indices (into [`read_audit_log`] order) of entries whose `seq`
+/// is not one greater than the previous entry's, which indicates a lost,
+/// duplicated, or reordered entry. Entries without a sequence number
+/// (written by older versions) are ignored.
+pub fn verify_audit_sequence(state_dir: &Path) -> Result<Vec<usize>> {
+    let entries = read_audit_log(state_dir)?;
+    let mut gaps = Vec::new();
+    let mut prev: Option<u64> = None;
+
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.seq == 0 {
+            continue;
+        }
+        if let Some(prev) = prev
+            && entry.seq != prev + 1
+        {
+            gaps.push(i);
+        }
+        prev = Some(entry.seq);
+    }
+
+    Ok(gaps)
+}
+
 /// Get the full path to the audit log file.
 pub fn audit_file_path(state_dir: &Path) -> PathBuf {
     state_dir.join(AUDIT_FILENAME)
@@ -295,6 +515,113 @@ mod tests {
         assert!(broken.is_empty(), "Chain should be intact: {:?}", broken);
     }
 
+    #[test]
+    fn read_audit_entries_pages_and_filters() {
+        let tmp = tempfile::tempdir().unwrap();
+        for i in 0..5 {
+            let action = if i % 2 == 0 {
+                AuditAction::Verified
+            } else {
+                AuditAction::ToolMutation
+            };
+            append_audit_entry(tmp.path(), action, &format!("sha256_{}", i), "test").unwrap();
+        }
+
+        let first = read_audit_entries(
+            tmp.path(),
+            &AuditQuery {
+                limit: 2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(first.entries.len(), 2);
+        assert!(first.has_more);
+
+        let rest = read_audit_entries(
+            tmp.path(),
+            &AuditQuery {
+                cursor: first.next_cursor,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(rest.entries.len(), 3);
+        assert!(!rest.has_more);
+        assert_eq!(rest.entries[0].content_sha256, "sha256_2");
+
+        // Tailing from the end finds only new entries
+        append_audit_entry(tmp.path(), AuditAction::ToolMutation, "sha256_5", "test").unwrap();
+        let mutations = read_audit_entries(
+            tmp.path(),
+            &AuditQuery {
+                cursor: rest.next_cursor,
+                actions: vec![AuditAction::ToolMutation],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(mutations.entries.len(), 1);
+        assert_eq!(mutations.entries[0].content_sha256, "sha256_5");
+
+        let future = read_audit_entries(
+            tmp.path(),
+            &AuditQuery {
+                since: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(future.entries.is_empty());
+    }
+
+    #[test]
+    fn read_audit_entries_peeks_one_line_past_the_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        for i in 0..3 {
+            append_audit_entry(
+                tmp.path(),
+                AuditAction::Verified,
+                &format!("sha256_{}", i),
+                "test",
+            )
+            .unwrap();
+        }
+        let query = |cursor: u64, limit: usize| AuditQuery {
+            cursor,
+            limit,
+            ..Default::default()
+        };
+
+        // A page that ends exactly at the last line has nothing more
+        let all = read_audit_entries(tmp.path(), &query(0, 3)).unwrap();
+        assert_eq!(all.entries.len(), 3);
+        assert!(!all.has_more);
+        let len = fs::metadata(audit_file_path(tmp.path())).unwrap().len();
+        assert_eq!(all.next_cursor, len);
+
+        // The peeked line isn't consumed
+        let first = read_audit_entries(tmp.path(), &query(0, 1)).unwrap();
+        assert!(first.has_more);
+        let second = read_audit_entries(tmp.path(), &query(first.next_cursor, 1)).unwrap();
+        assert_eq!(second.entries[0].content_sha256, "sha256_1");
+
+        // A line still being written is left for the next read
+        append_unterminated(tmp.path(), "{\"partial");
+        let tail = read_audit_entries(tmp.path(), &query(len, 0)).unwrap();
+        assert!(tail.entries.is_empty());
+        assert!(!tail.has_more);
+        assert_eq!(tail.next_cursor, len);
+    }
+
+    fn append_unterminated(state_dir: &Path, text: &str) {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(audit_file_path(state_dir))
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
     #[test]
     fn first_entry_uses_genesis_hash() {
         let tmp = tempfile::tempdir().unwrap();
@@ -352,6 +679,7 @@ mod tests {
     fn audit_actions_serialize_snake_case() {
         let entry = AuditEntry {
             ts: "2026-02-09T14:00:00Z".to_string(),
+            seq: 1,
             action: AuditAction::TamperDetected,
             content_sha256: "abc".to_string(),
             prev_entry_sha256: GENESIS_HASH.to_string(),
@@ -369,6 +697,7 @@ mod tests {
     fn detail_field_serialized_when_present() {
         let entry = AuditEntry {
             ts: "2026-02-09T14:00:00Z".to_string(),
+            seq: 1,
             action: AuditAction::WriteBlocked,
             content_sha256: String::new(),
             prev_entry_sha256: GENESIS_HASH.to_string(),
@@ -431,4 +760,120 @@ mod tests {
         // Signed + ChainRecovery + Verified = 3 (garbage line skipped)
         assert_eq!(entries.len(), 3);
     }
+
+    #[test]
+    fn sequence_numbers_increase() {
+        let tmp = tempfile::tempdir().unwrap();
+        for _ in 0..3 {
+            append_audit_entry(tmp.path(), AuditAction::Verified, "abc", "test").unwrap();
+        }
+
+        let seqs: Vec<u64> = read_audit_log(tmp.path())
+            .unwrap()
+            .iter()
+            .map(|e| e.seq)
+            .collect();
+        assert_eq!(seqs, vec![1, 2, 3]);
+        assert!(verify_audit_sequence(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn append_reads_only_the_tail_of_a_long_log() {
+        let tmp = tempfile::tempdir().unwrap();
+        // Enough entries that the chain's tail lies well past the first window
+        let detail = "x".repeat(1000);
+        for _ in 0..40 {
+            append_audit_entry_with_detail(
+                tmp.path(),
+                AuditAction::ToolInvoked,
+                "",
+                "tool:read_file",
+                Some(&detail),
+            )
+            .unwrap();
+        }
+        let path = audit_file_path(tmp.path());
+        assert!(fs::metadata(&path).unwrap().len() > AUDIT_TAIL_BYTES);
+
+        let tail = read_log_tail(&path).unwrap();
+        assert!(tail.len() as u64 <= AUDIT_TAIL_BYTES);
+        assert!(serde_json::from_str::<AuditEntry>(tail.lines().next().unwrap()).is_ok());
+
+        let entries = read_audit_log(tmp.path()).unwrap();
+        assert_eq!(entries.last().unwrap().seq, 40);
+        assert!(verify_audit_chain(tmp.path()).unwrap().is_empty());
+        assert!(verify_audit_sequence(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn sequence_gap_detected() {
+        let tmp = tempfile::tempdir().unwrap();
+        for _ in 0..3 {
+            append_audit_entry(tmp.path(), AuditAction::Verified, "abc", "test").unwrap();
+        }
+
+        // Drop the middle entry
+        let path = audit_file_path(tmp.path());
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+
+        assert_eq!(verify_audit_sequence(tmp.path()).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn legacy_entries_without_seq_are_continued() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = audit_file_path(tmp.path());
+        fs::write(
+            &path,
+            format!(
+                "{{\"ts\":\"2026-02-09T14:00:00Z\",\"action\":\"created\",\"content_sha256\":\"\",\"prev_entry_sha256\":\"{}\",\"source\":\"cli\"}}\n",
+                GENESIS_HASH
+            ),
+        )
+        .unwrap();
+
+        append_audit_entry(tmp.path(), AuditAction::Verified, "abc", "test").unwrap();
+
+        let entries = read_audit_log(tmp.path()).unwrap();
+        assert_eq!(entries[0].seq, 0);
+        assert_eq!(entries[1].seq, 1);
+        assert!(verify_audit_chain(tmp.path()).unwrap().is_empty());
+        assert!(verify_audit_sequence(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn concurrent_appends_are_serialized() {
+        let tmp = tempfile::tempdir().unwrap();
+        let threads = 8;
+        let per_thread = 25;
+
+        std::thread::scope(|scope| {
+            for t in 0..threads {
+                let dir = tmp.path();
+                scope.spawn(move || {
+                    for i in 0..per_thread {
+                        append_audit_entry(
+                            dir,
+                            AuditAction::Verified,
+                            &format!("{}_{}", t, i),
+                            "stress",
+                        )
+                        .unwrap();
+                    }
+                });
+            }
+        });
+
+        let entries = read_audit_log(tmp.path()).unwrap();
+        assert_eq!(entries.len(), threads * per_thread);
+
+        let seqs: Vec<u64> = entries.iter().map(|e| e.seq).collect();
+        let expected: Vec<u64> = (1..=(threads * per_thread) as u64).collect();
+        assert_eq!(seqs, expected);
+
+        assert!(verify_audit_chain(tmp.path()).unwrap().is_empty());
+        assert!(verify_audit_sequence(tmp.path()).unwrap().is_empty());
+    }
 }
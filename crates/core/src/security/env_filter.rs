@@ -0,0 +1,208 @@
+//! Name-based filtering of the environment handed to bash commands.
+//!
+//! An [`EnvPolicy`] picks one of three modes: pass everything through,
+//! drop names on a deny list, or keep only names on an allow list plus
+//! [`ENV_ALWAYS_ALLOWED`]. Patterns are variable names with `*` wildcards,
+//! matched case-sensitively against the whole name: `GITHUB_TOKEN`,
+//! `AWS_*`, `*_SECRET`, `*TOKEN*`, or `AWS_*_KEY` with wildcards anywhere
+//! in between.
+
+use serde::{Deserialize, Serialize};
+
+/// Variables kept under [`EnvPolicy::AllowOnly`] regardless of the allow list.
+pub const ENV_ALWAYS_ALLOWED: &[&str] = &["PATH", "HOME", "LANG"];
+
+/// How the inherited environment is filtered for bash commands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvPolicy {
+    /// Pass every inherited variable through.
+    Inherit,
+    /// Drop variables matching the deny patterns.
+    #[default]
+    Deny,
+    /// Drop everything except variables matching the allow patterns and
+    /// [`ENV_ALWAYS_ALLOWED`].
+    AllowOnly,
+}
+
+/// An [`EnvPolicy`] with its pattern list. Only the list for the active
+/// mode is kept, so the modes never combine.
+#[derive(Debug, Clone, Default)]
+pub struct EnvFilter {
+    policy: EnvPolicy,
+    patterns: Vec<String>,
+}
+
+impl EnvFilter {
+    pub fn new(policy: EnvPolicy, deny_patterns: &[String], allow_patterns: &[String]) -> Self {
+        let patterns = match policy {
+            EnvPolicy::Inherit => Vec::new(),
+            EnvPolicy::Deny => deny_patterns.to_vec(),
+            EnvPolicy::AllowOnly => allow_patterns.to_vec(),
+        };
+        Self { policy, patterns }
+    }
+
+    /// Build from `security.env_policy` and its pattern lists, warning about
+    /// a configured list the chosen mode ignores.
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        let security = &config.security;
+        for (name, list, used_by) in [
+            (
+                "env_deny_patterns",
+                &security.env_deny_patterns,
+                EnvPolicy::Deny,
+            ),
+            (
+                "env_allow_patterns",
+                &security.env_allow_patterns,
+                EnvPolicy::AllowOnly,
+            ),
+        ] {
+            if !list.is_empty() && security.env_policy != used_by {
+                tracing::warn!(
+                    "security.{} is ignored with env_policy = {:?}",
+                    name,
+                    security.env_policy
+                );
+            }
+        }
+        Self::new(
+            security.env_policy,
+            &security.env_deny_patterns,
+            &security.env_allow_patterns,
+        )
+    }
+
+    pub fn policy(&self) -> EnvPolicy {
+        self.policy
+    }
+
+    /// Whether the inherited variable `name` is passed to the command.
+    pub fn keeps(&self, name: &str) -> bool {
+        match self.policy {
+            EnvPolicy::Inherit => true,
+            EnvPolicy::Deny => !env_var_denied(name, &self.patterns),
+            EnvPolicy::AllowOnly => {
+                ENV_ALWAYS_ALLOWED.contains(&name) || env_var_denied(name, &self.patterns)
+            }
+        }
+    }
+}
+
+/// Whether `name` matches the wildcard `pattern`.
+///
+/// Each `*` matches any run of characters, including none. Without a
+/// leading or trailing `*` the pattern is anchored at that end.
+pub fn env_pattern_matches(pattern: &str, name: &str) -> bool {
+    let Some((head, rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+
+    // Common single-wildcard shapes: PREFIX*, *SUFFIX, *INFIX*
+    if !rest.contains('*') {
+        return name.len() >= head.len() + rest.len()
+            && name.starts_with(head)
+            && name.ends_with(rest);
+    }
+    if head.is_empty()
+        && let Some(infix) = rest.strip_suffix('*')
+        && !infix.contains('*')
+    {
+        return name.contains(infix);
+    }
+
+    // General case: anchored head and tail with the middle segments found
+    // in order, each after the previous one
+    let (middle, tail) = rest.rsplit_once('*').unwrap_or(("", rest));
+    if name.len() < head.len() + tail.len() || !name.starts_with(head) || !name.ends_with(tail) {
+        return false;
+    }
+    let mut remaining = &name[head.len()..name.len() - tail.len()];
+    for segment in middle.split('*').filter(|s| !s.is_empty()) {
+        match remaining.find(segment) {
+            Some(pos) => remaining = &remaining[pos + segment.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Whether `name` matches any of the deny `patterns`.
+pub fn env_var_denied(name: &str, patterns: &[String]) -> bool {
+    patterns.iter().any(|p| env_pattern_matches(p, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_patterns() {
+        assert!(env_pattern_matches("GITHUB_TOKEN", "GITHUB_TOKEN"));
+        assert!(!env_pattern_matches("GITHUB_TOKEN", "GITHUB_TOKENS"));
+        assert!(env_pattern_matches("AWS_*", "AWS_REGION"));
+        assert!(!env_pattern_matches("AWS_*", "MY_AWS_REGION"));
+        assert!(env_pattern_matches("*_SECRET", "CLIENT_SECRET"));
+        assert!(!env_pattern_matches("*_SECRET", "CLIENT_SECRET_FILE"));
+        assert!(env_pattern_matches("*TOKEN*", "NPM_TOKEN_RO"));
+        assert!(env_pattern_matches("*", "ANYTHING"));
+    }
+
+    #[test]
+    fn test_middle_wildcards() {
+        assert!(env_pattern_matches("AWS_*_KEY", "AWS_SECRET_KEY"));
+        assert!(env_pattern_matches("AWS_*_KEY", "AWS_SECRET_ACCESS_KEY"));
+        assert!(!env_pattern_matches("AWS_*_KEY", "MY_AWS_KEY"));
+        assert!(!env_pattern_matches("AWS_*_KEY", "AWS_KEY"));
+        assert!(!env_pattern_matches("AWS_*_KEY", "AWS_SECRET_KEY_ID"));
+
+        assert!(env_pattern_matches("*_*_TOKEN", "GH_CI_TOKEN"));
+        assert!(env_pattern_matches("A*B*C", "AXXBYYC"));
+        assert!(env_pattern_matches("A*B*C", "ABC"));
+        assert!(!env_pattern_matches("A*B*C", "ACB"));
+        // Segments may not overlap the anchored tail
+        assert!(!env_pattern_matches("A*BC*C", "ABC"));
+    }
+
+    #[test]
+    fn test_env_filter_modes() {
+        let deny = vec!["*TOKEN*".to_string()];
+        let allow = vec!["CARGO_*".to_string()];
+
+        let inherit = EnvFilter::new(EnvPolicy::Inherit, &deny, &allow);
+        assert!(inherit.keeps("SLACK_TOKEN"));
+        assert!(inherit.keeps("EDITOR"));
+
+        let denying = EnvFilter::new(EnvPolicy::Deny, &deny, &allow);
+        assert!(!denying.keeps("SLACK_TOKEN"));
+        assert!(denying.keeps("EDITOR"));
+        // The allow list plays no part in deny mode
+        assert!(denying.keeps("CARGO_HOME"));
+
+        let allow_only = EnvFilter::new(EnvPolicy::AllowOnly, &deny, &allow);
+        assert!(allow_only.keeps("CARGO_HOME"));
+        assert!(allow_only.keeps("PATH"));
+        assert!(allow_only.keeps("HOME"));
+        assert!(allow_only.keeps("LANG"));
+        assert!(!allow_only.keeps("EDITOR"));
+        assert!(!allow_only.keeps("SLACK_TOKEN"));
+    }
+
+    #[test]
+    fn test_env_policy_default_is_deny() {
+        assert_eq!(EnvPolicy::default(), EnvPolicy::Deny);
+        // With no patterns, deny mode passes everything through
+        assert!(EnvFilter::default().keeps("GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn test_env_var_denied() {
+        let patterns = vec!["AWS_*_KEY".to_string(), "*TOKEN*".to_string()];
+        assert!(env_var_denied("AWS_SECRET_KEY", &patterns));
+        assert!(env_var_denied("SLACK_TOKEN", &patterns));
+        assert!(!env_var_denied("PATH", &patterns));
+        assert!(!env_var_denied("PATH", &[]));
+    }
+}
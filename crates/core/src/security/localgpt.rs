@@ -18,6 +18,10 @@
 //! │                │               │ chain    │                     │
 //! ├────────────────┴───────────────┴──────────┴─────────────────────┤
 //! │  suffix.rs — Hardcoded security suffix (always last in context) │
+//! ├─────────────────────────────────────────────────────────────────┤
+//! │  secret_scanner.rs — Detect/redact credentials by value pattern │
+//! ├─────────────────────────────────────────────────────────────────┤
+//! │  risk.rs — Advisory low/medium/high risk tags for bash commands │
 //! └──────────────────────────────────────────────────────────────────┘
 //! ```
 //!
@@ -116,8 +120,9 @@ pub use super::signing::{
 // ── Audit Log ───────────────────────────────────────────────────────
 
 pub use super::audit::{
-    AuditAction, AuditEntry, append_audit_entry, append_audit_entry_with_detail, audit_file_path,
-    read_audit_log, verify_audit_chain,
+    AuditAction, AuditEntry, AuditPage, AuditQuery, append_audit_entry,
+    append_audit_entry_with_detail, audit_file_path, read_audit_entries, read_audit_log,
+    verify_audit_chain, verify_audit_sequence,
 };
 
 // ── Protected Files ─────────────────────────────────────────────────
@@ -127,6 +132,23 @@ pub use super::protected_files::{
     is_workspace_file_protected,
 };
 
+// ── Secret Scanning ─────────────────────────────────────────────────
+
+pub use super::secret_scanner::{
+    RedactOptions, SecretMatch, SecretScanner, contains_secret, find_secrets,
+    find_secrets_with_opts, redact_secrets, redact_secrets_with_opts,
+};
+
+// ── Environment Filtering ───────────────────────────────────────────
+
+pub use super::env_filter::{
+    ENV_ALWAYS_ALLOWED, EnvFilter, EnvPolicy, env_pattern_matches, env_var_denied,
+};
+
+// ── Command Risk Classification ─────────────────────────────────────
+
+pub use super::risk::{RiskAssessment, RiskClassifier, RiskLevel, classify_command_risk};
+
 // ── Context Window Suffix ───────────────────────────────────────────
 
 pub use super::suffix::{HARDCODED_SECURITY_SUFFIX, build_ending_security_block};
@@ -4,9 +4,12 @@
 //! and public API documentation.
 
 mod audit;
+mod env_filter;
 mod localgpt;
 mod policy;
 mod protected_files;
+mod risk;
+mod secret_scanner;
 mod signing;
 mod suffix;
 
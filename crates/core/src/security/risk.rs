@@ -0,0 +1,280 @@
+//! Advisory risk classification for shell commands.
+//!
+//! Tags a command as low, medium, or high risk from a ruleset of
+//! destructive verbs, force flags, and recursive deletes. Nothing here
+//! blocks execution — the level is surfaced in tool output and lets the
+//! approval flow ask for confirmation only when a command looks dangerous.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// How dangerous a command looks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of classifying a command.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    /// Highest level among the matched rules (`Low` if none matched).
+    pub level: RiskLevel,
+    /// Why the command was flagged, one entry per matched rule.
+    pub reasons: Vec<String>,
+}
+
+/// Built-in rules as `(level, reason, regex)`.
+const RISK_RULES: &[(RiskLevel, &str, &str)] = &[
+    // High
+    (
+        RiskLevel::High,
+        "recursive delete",
+        r"\brm\s+(?:\S+\s+)*-(?:[a-zA-Z]*[rR][a-zA-Z]*|-recursive)\b",
+    ),
+    (
+        RiskLevel::High,
+        "force push",
+        r"\bgit\s+push\b.*(?:\s--force\b|\s--force-with-lease\b|\s-[a-zA-Z]*f\b|\s\+\S)",
+    ),
+    (
+        RiskLevel::High,
+        "discards uncommitted changes",
+        r"\bgit\s+(?:reset\s+.*--hard\b|clean\s+.*-[a-zA-Z]*f|checkout\s+(?:--\s+)?\.(?:\s|$))",
+    ),
+    (
+        RiskLevel::High,
+        "drops database objects",
+        r"(?i)\b(?:drop\s+(?:table|database|schema|index|view)|truncate\s+table)\b",
+    ),
+    (
+        RiskLevel::High,
+        "writes to a block device",
+        r"\bmkfs(?:\.\w+)?\b|\bdd\b.*\bof=/dev/|>\s*/dev/(?:sd|nvme|disk|hd)",
+    ),
+    (
+        RiskLevel::High,
+        "pipes a downloaded script to a shell",
+        r"\b(?:curl|wget)\b[^|]*\|\s*(?:sudo\s+)?(?:ba|z|da)?sh\b",
+    ),
+    (
+        RiskLevel::High,
+        "shuts down the machine",
+        r"\b(?:shutdown|reboot|halt|poweroff)\b",
+    ),
+    // Medium
+    (
+        RiskLevel::Medium,
+        "deletes files",
+        r"\b(?:rm|rmdir|unlink|shred)\s",
+    ),
+    (RiskLevel::Medium, "deletes rows", r"(?i)\bdelete\s+from\b"),
+    (RiskLevel::Medium, "publishes commits", r"\bgit\s+push\b"),
+    (
+        RiskLevel::Medium,
+        "deletes a branch",
+        r"\bgit\s+branch\s+.*-D\b",
+    ),
+    (
+        RiskLevel::Medium,
+        "publishes a package",
+        r"\b(?:npm|cargo|twine|gem)\s+(?:publish|upload|push)\b",
+    ),
+    (
+        RiskLevel::Medium,
+        "runs with elevated privileges",
+        r"\b(?:sudo|doas)\s",
+    ),
+    (
+        RiskLevel::Medium,
+        "recursive permission change",
+        r"\b(?:chmod|chown|chgrp)\s+(?:\S+\s+)*-[a-zA-Z]*R",
+    ),
+    (
+        RiskLevel::Medium,
+        "kills processes",
+        r"\b(?:kill|pkill|killall)\s",
+    ),
+];
+
+static COMPILED_RULES: Lazy<Vec<(RiskLevel, &'static str, Regex)>> = Lazy::new(|| {
+    RISK_RULES
+        .iter()
+        .map(|(level, reason, pattern)| {
+            (
+                *level,
+                *reason,
+                Regex::new(pattern).expect("valid risk rule"),
+            )
+        })
+        .collect()
+});
+
+/// Classifies commands with the built-in rules plus user-supplied
+/// high-risk patterns.
+#[derive(Debug, Clone, Default)]
+pub struct RiskClassifier {
+    extra_high: Vec<Regex>,
+}
+
+impl RiskClassifier {
+    /// Build a classifier with extra regex patterns that mark a command
+    /// as high risk (e.g. `security.high_risk_patterns` from config).
+    pub fn new(extra_high_patterns: &[String]) -> anyhow::Result<Self> {
+        let extra_high = extra_high_patterns
+            .iter()
+            .map(|p| {
+                Regex::new(p).map_err(|e| anyhow::anyhow!("Invalid risk pattern '{}': {}", p, e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { extra_high })
+    }
+
+    pub fn classify(&self, command: &str) -> RiskAssessment {
+        let mut assessment = classify_command_risk(command);
+        for regex in &self.extra_high {
+            if regex.is_match(command) {
+                assessment.level = RiskLevel::High;
+                assessment
+                    .reasons
+                    .push(format!("matches configured pattern `{}`", regex.as_str()));
+            }
+        }
+        assessment
+    }
+}
+
+/// Classify a command with the built-in rules only.
+pub fn classify_command_risk(command: &str) -> RiskAssessment {
+    let mut assessment = RiskAssessment::default();
+    for (level, reason, regex) in COMPILED_RULES.iter() {
+        if regex.is_match(command) {
+            assessment.level = assessment.level.max(*level);
+            assessment.reasons.push(reason.to_string());
+        }
+    }
+    assessment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harmless_commands_are_low() {
+        for cmd in [
+            "ls -la",
+            "cargo test",
+            "git status",
+            "echo rm",
+            "grep -r foo .",
+        ] {
+            let a = classify_command_risk(cmd);
+            assert_eq!(a.level, RiskLevel::Low, "{cmd}: {:?}", a.reasons);
+            assert!(a.reasons.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_recursive_delete_is_high() {
+        for cmd in [
+            "rm -rf build",
+            "rm -fr /tmp/x",
+            "rm -v -R dir",
+            "rm --recursive dir",
+        ] {
+            assert_eq!(classify_command_risk(cmd).level, RiskLevel::High, "{cmd}");
+        }
+    }
+
+    #[test]
+    fn test_plain_delete_is_medium() {
+        let a = classify_command_risk("rm notes.txt");
+        assert_eq!(a.level, RiskLevel::Medium);
+        assert_eq!(a.reasons, vec!["deletes files"]);
+    }
+
+    #[test]
+    fn test_force_push_is_high() {
+        for cmd in [
+            "git push --force origin main",
+            "git push -f",
+            "git push origin +main",
+            "git push --force-with-lease",
+        ] {
+            let a = classify_command_risk(cmd);
+            assert_eq!(a.level, RiskLevel::High, "{cmd}");
+            assert!(a.reasons.contains(&"force push".to_string()));
+        }
+        assert_eq!(
+            classify_command_risk("git push origin main").level,
+            RiskLevel::Medium
+        );
+    }
+
+    #[test]
+    fn test_sql_and_disk_operations_are_high() {
+        for cmd in [
+            "psql -c 'DROP TABLE users'",
+            "sqlite3 db 'truncate table logs'",
+            "dd if=image.iso of=/dev/sdb",
+            "mkfs.ext4 /dev/sdb1",
+            "curl -sSL https://example.com/install.sh | sh",
+            "git reset --hard HEAD~3",
+        ] {
+            assert_eq!(classify_command_risk(cmd).level, RiskLevel::High, "{cmd}");
+        }
+    }
+
+    #[test]
+    fn test_multiple_reasons_collected() {
+        let a = classify_command_risk("sudo rm -rf /var/lib/app");
+        assert_eq!(a.level, RiskLevel::High);
+        assert!(a.reasons.contains(&"recursive delete".to_string()));
+        assert!(
+            a.reasons
+                .contains(&"runs with elevated privileges".to_string())
+        );
+    }
+
+    #[test]
+    fn test_configured_patterns_raise_to_high() {
+        let classifier = RiskClassifier::new(&["terraform\\s+destroy".to_string()]).unwrap();
+        let a = classifier.classify("terraform destroy -auto-approve");
+        assert_eq!(a.level, RiskLevel::High);
+        assert_eq!(a.reasons.len(), 1);
+
+        assert_eq!(classifier.classify("terraform plan").level, RiskLevel::Low);
+    }
+
+    #[test]
+    fn test_invalid_configured_pattern_rejected() {
+        assert!(RiskClassifier::new(&["(unclosed".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_levels_order() {
+        assert!(RiskLevel::Low < RiskLevel::Medium);
+        assert!(RiskLevel::Medium < RiskLevel::High);
+    }
+}
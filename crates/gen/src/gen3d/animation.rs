@@ -0,0 +1,334 @@
+//! Keyframe animation of an entity's position, rotation, scale or color.
+//!
+//! Each animated entity carries a `KeyframeAnimations` component with one
+//! track per property; starting a track for a property that is already
+//! animated replaces it. Every frame, `animate_entities` samples the tracks
+//! at the elapsed time and writes the result, so time follows the app
+//! clock, including deterministic mode.
+//!
+//! Rotation keyframes are Euler degrees interpolated per axis, so a track
+//! from 0 to 720 spins twice. Color tracks give the entity a material of
+//! its own when they start and then update that material in place, so
+//! entities that shared the original material are unaffected.
+//!
+//! Tracks that don't loop hold their last value and are removed once
+//! finished. Animations are not part of scene snapshots.
+
+use bevy::prelude::*;
+
+use super::commands::{AnimateEntityCmd, AnimatedProperty, AnimationData, Easing, GenResponse};
+use super::registry::NameRegistry;
+
+/// Maximum number of keyframes in one track.
+pub const MAX_KEYFRAMES: usize = 256;
+
+/// Running animation tracks of one entity.
+#[derive(Component, Default)]
+pub struct KeyframeAnimations {
+    tracks: Vec<Track>,
+}
+
+struct Track {
+    property: AnimatedProperty,
+    /// Sorted by time. Unused components are zero.
+    keyframes: Vec<(f32, Vec4)>,
+    easing: Easing,
+    looping: bool,
+    elapsed: f32,
+}
+
+impl Track {
+    fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |(t, _)| *t)
+    }
+
+    fn finished(&self) -> bool {
+        !self.looping && self.elapsed >= self.duration()
+    }
+
+    /// Value at the current elapsed time.
+    fn sample(&self) -> Vec4 {
+        let duration = self.duration();
+        let t = if self.looping && duration > 0.0 {
+            self.elapsed % duration
+        } else {
+            self.elapsed.min(duration)
+        };
+
+        let next = self.keyframes.partition_point(|(kt, _)| *kt <= t);
+        if next == 0 {
+            return self.keyframes[0].1;
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes[next - 1].1;
+        }
+        let (t0, v0) = self.keyframes[next - 1];
+        let (t1, v1) = self.keyframes[next];
+        let s = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+        v0.lerp(v1, ease(self.easing, s))
+    }
+}
+
+/// Map linear progress `s` in 0..=1 through an easing curve.
+fn ease(easing: Easing, s: f32) -> f32 {
+    match easing {
+        Easing::Linear => s,
+        Easing::EaseIn => s * s * s,
+        Easing::EaseOut => 1.0 - (1.0 - s).powi(3),
+        Easing::EaseInOut => s * s * (3.0 - 2.0 * s),
+        Easing::Step => {
+            if s >= 1.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+pub fn start_animation(world: &mut World, cmd: AnimateEntityCmd) -> GenResponse {
+    let Some(entity) = world.resource::<NameRegistry>().get_entity(&cmd.name) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' not found", cmd.name),
+        };
+    };
+    if cmd.keyframes.len() < 2 || cmd.keyframes.len() > MAX_KEYFRAMES {
+        return GenResponse::Error {
+            message: format!("Between 2 and {} keyframes are required", MAX_KEYFRAMES),
+        };
+    }
+
+    let mut keyframes = Vec::with_capacity(cmd.keyframes.len());
+    for keyframe in &cmd.keyframes {
+        if !keyframe.t_seconds.is_finite() || keyframe.t_seconds < 0.0 {
+            return GenResponse::Error {
+                message: format!(
+                    "Keyframe time {} must be zero or positive",
+                    keyframe.t_seconds
+                ),
+            };
+        }
+        let value = match (cmd.property, keyframe.value.as_slice()) {
+            (AnimatedProperty::Color, &[r, g, b]) => Vec4::new(r, g, b, 1.0),
+            (AnimatedProperty::Color, &[r, g, b, a]) => Vec4::new(r, g, b, a),
+            (AnimatedProperty::Color, _) => {
+                return GenResponse::Error {
+                    message: "Color keyframes need [r, g, b] or [r, g, b, a]".to_string(),
+                };
+            }
+            (_, &[x, y, z]) => Vec4::new(x, y, z, 0.0),
+            (property, _) => {
+                return GenResponse::Error {
+                    message: format!("{:?} keyframes need [x, y, z]", property),
+                };
+            }
+        };
+        keyframes.push((keyframe.t_seconds, value));
+    }
+    keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let track = Track {
+        property: cmd.property,
+        keyframes,
+        easing: cmd.easing,
+        looping: cmd.looping,
+        elapsed: 0.0,
+    };
+    let duration = track.duration();
+    if duration <= 0.0 {
+        return GenResponse::Error {
+            message: "Keyframes must span a positive duration".to_string(),
+        };
+    }
+
+    if cmd.property == AnimatedProperty::Color {
+        // Animate a copy so entities sharing the material keep their color
+        let Some(handle) = world
+            .get::<MeshMaterial3d<StandardMaterial>>(entity)
+            .map(|m| m.0.clone())
+        else {
+            return GenResponse::Error {
+                message: format!("Entity '{}' has no material to animate", cmd.name),
+            };
+        };
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let Some(copy) = materials.get(&handle).cloned() else {
+            return GenResponse::Error {
+                message: format!("Entity '{}' has no material to animate", cmd.name),
+            };
+        };
+        let copy = materials.add(copy);
+        world.entity_mut(entity).insert(MeshMaterial3d(copy));
+    }
+
+    let mut entity_mut = world.entity_mut(entity);
+    let mut animations = entity_mut.take::<KeyframeAnimations>().unwrap_or_default();
+    animations.tracks.retain(|t| t.property != cmd.property);
+    animations.tracks.push(track);
+    entity_mut.insert(animations);
+
+    GenResponse::Animated(AnimationData {
+        name: cmd.name,
+        property: cmd.property,
+        duration_seconds: duration,
+        keyframes: cmd.keyframes.len(),
+        looping: cmd.looping,
+        easing: cmd.easing,
+    })
+}
+
+pub fn stop_animation(world: &mut World, name: String) -> GenResponse {
+    let Some(entity) = world.resource::<NameRegistry>().get_entity(&name) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' not found", name),
+        };
+    };
+    let stopped = world
+        .entity_mut(entity)
+        .take::<KeyframeAnimations>()
+        .map_or(0, |a| a.tracks.len());
+    if stopped == 0 {
+        return GenResponse::Error {
+            message: format!("Entity '{}' is not animated", name),
+        };
+    }
+    GenResponse::AnimationStopped { name, stopped }
+}
+
+/// Advance every track and apply its value.
+pub fn animate_entities(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut animated: Query<(
+        Entity,
+        &mut KeyframeAnimations,
+        &mut Transform,
+        Option<&MeshMaterial3d<StandardMaterial>>,
+    )>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut animations, mut transform, material) in &mut animated {
+        for track in &mut animations.tracks {
+            track.elapsed += delta;
+            let value = track.sample();
+            match track.property {
+                AnimatedProperty::Position => transform.translation = value.truncate(),
+                AnimatedProperty::Rotation => {
+                    transform.rotation = Quat::from_euler(
+                        EulerRot::XYZ,
+                        value.x.to_radians(),
+                        value.y.to_radians(),
+                        value.z.to_radians(),
+                    )
+                }
+                AnimatedProperty::Scale => transform.scale = value.truncate(),
+                AnimatedProperty::Color => {
+                    if let Some(material) = material.and_then(|m| materials.get_mut(&m.0)) {
+                        material.base_color = Color::srgba(value.x, value.y, value.z, value.w);
+                    }
+                }
+            }
+        }
+
+        animations.tracks.retain(|t| !t.finished());
+        if animations.tracks.is_empty() {
+            commands.entity(entity).remove::<KeyframeAnimations>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(keyframes: &[(f32, f32)], easing: Easing, looping: bool, elapsed: f32) -> Track {
+        Track {
+            property: AnimatedProperty::Position,
+            keyframes: keyframes
+                .iter()
+                .map(|&(t, v)| (t, Vec4::splat(v)))
+                .collect(),
+            easing,
+            looping,
+            elapsed,
+        }
+    }
+
+    #[test]
+    fn sample_before_first_key_holds_its_value() {
+        let t = track(&[(1.0, 2.0), (2.0, 4.0)], Easing::Linear, false, 0.0);
+        assert_eq!(t.sample(), Vec4::splat(2.0));
+        assert!(!t.finished());
+    }
+
+    #[test]
+    fn sample_after_last_key_holds_its_value() {
+        let t = track(&[(0.0, 2.0), (2.0, 4.0)], Easing::Linear, false, 5.0);
+        assert_eq!(t.sample(), Vec4::splat(4.0));
+        assert!(t.finished());
+    }
+
+    #[test]
+    fn sample_single_key() {
+        for looping in [false, true] {
+            for elapsed in [0.0, 0.5, 3.0] {
+                let t = track(&[(1.0, 7.0)], Easing::Linear, looping, elapsed);
+                assert_eq!(t.sample(), Vec4::splat(7.0));
+            }
+        }
+        let t = track(&[(0.0, 7.0)], Easing::Linear, true, 2.0);
+        assert_eq!(t.sample(), Vec4::splat(7.0));
+        assert!(!t.finished());
+        assert!(track(&[(0.0, 7.0)], Easing::Linear, false, 0.0).finished());
+    }
+
+    #[test]
+    fn sample_interpolates_between_keys() {
+        let keys = [(0.0, 0.0), (2.0, 4.0), (4.0, 0.0)];
+        assert_eq!(
+            track(&keys, Easing::Linear, false, 1.0).sample(),
+            Vec4::splat(2.0)
+        );
+        assert_eq!(
+            track(&keys, Easing::Linear, false, 2.0).sample(),
+            Vec4::splat(4.0)
+        );
+        assert_eq!(
+            track(&keys, Easing::Linear, false, 3.0).sample(),
+            Vec4::splat(2.0)
+        );
+        assert_eq!(
+            track(&keys, Easing::Step, false, 1.9).sample(),
+            Vec4::splat(0.0)
+        );
+        assert_eq!(
+            track(&keys, Easing::Step, false, 2.0).sample(),
+            Vec4::splat(4.0)
+        );
+    }
+
+    #[test]
+    fn looping_tracks_wrap_around() {
+        let t = track(&[(0.0, 0.0), (2.0, 4.0)], Easing::Linear, true, 5.0);
+        assert_eq!(t.sample(), Vec4::splat(2.0));
+        assert!(!t.finished());
+    }
+
+    #[test]
+    fn easing_curves_hit_their_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::Step,
+        ] {
+            assert_eq!(ease(easing, 0.0), 0.0, "{:?}", easing);
+            assert_eq!(ease(easing, 1.0), 1.0, "{:?}", easing);
+        }
+        assert!(ease(Easing::EaseIn, 0.5) < 0.5);
+        assert!(ease(Easing::EaseOut, 0.5) > 0.5);
+        assert_eq!(ease(Easing::EaseInOut, 0.5), 0.5);
+    }
+}
@@ -11,6 +11,7 @@ use std::collections::HashMap;
 pub enum GenCommand {
     // Tier 1: Perceive
     SceneInfo,
+    SceneTree,
     Screenshot {
         width: u32,
         height: u32,
@@ -19,6 +20,13 @@ pub enum GenCommand {
     EntityInfo {
         name: String,
     },
+    EntitiesInRegion {
+        center: [f32; 3],
+        radius: f32,
+    },
+    EntitiesByTag {
+        tag: String,
+    },
 
     // Tier 2: Mutate
     SpawnPrimitive(SpawnPrimitiveCmd),
@@ -29,9 +37,59 @@ pub enum GenCommand {
     SetCamera(CameraCmd),
     SetLight(SetLightCmd),
     SetEnvironment(EnvironmentCmd),
+    SetQuality {
+        level: QualityLevel,
+    },
+    SetInteractiveCamera {
+        enabled: bool,
+    },
+    /// Move the camera, keeping its view direction, so the named entities
+    /// (or every non-camera, non-light entity) fit in view. `padding` is
+    /// extra margin as a fraction of the scene's bounding radius.
+    FrameScene {
+        padding: f32,
+        entities: Option<Vec<String>>,
+    },
+    /// Omitted fields keep their current value, so an empty command
+    /// just reports the mode.
+    SetDeterministicMode {
+        enabled: Option<bool>,
+        frame_rate: Option<f32>,
+    },
 
     // Tier 3: Advanced
     SpawnMesh(RawMeshCmd),
+    SpawnTerrain(SpawnTerrainCmd),
+    SpawnInstanced(SpawnInstancedCmd),
+    SpawnText3D(SpawnText3DCmd),
+    /// Copy an entity's transform, mesh and material. With `count` > 1
+    /// the copies are named `new_name_1..n`, each `offset` further on.
+    DuplicateEntity {
+        source: String,
+        new_name: String,
+        offset: [f32; 3],
+        count: Option<u32>,
+    },
+    AnimateEntity(AnimateEntityCmd),
+    SetMaterial(SetMaterialCmd),
+    /// Find the nearest mesh surface along a ray.
+    Raycast {
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+    },
+    /// Stop every animation on the entity, leaving it where it is.
+    StopAnimation {
+        name: String,
+    },
+    WeldMesh(WeldMeshCmd),
+    SnapshotScene {
+        label: String,
+    },
+    RestoreSnapshot {
+        label: String,
+    },
+    ListSnapshots,
 
     // Tier 4: Export
     ExportScreenshot {
@@ -39,6 +97,15 @@ pub enum GenCommand {
         width: u32,
         height: u32,
     },
+    /// Write the visible meshes to a Wavefront `.obj`, plus a `.mtl` beside
+    /// it when `export_materials` is set. Without a path, writes to a
+    /// temporary file.
+    ExportObj {
+        path: Option<String>,
+        export_materials: bool,
+    },
+    SetExportSettings(ExportSettings),
+    OrbitLight(OrbitLightCmd),
 }
 
 // ---------------------------------------------------------------------------
@@ -66,6 +133,10 @@ pub struct SpawnPrimitiveCmd {
     #[serde(default)]
     pub emissive: [f32; 4],
     pub parent: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -77,6 +148,10 @@ pub enum PrimitiveShape {
     Capsule,
     Torus,
     Plane,
+    /// Right-triangular prism: a ramp rising toward -Z.
+    Wedge,
+    /// Sphere built by subdividing an icosahedron.
+    Icosphere,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +166,14 @@ pub struct ModifyEntityCmd {
     pub emissive: Option<[f32; 4]>,
     pub visible: Option<bool>,
     pub parent: Option<Option<String>>,
+    /// Replaces the entity's tags.
+    pub tags: Option<Vec<String>>,
+    /// Merged into the entity's metadata; an empty value removes the key.
+    pub metadata: Option<HashMap<String, String>>,
+    /// Whether the entity casts shadows onto others.
+    pub casts_shadows: Option<bool>,
+    /// Whether shadows from other entities fall on this one.
+    pub receives_shadows: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,6 +216,91 @@ pub struct EnvironmentCmd {
     pub ambient_color: Option<[f32; 4]>,
 }
 
+/// Render quality preset, trading frame time for fidelity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityLevel {
+    /// Default pipeline: no SSAO, standard shadow map, MSAA.
+    #[default]
+    Low,
+    /// SSAO and sharper shadows.
+    Medium,
+    /// High-quality SSAO, largest shadow maps, filmic tone mapping.
+    High,
+}
+
+impl QualityLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QualityLevel::Low => "low",
+            QualityLevel::Medium => "medium",
+            QualityLevel::High => "high",
+        }
+    }
+
+    /// MSAA samples the preset renders with. SSAO (medium and high)
+    /// requires MSAA off.
+    pub fn msaa_samples(&self) -> u32 {
+        match self {
+            QualityLevel::Low => 4,
+            QualityLevel::Medium | QualityLevel::High => 1,
+        }
+    }
+
+    pub fn tonemapping(&self) -> TonemappingMode {
+        match self {
+            QualityLevel::Low | QualityLevel::Medium => TonemappingMode::TonyMcMapface,
+            QualityLevel::High => TonemappingMode::AcesFitted,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrbitLightCmd {
+    pub name: String,
+    #[serde(default = "default_orbit_frames")]
+    pub frames: u32,
+    /// Horizontal distance from the scene center (default: the light's current one).
+    pub radius: Option<f32>,
+    /// Height above the scene center (default: the light's current one).
+    pub height: Option<f32>,
+    pub output_dir: String,
+}
+
+/// Antialiasing and color management for screenshots and exports.
+/// Unset fields follow the active quality preset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportSettings {
+    /// 1 (off), 2, 4 or 8.
+    pub msaa_samples: Option<u32>,
+    pub tonemapping: Option<TonemappingMode>,
+    #[serde(default)]
+    pub color_space: ExportColorSpace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TonemappingMode {
+    None,
+    Reinhard,
+    ReinhardLuminance,
+    AcesFitted,
+    Agx,
+    TonyMcMapface,
+    BlenderFilmic,
+}
+
+/// Encoding of exported pixel values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportColorSpace {
+    /// Gamma-encoded sRGB, for viewing and the web.
+    #[default]
+    Srgb,
+    /// Linear values, for compositing.
+    Linear,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawMeshCmd {
     pub name: String,
@@ -150,21 +318,190 @@ pub struct RawMeshCmd {
     pub position: [f32; 3],
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeldMeshCmd {
+    pub name: String,
+    /// Vertices closer than this (world units) are merged.
+    #[serde(default = "default_weld_tolerance")]
+    pub tolerance: f32,
+    /// Keep vertices with different UVs apart so texture seams survive.
+    #[serde(default = "default_true")]
+    pub preserve_uv_seams: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnTerrainCmd {
+    pub name: String,
+    /// Extent in world units along X and Z.
+    #[serde(default = "default_terrain_size")]
+    pub size: [f32; 2],
+    /// Quads per side (capped at `MAX_TERRAIN_RESOLUTION`).
+    #[serde(default = "default_terrain_resolution")]
+    pub resolution: u32,
+    pub heightmap: HeightmapSource,
+    #[serde(default)]
+    pub position: [f32; 3],
+    #[serde(default = "default_terrain_color")]
+    pub color: [f32; 4],
+    #[serde(default = "default_terrain_roughness")]
+    pub roughness: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnText3DCmd {
+    pub name: String,
+    /// Label text; newlines start new lines.
+    pub text: String,
+    /// Glyph size in world units, roughly the height of one line.
+    #[serde(default = "default_text_font_size")]
+    pub font_size: f32,
+    #[serde(default = "default_white")]
+    pub color: [f32; 4],
+    #[serde(default = "default_position")]
+    pub position: [f32; 3],
+    /// Orientation of the text, which faces +Z. Ignored when billboarded.
+    #[serde(default)]
+    pub rotation_degrees: [f32; 3],
+    /// Keep the text turned toward the camera.
+    #[serde(default)]
+    pub billboard: bool,
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMaterialCmd {
+    pub name: String,
+    /// Image paths; omitted textures keep the current material's.
+    pub base_color_texture: Option<String>,
+    pub normal_texture: Option<String>,
+    /// glTF layout: roughness in green, metallic in blue.
+    pub metallic_roughness_texture: Option<String>,
+    /// Texture repeats across the UV range, [u, v].
+    #[serde(default = "default_tiling")]
+    pub tiling: [f32; 2],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimateEntityCmd {
+    pub name: String,
+    pub property: AnimatedProperty,
+    /// At least two, in any order.
+    pub keyframes: Vec<Keyframe>,
+    /// Restart from the first keyframe after the last one.
+    #[serde(default, rename = "loop")]
+    pub looping: bool,
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub t_seconds: f32,
+    /// [x, y, z] for position and scale, Euler degrees for rotation,
+    /// RGB or RGBA for color.
+    pub value: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimatedProperty {
+    Position,
+    Rotation,
+    Scale,
+    Color,
+}
+
+/// How values move between two keyframes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Jump to each keyframe's value when it is reached.
+    Step,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnInstancedCmd {
+    /// Existing entity whose mesh and material are copied.
+    pub source: String,
+    pub name: String,
+    /// Copies along [x, y, z].
+    pub grid: [u32; 3],
+    #[serde(default = "default_scale")]
+    pub spacing: [f32; 3],
+    /// Random displacement per copy, as a fraction (0–1) of `spacing`.
+    #[serde(default)]
+    pub jitter: f32,
+    #[serde(default)]
+    pub seed: u32,
+    #[serde(default = "default_position")]
+    pub position: [f32; 3],
+}
+
+/// Where terrain heights come from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HeightmapSource {
+    /// Fractal Perlin noise.
+    Noise {
+        #[serde(default)]
+        seed: u32,
+        #[serde(default = "default_octaves")]
+        octaves: u32,
+        #[serde(default = "default_amplitude")]
+        amplitude: f32,
+        /// Noise features per world unit.
+        #[serde(default = "default_frequency")]
+        frequency: f32,
+    },
+    /// Grayscale image; black is 0, white is `amplitude`.
+    Image {
+        path: String,
+        #[serde(default = "default_amplitude")]
+        amplitude: f32,
+    },
+}
+
 // ---------------------------------------------------------------------------
 // Responses (Bevy → agent)
 // ---------------------------------------------------------------------------
 
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)] // One response per command; not worth boxing
 pub enum GenResponse {
     SceneInfo(SceneInfoData),
+    SceneTree(SceneTreeData),
     Screenshot { image_path: String },
     EntityInfo(EntityInfoData),
+    EntitiesInRegion(RegionInfoData),
+    EntitiesByTag(TaggedEntitiesData),
     Spawned { name: String, entity_id: u64 },
+    TerrainSpawned(TerrainInfoData),
+    InstancedSpawned(InstancedInfoData),
+    Duplicated(DuplicatedData),
+    Animated(AnimationData),
+    AnimationStopped { name: String, stopped: usize },
+    MaterialSet { name: String },
+    Raycast(RaycastData),
+    MeshWelded(WeldMeshData),
     Modified { name: String },
     Deleted { name: String },
     CameraSet,
     LightSet { name: String },
     EnvironmentSet,
+    QualitySet { level: QualityLevel },
+    ExportSettingsSet(ExportSettingsData),
+    LightOrbited(LightOrbitData),
+    InteractiveCamera(InteractiveCameraData),
+    SceneFramed(FrameSceneData),
+    DeterministicMode(DeterministicModeData),
+    SnapshotSaved(SnapshotInfo),
+    SnapshotRestored(SnapshotInfo),
+    Snapshots { snapshots: Vec<SnapshotInfo> },
     Exported { path: String },
     Error { message: String },
 }
@@ -185,6 +522,185 @@ pub struct EntitySummary {
     pub color: Option<[f32; 4]>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneTreeData {
+    pub entity_count: usize,
+    /// Top-level entities (no named parent), sorted by name.
+    pub roots: Vec<SceneTreeNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneTreeNode {
+    pub name: String,
+    pub entity_type: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SceneTreeNode>,
+    /// Children without a registered name (e.g. copies in an instanced field).
+    #[serde(skip_serializing_if = "is_zero")]
+    pub unnamed_children: usize,
+}
+
+impl SceneTreeNode {
+    /// Render as an indented outline, one entity per line.
+    pub fn write_outline(&self, depth: usize, out: &mut String) {
+        out.push_str(&format!(
+            "{}{} ({})",
+            "  ".repeat(depth),
+            self.name,
+            self.entity_type
+        ));
+        if self.unnamed_children > 0 {
+            out.push_str(&format!(" [+{} unnamed]", self.unnamed_children));
+        }
+        out.push('\n');
+        for child in &self.children {
+            child.write_outline(depth + 1, out);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionInfoData {
+    pub center: [f32; 3],
+    pub radius: f32,
+    pub entity_count: usize,
+    /// Entities whose bounding box intersects the sphere, nearest first.
+    pub entities: Vec<RegionEntitySummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionEntitySummary {
+    #[serde(flatten)]
+    pub summary: EntitySummary,
+    /// Distance from the region center to the entity's world position.
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaggedEntitiesData {
+    pub tag: String,
+    pub entity_count: usize,
+    /// Entities carrying the tag, sorted by name.
+    pub entities: Vec<EntitySummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrainInfoData {
+    pub name: String,
+    pub entity_id: u64,
+    /// Effective quads per side after capping.
+    pub resolution: u32,
+    pub triangle_count: usize,
+    pub min_height: f32,
+    pub max_height: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeldMeshData {
+    pub name: String,
+    pub vertices_before: usize,
+    pub vertices_after: usize,
+    pub triangles_before: usize,
+    /// Lower than before when welding collapsed degenerate triangles.
+    pub triangles_after: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstancedInfoData {
+    pub name: String,
+    pub entity_id: u64,
+    pub source: String,
+    pub instance_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatedData {
+    pub source: String,
+    /// Created entities, in offset order.
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationData {
+    pub name: String,
+    pub property: AnimatedProperty,
+    /// Time of the last keyframe; one cycle when looping.
+    pub duration_seconds: f32,
+    pub keyframes: usize,
+    #[serde(rename = "loop")]
+    pub looping: bool,
+    pub easing: Easing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaycastData {
+    /// `None` if nothing was hit within `max_distance`.
+    pub hit: Option<RayHit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RayHit {
+    /// Registered entity hit, or the registered ancestor of the mesh hit.
+    pub name: String,
+    pub point: [f32; 3],
+    /// Unit surface normal, facing back toward the ray origin.
+    pub normal: [f32; 3],
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightOrbitData {
+    pub name: String,
+    /// Point the light circled and aimed at: the mean position of the
+    /// scene's non-light, non-camera entities.
+    pub center: [f32; 3],
+    pub radius: f32,
+    pub height: f32,
+    /// One screenshot per step, in orbit order.
+    pub paths: Vec<String>,
+}
+
+/// Export settings in effect, with preset defaults resolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSettingsData {
+    pub msaa_samples: u32,
+    pub tonemapping: TonemappingMode,
+    pub color_space: ExportColorSpace,
+    /// Quality preset the unset fields were taken from.
+    pub quality: QualityLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractiveCameraData {
+    pub enabled: bool,
+    /// Camera pose at the time of the command, in `gen_set_camera` terms.
+    pub pose: CameraCmd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameSceneData {
+    /// The chosen camera pose, in `gen_set_camera` terms.
+    pub pose: CameraCmd,
+    /// Number of entities framed.
+    pub entities: usize,
+    /// Bounding radius fitted to the view, padding included.
+    pub radius: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeterministicModeData {
+    pub enabled: bool,
+    /// Simulated frames per second; each frame advances time by exactly
+    /// `1 / frame_rate` while enabled.
+    pub frame_rate: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub label: String,
+    pub entity_count: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityInfoData {
     pub name: String,
@@ -200,14 +716,27 @@ pub struct EntityInfoData {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roughness: Option<f32>,
     pub visible: bool,
+    pub casts_shadows: bool,
+    pub receives_shadows: bool,
+    /// World-space bounds of the rendered mesh (of the children's meshes
+    /// for groups); a point at the entity's position if it has none.
+    pub aabb_min: [f32; 3],
+    pub aabb_max: [f32; 3],
     pub children: Vec<String>,
     pub parent: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
 }
 
 // ---------------------------------------------------------------------------
 // Default helpers
 // ---------------------------------------------------------------------------
 
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
 fn default_position() -> [f32; 3] {
     [0.0, 0.0, 0.0]
 }
@@ -238,3 +767,36 @@ fn default_intensity() -> f32 {
 fn default_true() -> bool {
     true
 }
+fn default_orbit_frames() -> u32 {
+    8
+}
+fn default_weld_tolerance() -> f32 {
+    1e-4
+}
+fn default_tiling() -> [f32; 2] {
+    [1.0, 1.0]
+}
+fn default_text_font_size() -> f32 {
+    0.5
+}
+fn default_terrain_size() -> [f32; 2] {
+    [20.0, 20.0]
+}
+fn default_terrain_resolution() -> u32 {
+    128
+}
+fn default_terrain_color() -> [f32; 4] {
+    [0.35, 0.5, 0.3, 1.0]
+}
+fn default_terrain_roughness() -> f32 {
+    0.9
+}
+fn default_octaves() -> u32 {
+    4
+}
+fn default_amplitude() -> f32 {
+    2.0
+}
+fn default_frequency() -> f32 {
+    0.1
+}
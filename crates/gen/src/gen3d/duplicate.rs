@@ -0,0 +1,147 @@
+//! Entity duplication — independent copies of a registered entity.
+//!
+//! Each copy is a new registered entity sharing the source's mesh and
+//! material handles, so materials match exactly. Transform, visibility,
+//! parent, tags, shadow flags and light settings are copied as well.
+//! Instanced fields rebuild their instances, and text labels get a
+//! renderer of their own. Registered children are not copied.
+
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+
+use super::commands::{DuplicatedData, GenResponse};
+use super::instancing::InstancedField;
+use super::registry::{GenEntity, GenEntityType, GenTags, NameRegistry};
+use super::text3d::Text3dLabel;
+
+/// Maximum number of copies made by one command.
+pub const MAX_DUPLICATES: u32 = 1000;
+
+pub fn duplicate_entity(
+    world: &mut World,
+    source: String,
+    new_name: String,
+    offset: [f32; 3],
+    count: Option<u32>,
+) -> GenResponse {
+    let count = count.unwrap_or(1);
+    if count == 0 || count > MAX_DUPLICATES {
+        return GenResponse::Error {
+            message: format!("count must be between 1 and {}", MAX_DUPLICATES),
+        };
+    }
+
+    let registry = world.resource::<NameRegistry>();
+    let Some(source_entity) = registry.get_entity(&source) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' not found", source),
+        };
+    };
+    // A single copy takes `new_name` as is; several are numbered from 1
+    let names: Vec<String> = if count == 1 {
+        vec![new_name]
+    } else {
+        (1..=count).map(|i| format!("{}_{}", new_name, i)).collect()
+    };
+    if let Some(taken) = names.iter().find(|name| registry.contains_name(name)) {
+        return GenResponse::Error {
+            message: format!("Entity '{}' already exists", taken),
+        };
+    }
+
+    let source_ref = world.entity(source_entity);
+    let Some(entity_type) = source_ref.get::<GenEntity>().map(|g| g.entity_type) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' not found", source),
+        };
+    };
+    if entity_type == GenEntityType::Camera {
+        return GenResponse::Error {
+            message: "Cameras can't be duplicated".to_string(),
+        };
+    }
+    let transform = source_ref.get::<Transform>().copied().unwrap_or_default();
+    let visibility = source_ref.get::<Visibility>().copied();
+    let parent = source_ref.get::<Parent>().map(|p| p.get());
+    let mesh = source_ref.get::<Mesh3d>().cloned();
+    let material = source_ref
+        .get::<MeshMaterial3d<StandardMaterial>>()
+        .cloned();
+    let tags = source_ref.get::<GenTags>().cloned();
+    let casts_shadows = !source_ref.contains::<NotShadowCaster>();
+    let receives_shadows = !source_ref.contains::<NotShadowReceiver>();
+    let directional = source_ref.get::<DirectionalLight>().cloned();
+    let point = source_ref.get::<PointLight>().cloned();
+    let spot = source_ref.get::<SpotLight>().cloned();
+    let field = source_ref.get::<InstancedField>().cloned();
+    let label = source_ref.get::<Text3dLabel>().cloned();
+
+    let offset = Vec3::from_array(offset);
+    for (i, name) in names.iter().enumerate() {
+        // A label's copy needs its own texture, and a material showing it
+        let label_copy = label
+            .as_ref()
+            .map(|label| label.duplicate(world, material.as_ref()));
+
+        let mut entity = world.spawn((
+            Transform {
+                translation: transform.translation + offset * (i + 1) as f32,
+                ..transform
+            },
+            Name::new(name.clone()),
+            GenEntity { entity_type },
+        ));
+        if let Some(visibility) = visibility {
+            entity.insert(visibility);
+        }
+        if let Some(ref mesh) = mesh {
+            entity.insert(mesh.clone());
+        }
+        match label_copy {
+            Some((_, Some(ref label_material))) => {
+                entity.insert(label_material.clone());
+            }
+            _ => {
+                if let Some(ref material) = material {
+                    entity.insert(material.clone());
+                }
+            }
+        }
+        if let Some(ref tags) = tags {
+            entity.insert(tags.clone());
+        }
+        if !casts_shadows {
+            entity.insert(NotShadowCaster);
+        }
+        if !receives_shadows {
+            entity.insert(NotShadowReceiver);
+        }
+        if let Some(ref light) = directional {
+            entity.insert(light.clone());
+        }
+        if let Some(ref light) = point {
+            entity.insert(*light);
+        }
+        if let Some(ref light) = spot {
+            entity.insert(*light);
+        }
+
+        let id = entity.id();
+        if let Some(ref field) = field {
+            field.spawn_instances(world, id);
+            world.entity_mut(id).insert(field.clone());
+        }
+        if let Some((label, _)) = label_copy {
+            label.spawn_renderer(world, id);
+            world.entity_mut(id).insert(label);
+        }
+        if let Some(parent) = parent {
+            world.entity_mut(id).set_parent(parent);
+        }
+        world
+            .resource_mut::<NameRegistry>()
+            .insert(name.clone(), id);
+    }
+
+    GenResponse::Duplicated(DuplicatedData { source, names })
+}
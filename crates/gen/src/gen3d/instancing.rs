@@ -0,0 +1,318 @@
+//! Instanced fields — many copies of one mesh laid out on a grid.
+//!
+//! The copies are baked into one static mesh, so the whole field is a
+//! single registered entity (a group) with one unregistered child drawing
+//! that mesh with the source's material: one entity and one draw call no
+//! matter how many copies. Moving, rotating, scaling or hiding the field
+//! entity affects every copy; individual copies can't be edited.
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+
+use super::commands::{GenResponse, InstancedInfoData, SpawnInstancedCmd};
+use super::registry::{GenEntity, GenEntityType, NameRegistry};
+
+/// Maximum number of instances in one field.
+pub const MAX_INSTANCES: u32 = 50_000;
+
+/// Maximum vertices in a field's baked mesh, bounding its memory use for
+/// detailed source meshes.
+pub const MAX_FIELD_VERTICES: usize = 2_000_000;
+
+/// A field's baked mesh and material. Kept on the field entity so
+/// snapshots and duplicates can rebuild its child.
+#[derive(Component, Clone)]
+pub struct InstancedField {
+    pub mesh: Mesh3d,
+    pub material: MeshMaterial3d<StandardMaterial>,
+}
+
+impl InstancedField {
+    /// Spawn the child drawing the baked mesh under `parent`.
+    pub fn spawn_instances(&self, world: &mut World, parent: Entity) {
+        let child = world
+            .spawn((
+                self.mesh.clone(),
+                self.material.clone(),
+                Transform::default(),
+            ))
+            .id();
+        world.entity_mut(parent).add_child(child);
+    }
+}
+
+pub fn spawn_instanced(world: &mut World, cmd: SpawnInstancedCmd) -> GenResponse {
+    let registry = world.resource::<NameRegistry>();
+    if registry.contains_name(&cmd.name) {
+        return GenResponse::Error {
+            message: format!("Entity '{}' already exists", cmd.name),
+        };
+    }
+    let Some(source) = registry.get_entity(&cmd.source) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' not found", cmd.source),
+        };
+    };
+
+    let source_ref = world.entity(source);
+    let (Some(source_mesh), Some(material)) = (
+        source_ref.get::<Mesh3d>().cloned(),
+        source_ref
+            .get::<MeshMaterial3d<StandardMaterial>>()
+            .cloned(),
+    ) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' has no mesh to instance", cmd.source),
+        };
+    };
+    // Copies keep the source's own rotation and scale; the grid sets position.
+    let source_transform = source_ref.get::<Transform>().copied().unwrap_or_default();
+
+    let count = cmd
+        .grid
+        .iter()
+        .try_fold(1u32, |acc, &n| acc.checked_mul(n))
+        .filter(|&n| n <= MAX_INSTANCES);
+    let count = match count {
+        Some(0) => {
+            return GenResponse::Error {
+                message: "Grid dimensions must all be at least 1".to_string(),
+            };
+        }
+        Some(n) => n,
+        None => {
+            return GenResponse::Error {
+                message: format!(
+                    "Grid {:?} exceeds the maximum of {} instances",
+                    cmd.grid, MAX_INSTANCES
+                ),
+            };
+        }
+    };
+
+    let offsets: Vec<Transform> = grid_offsets(cmd.grid, cmd.spacing, cmd.jitter, cmd.seed)
+        .map(|translation| Transform {
+            translation,
+            ..source_transform
+        })
+        .collect();
+    let merged = match world.resource::<Assets<Mesh>>().get(&source_mesh.0) {
+        Some(mesh) => merge_copies(mesh, &offsets),
+        None => Err(format!("Entity '{}' has no loaded mesh", cmd.source)),
+    };
+    let merged = match merged {
+        Ok(merged) => merged,
+        Err(message) => return GenResponse::Error { message },
+    };
+    let field = InstancedField {
+        mesh: Mesh3d(world.resource_mut::<Assets<Mesh>>().add(merged)),
+        material,
+    };
+
+    let entity = world
+        .spawn((
+            Transform::from_translation(Vec3::from_array(cmd.position)),
+            Visibility::default(),
+            Name::new(cmd.name.clone()),
+            GenEntity {
+                entity_type: GenEntityType::Group,
+            },
+        ))
+        .id();
+    field.spawn_instances(world, entity);
+    world.entity_mut(entity).insert(field);
+    world
+        .resource_mut::<NameRegistry>()
+        .insert(cmd.name.clone(), entity);
+
+    GenResponse::InstancedSpawned(InstancedInfoData {
+        name: cmd.name,
+        entity_id: entity.to_bits(),
+        source: cmd.source,
+        instance_count: count as usize,
+    })
+}
+
+/// Bake a copy of the triangle-list mesh `source` at each of `transforms`
+/// into one mesh. Positions, normals and the first UV set carry over;
+/// normals go through the inverse transpose so non-uniform scale keeps
+/// them perpendicular.
+pub fn merge_copies(source: &Mesh, transforms: &[Transform]) -> Result<Mesh, String> {
+    if source.primitive_topology() != PrimitiveTopology::TriangleList {
+        return Err("Only triangle-list meshes can be instanced".to_string());
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        source.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return Err("Mesh has no vertex positions".to_string());
+    };
+    let normals = match source.attribute(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) if normals.len() == positions.len() => {
+            Some(normals)
+        }
+        _ => None,
+    };
+    let uvs = match source.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) if uvs.len() == positions.len() => Some(uvs),
+        _ => None,
+    };
+    let indices: Vec<u32> = match source.indices() {
+        Some(indices) => indices.iter().map(|i| i as u32).collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    let vertices = positions.len().saturating_mul(transforms.len());
+    if vertices > MAX_FIELD_VERTICES {
+        return Err(format!(
+            "Field would have {} vertices (max {}); use fewer copies or a simpler mesh",
+            vertices, MAX_FIELD_VERTICES
+        ));
+    }
+
+    let mut merged_positions = Vec::with_capacity(vertices);
+    let mut merged_normals = Vec::with_capacity(if normals.is_some() { vertices } else { 0 });
+    let mut merged_uvs = Vec::with_capacity(if uvs.is_some() { vertices } else { 0 });
+    let mut merged_indices = Vec::with_capacity(indices.len() * transforms.len());
+    for transform in transforms {
+        let matrix = transform.compute_matrix();
+        let base = merged_positions.len() as u32;
+        merged_positions.extend(
+            positions
+                .iter()
+                .map(|&p| matrix.transform_point3(Vec3::from_array(p)).to_array()),
+        );
+        if let Some(normals) = normals {
+            let normal_matrix = matrix.inverse().transpose();
+            merged_normals.extend(normals.iter().map(|&n| {
+                normal_matrix
+                    .transform_vector3(Vec3::from_array(n))
+                    .normalize_or_zero()
+                    .to_array()
+            }));
+        }
+        if let Some(uvs) = uvs {
+            merged_uvs.extend_from_slice(uvs);
+        }
+        merged_indices.extend(indices.iter().map(|&i| base + i));
+    }
+
+    let mut merged = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    merged.insert_attribute(Mesh::ATTRIBUTE_POSITION, merged_positions);
+    if normals.is_some() {
+        merged.insert_attribute(Mesh::ATTRIBUTE_NORMAL, merged_normals);
+    }
+    if uvs.is_some() {
+        merged.insert_attribute(Mesh::ATTRIBUTE_UV_0, merged_uvs);
+    }
+    merged.insert_indices(Indices::U32(merged_indices));
+    Ok(merged)
+}
+
+/// Grid cell positions, centered on the origin in X and Z and stacked
+/// upward from 0 in Y. `jitter` displaces each copy by up to that fraction
+/// of `spacing` per axis, deterministically for a given `seed`.
+fn grid_offsets(
+    grid: [u32; 3],
+    spacing: [f32; 3],
+    jitter: f32,
+    seed: u32,
+) -> impl Iterator<Item = Vec3> {
+    let spacing = Vec3::from_array(spacing);
+    let jitter = jitter.clamp(0.0, 1.0);
+    let origin = Vec3::new(
+        (grid[0] - 1) as f32 * spacing.x * -0.5,
+        0.0,
+        (grid[2] - 1) as f32 * spacing.z * -0.5,
+    );
+
+    (0..grid[1]).flat_map(move |y| {
+        (0..grid[2]).flat_map(move |z| {
+            (0..grid[0]).map(move |x| {
+                let cell = Vec3::new(x as f32, y as f32, z as f32);
+                let mut pos = origin + cell * spacing;
+                if jitter > 0.0 {
+                    let index = (y * grid[2] + z) * grid[0] + x;
+                    let noise = Vec3::new(
+                        unit_noise(seed, index, 0),
+                        unit_noise(seed, index, 1),
+                        unit_noise(seed, index, 2),
+                    );
+                    pos += noise * spacing * jitter;
+                }
+                pos
+            })
+        })
+    })
+}
+
+/// Deterministic value in [-1, 1] for (seed, index, axis).
+fn unit_noise(seed: u32, index: u32, axis: u32) -> f32 {
+    let mut h = ((seed as u64) << 32) ^ ((index as u64) << 2) ^ axis as u64;
+    // splitmix64 finalizer
+    h = h.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 31;
+    (h >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; 3]);
+        mesh.insert_indices(Indices::U32(vec![0, 1, 2]));
+        mesh
+    }
+
+    #[test]
+    fn merge_copies_offsets_positions_and_indices() {
+        let transforms = [Transform::default(), Transform::from_xyz(5.0, 0.0, 0.0)];
+        let merged = merge_copies(&triangle(), &transforms).unwrap();
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            merged.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("merged mesh has no positions");
+        };
+        assert_eq!(positions.len(), 6);
+        assert_eq!(positions[4], [6.0, 0.0, 0.0]);
+        let indices: Vec<usize> = merged.indices().unwrap().iter().collect();
+        assert_eq!(indices, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn merge_copies_keeps_normals_perpendicular_under_scale() {
+        let transforms = [Transform::from_scale(Vec3::new(1.0, 1.0, 4.0))
+            .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2))];
+        let merged = merge_copies(&triangle(), &transforms).unwrap();
+
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            merged.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("merged mesh has no normals");
+        };
+        let normal = Vec3::from_array(normals[0]);
+        assert!((normal.length() - 1.0).abs() < 1e-5);
+        assert!(normal.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+    }
+
+    #[test]
+    fn merge_copies_rejects_fields_over_the_vertex_cap() {
+        let transforms = vec![Transform::default(); MAX_FIELD_VERTICES / 3 + 1];
+        assert!(merge_copies(&triangle(), &transforms).is_err());
+    }
+}
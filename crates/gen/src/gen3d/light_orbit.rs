@@ -0,0 +1,223 @@
+//! Light orbits — move a light around the scene and capture a screenshot
+//! at each step, for judging materials and shadows from every side.
+//!
+//! Starting an orbit stores its steps in the `LightOrbit` resource. Each
+//! frame, `process_light_orbit` moves the light to the next step and queues
+//! an export through the regular screenshot path; once every capture is
+//! written it restores the light and sends a single response.
+
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+use super::commands::{ExportColorSpace, GenResponse, LightOrbitData, OrbitLightCmd};
+use super::plugin::{
+    ActiveExportSettings, PendingScreenshot, PendingScreenshots, RenderQuality,
+    export_camera_components, resolve_export_settings,
+};
+use super::registry::{GenEntity, GenEntityType, NameRegistry};
+
+/// Maximum number of steps in one orbit.
+pub const MAX_ORBIT_FRAMES: u32 = 360;
+
+/// Frames to let lighting and shadows settle after each move, as for exports.
+const SETTLE_FRAMES: u32 = 3;
+
+const CAPTURE_WIDTH: u32 = 1920;
+const CAPTURE_HEIGHT: u32 = 1080;
+
+/// The orbit in progress, if any.
+#[derive(Resource, Default)]
+pub struct LightOrbit {
+    active: Option<ActiveOrbit>,
+}
+
+struct ActiveOrbit {
+    data: LightOrbitData,
+    light: Entity,
+    original: Transform,
+    steps: Vec<Transform>,
+    output_dir: PathBuf,
+    color_space: ExportColorSpace,
+    /// Index of the next step to move to.
+    next: usize,
+    /// Why a capture failed; the orbit stops there.
+    failed: Option<String>,
+    resp_tx: mpsc::UnboundedSender<GenResponse>,
+}
+
+impl LightOrbit {
+    /// Record that the current step's screenshot was written, or why it
+    /// couldn't be.
+    pub fn step_captured(&mut self, saved: Result<String, String>) {
+        if let Some(ref mut active) = self.active {
+            match saved {
+                Ok(path) => active.data.paths.push(path),
+                Err(message) => active.failed = Some(message),
+            }
+        }
+    }
+}
+
+/// Validate the command and start the orbit. The response is sent by
+/// `process_light_orbit` when the last capture is done.
+pub fn start_light_orbit(
+    world: &mut World,
+    cmd: OrbitLightCmd,
+    resp_tx: mpsc::UnboundedSender<GenResponse>,
+) -> Result<(), String> {
+    if world.resource::<LightOrbit>().active.is_some() {
+        return Err("A light orbit is already running".to_string());
+    }
+    if cmd.frames == 0 || cmd.frames > MAX_ORBIT_FRAMES {
+        return Err(format!("frames must be between 1 and {}", MAX_ORBIT_FRAMES));
+    }
+
+    let registry = world.resource::<NameRegistry>();
+    let Some(light) = registry.get_entity(&cmd.name) else {
+        return Err(format!("Entity '{}' not found", cmd.name));
+    };
+    let light_ref = world.entity(light);
+    if !(light_ref.contains::<DirectionalLight>()
+        || light_ref.contains::<PointLight>()
+        || light_ref.contains::<SpotLight>())
+    {
+        return Err(format!("Entity '{}' is not a light", cmd.name));
+    }
+    let original = light_ref.get::<Transform>().copied().unwrap_or_default();
+
+    // Orbit around the mean position of everything but lights and cameras
+    let mut sum = Vec3::ZERO;
+    let mut count = 0;
+    for (_, entity) in registry.all_names() {
+        let Ok(entity_ref) = world.get_entity(entity) else {
+            continue;
+        };
+        let skip = entity_ref
+            .get::<GenEntity>()
+            .is_some_and(|g| matches!(g.entity_type, GenEntityType::Light | GenEntityType::Camera));
+        if let (false, Some(global)) = (skip, entity_ref.get::<GlobalTransform>()) {
+            sum += global.translation();
+            count += 1;
+        }
+    }
+    let center = if count > 0 {
+        sum / count as f32
+    } else {
+        Vec3::ZERO
+    };
+
+    // Defaults keep the light's current distance and elevation
+    let offset = original.translation - center;
+    let horizontal = Vec2::new(offset.x, offset.z);
+    let radius = cmd.radius.unwrap_or(if horizontal.length() > 0.01 {
+        horizontal.length()
+    } else {
+        10.0
+    });
+    let height = cmd.height.unwrap_or(offset.y);
+    if !(radius.is_finite() && radius > 0.0 && height.is_finite()) {
+        return Err(format!(
+            "Invalid orbit: radius {} must be positive, height {} finite",
+            radius, height
+        ));
+    }
+
+    let output_dir = PathBuf::from(&cmd.output_dir);
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Cannot create {}: {}", output_dir.display(), e))?;
+
+    let start_angle = horizontal.y.atan2(horizontal.x);
+    let steps = (0..cmd.frames)
+        .map(|i| {
+            let angle = start_angle + TAU * i as f32 / cmd.frames as f32;
+            let position = center + Vec3::new(radius * angle.cos(), height, radius * angle.sin());
+            Transform::from_translation(position).looking_at(center, Vec3::Y)
+        })
+        .collect();
+
+    // Same antialiasing and tone mapping as any other export
+    let export = resolve_export_settings(
+        &world.resource::<ActiveExportSettings>().settings,
+        world.resource::<RenderQuality>().level,
+    );
+    if let Some(camera) = world.resource::<NameRegistry>().get_entity("main_camera") {
+        world
+            .entity_mut(camera)
+            .insert(export_camera_components(&export));
+    }
+
+    world.resource_mut::<LightOrbit>().active = Some(ActiveOrbit {
+        data: LightOrbitData {
+            name: cmd.name,
+            center: center.to_array(),
+            radius,
+            height,
+            paths: Vec::new(),
+        },
+        light,
+        original,
+        steps,
+        output_dir,
+        color_space: export.color_space,
+        next: 0,
+        failed: None,
+        resp_tx,
+    });
+    Ok(())
+}
+
+/// Advance the running orbit: move the light and queue the next capture
+/// once the previous one is written, then finish up.
+pub fn process_light_orbit(
+    mut orbit: ResMut<LightOrbit>,
+    mut pending: ResMut<PendingScreenshots>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let Some(ref mut active) = orbit.active else {
+        return;
+    };
+    if active.failed.is_none() && active.data.paths.len() < active.next {
+        return;
+    }
+
+    if let (None, Some(step)) = (&active.failed, active.steps.get(active.next)) {
+        if let Ok(mut transform) = transforms.get_mut(active.light) {
+            *transform = *step;
+        }
+        let file_stem: String = active
+            .data
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = active
+            .output_dir
+            .join(format!("{}_orbit_{:03}.png", file_stem, active.next));
+        pending.queue.push(PendingScreenshot {
+            frames_remaining: SETTLE_FRAMES,
+            width: CAPTURE_WIDTH,
+            height: CAPTURE_HEIGHT,
+            path: Some(path.to_string_lossy().into_owned()),
+            color_space: active.color_space,
+            orbit_step: true,
+        });
+        active.next += 1;
+        return;
+    }
+
+    let Some(active) = orbit.active.take() else {
+        return;
+    };
+    if let Ok(mut transform) = transforms.get_mut(active.light) {
+        *transform = active.original;
+    }
+    let response = match active.failed {
+        Some(message) => GenResponse::Error {
+            message: format!("Light orbit stopped: {}", message),
+        },
+        None => GenResponse::LightOrbited(active.data),
+    };
+    let _ = active.resp_tx.send(response);
+}
@@ -5,10 +5,21 @@
 //!
 //! Architecture: Agent loop (tokio) ←mpsc channels→ Bevy App (main thread)
 
+pub mod animation;
 pub mod commands;
+pub mod duplicate;
+pub mod instancing;
+pub mod light_orbit;
+pub mod obj_export;
 pub mod plugin;
+pub mod raycast;
 pub mod registry;
+pub mod snapshot;
+pub mod terrain;
+pub mod text3d;
+pub mod textures;
 pub mod tools;
+pub mod weld;
 
 use std::sync::Arc;
 use tokio::sync::{Mutex, mpsc};
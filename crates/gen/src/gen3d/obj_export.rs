@@ -0,0 +1,296 @@
+//! Wavefront OBJ export of the visible scene.
+//!
+//! Every visible triangle mesh is written in world space as one `o` object
+//! named after its nearest registered ancestor, so instanced copies and
+//! other unregistered children appear under their owner. Normals and UVs
+//! are written when the mesh has them; V is flipped because OBJ puts the
+//! texture origin at the bottom left.
+//!
+//! With materials, a `.mtl` file is written next to the `.obj` with one
+//! material per distinct `StandardMaterial`: base color, opacity, emission
+//! and the PBR roughness/metallic extension. Textures are not exported,
+//! since images loaded at runtime keep no source path.
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
+use bevy::render::render_resource::PrimitiveTopology;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use super::commands::GenResponse;
+use super::raycast::registered_ancestor;
+
+pub fn export_obj(world: &mut World, path: Option<String>, export_materials: bool) -> GenResponse {
+    let path = path.unwrap_or_else(|| {
+        let tmp = std::env::temp_dir().join(format!(
+            "localgpt_gen_scene_{}.obj",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        ));
+        tmp.to_string_lossy().into_owned()
+    });
+    match write_obj(world, Path::new(&path), export_materials) {
+        Ok(()) => GenResponse::Exported { path },
+        Err(message) => GenResponse::Error { message },
+    }
+}
+
+fn write_obj(world: &mut World, path: &Path, export_materials: bool) -> Result<(), String> {
+    let mtl_path = path.with_extension("mtl");
+    let mut obj = String::from("# Exported by LocalGPT Gen\n");
+    if export_materials && let Some(file_name) = mtl_path.file_name() {
+        let _ = writeln!(obj, "mtllib {}", file_name.to_string_lossy());
+    }
+
+    let mut candidates = world.query::<(
+        Entity,
+        &Mesh3d,
+        &GlobalTransform,
+        Option<&MeshMaterial3d<StandardMaterial>>,
+        Option<&InheritedVisibility>,
+    )>();
+    let meshes = world.resource::<Assets<Mesh>>();
+    // OBJ indices are 1-based and global across the file
+    let mut offsets = (1usize, 1usize, 1usize);
+    let mut material_ids: HashMap<AssetId<StandardMaterial>, usize> = HashMap::new();
+    let mut used_materials = Vec::new();
+    let mut objects = 0;
+    for (entity, mesh, global, material, visibility) in candidates.iter(world) {
+        if visibility.is_some_and(|v| !v.get()) {
+            continue;
+        }
+        let Some(mesh) = meshes.get(&mesh.0) else {
+            continue;
+        };
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            continue;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+        let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) if normals.len() == positions.len() => {
+                Some(normals)
+            }
+            _ => None,
+        };
+        let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(uvs)) if uvs.len() == positions.len() => {
+                Some(uvs)
+            }
+            _ => None,
+        };
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(Indices::U16(indices)) => indices.iter().map(|&i| i as usize).collect(),
+            Some(Indices::U32(indices)) => indices.iter().map(|&i| i as usize).collect(),
+            None => (0..positions.len()).collect(),
+        };
+
+        let name = registered_ancestor(world, entity).unwrap_or_else(|| "unnamed".to_string());
+        let _ = writeln!(obj, "o {}", name.replace(char::is_whitespace, "_"));
+
+        let matrix = global.compute_matrix();
+        for p in positions {
+            let p = matrix.transform_point3(Vec3::from(*p));
+            let _ = writeln!(obj, "v {} {} {}", p.x, p.y, p.z);
+        }
+        if let Some(uvs) = uvs {
+            for [u, v] in uvs {
+                let _ = writeln!(obj, "vt {} {}", u, 1.0 - v);
+            }
+        }
+        if let Some(normals) = normals {
+            // Normals transform by the inverse transpose
+            let normal_matrix = matrix.inverse().transpose();
+            for n in normals {
+                let n = normal_matrix
+                    .transform_vector3(Vec3::from(*n))
+                    .normalize_or_zero();
+                let _ = writeln!(obj, "vn {} {} {}", n.x, n.y, n.z);
+            }
+        }
+
+        if export_materials && let Some(material) = material {
+            let next = material_ids.len();
+            let id = *material_ids.entry(material.0.id()).or_insert_with(|| {
+                used_materials.push(material.0.id());
+                next
+            });
+            let _ = writeln!(obj, "usemtl material_{}", id);
+        }
+
+        let (v0, vt0, vn0) = offsets;
+        for tri in indices.chunks_exact(3) {
+            if tri.iter().any(|&i| i >= positions.len()) {
+                continue;
+            }
+            obj.push('f');
+            for &i in tri {
+                let _ = match (uvs.is_some(), normals.is_some()) {
+                    (true, true) => write!(obj, " {}/{}/{}", v0 + i, vt0 + i, vn0 + i),
+                    (true, false) => write!(obj, " {}/{}", v0 + i, vt0 + i),
+                    (false, true) => write!(obj, " {}//{}", v0 + i, vn0 + i),
+                    (false, false) => write!(obj, " {}", v0 + i),
+                };
+            }
+            obj.push('\n');
+        }
+
+        offsets = (
+            v0 + positions.len(),
+            vt0 + uvs.map_or(0, |uvs| uvs.len()),
+            vn0 + normals.map_or(0, |normals| normals.len()),
+        );
+        objects += 1;
+    }
+
+    if objects == 0 {
+        return Err("No visible meshes to export".to_string());
+    }
+    std::fs::write(path, obj).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    if export_materials {
+        let materials = world.resource::<Assets<StandardMaterial>>();
+        let mut mtl = String::from("# Exported by LocalGPT Gen\n");
+        for (i, id) in used_materials.iter().enumerate() {
+            let material = materials.get(*id).cloned().unwrap_or_default();
+            let color = material.base_color.to_srgba();
+            let emissive = material.emissive;
+            let _ = writeln!(mtl, "\nnewmtl material_{}", i);
+            let _ = writeln!(mtl, "Kd {} {} {}", color.red, color.green, color.blue);
+            let _ = writeln!(
+                mtl,
+                "Ke {} {} {}",
+                emissive.red, emissive.green, emissive.blue
+            );
+            let _ = writeln!(mtl, "d {}", color.alpha);
+            let _ = writeln!(mtl, "Pr {}", material.perceptual_roughness);
+            let _ = writeln!(mtl, "Pm {}", material.metallic);
+        }
+        std::fs::write(&mtl_path, mtl)
+            .map_err(|e| format!("Failed to write {}: {}", mtl_path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen3d::registry::NameRegistry;
+    use bevy::render::render_asset::RenderAssetUsages;
+
+    fn triangle(normals: bool, uvs: bool) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        );
+        if normals {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; 3]);
+        }
+        if uvs {
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_UV_0,
+                vec![[0.0, 0.0], [1.0, 0.0], [0.0, 0.25]],
+            );
+        }
+        mesh.insert_indices(Indices::U16(vec![0, 1, 2]));
+        mesh
+    }
+
+    fn spawn(world: &mut World, name: &str, mesh: Mesh, at: Vec3) {
+        let handle = world.resource_mut::<Assets<Mesh>>().add(mesh);
+        let entity = world
+            .spawn((
+                Mesh3d(handle),
+                GlobalTransform::from(Transform::from_translation(at)),
+                // Nothing propagates visibility without an app
+                InheritedVisibility::VISIBLE,
+            ))
+            .id();
+        world
+            .resource_mut::<NameRegistry>()
+            .insert(name.to_string(), entity);
+    }
+
+    fn export(world: &mut World) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scene.obj");
+        write_obj(world, &path, false).unwrap();
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    fn scene() -> World {
+        let mut world = World::new();
+        world.init_resource::<NameRegistry>();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        world
+    }
+
+    #[test]
+    fn face_indices_are_one_based_and_global() {
+        let mut world = scene();
+        spawn(
+            &mut world,
+            "full",
+            triangle(true, true),
+            Vec3::new(10.0, 0.0, 0.0),
+        );
+        spawn(&mut world, "lit", triangle(true, false), Vec3::ZERO);
+        spawn(&mut world, "bare", triangle(false, false), Vec3::ZERO);
+        let obj = export(&mut world);
+
+        let faces: Vec<&str> = obj.lines().filter(|l| l.starts_with("f ")).collect();
+        assert_eq!(
+            faces,
+            vec!["f 1/1/1 2/2/2 3/3/3", "f 4//4 5//5 6//6", "f 7 8 9"]
+        );
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 9);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("vt ")).count(), 3);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("vn ")).count(), 6);
+    }
+
+    #[test]
+    fn vertices_are_in_world_space_with_flipped_v() {
+        let mut world = scene();
+        spawn(
+            &mut world,
+            "my mesh",
+            triangle(false, true),
+            Vec3::new(10.0, 0.0, 0.0),
+        );
+        let obj = export(&mut world);
+
+        assert!(obj.contains("o my_mesh\n"));
+        assert!(obj.contains("v 11 0 0\n"));
+        assert!(obj.contains("vt 0 0.75\n"));
+    }
+
+    #[test]
+    fn out_of_range_faces_are_skipped() {
+        let mut world = scene();
+        let mut mesh = triangle(false, false);
+        mesh.insert_indices(Indices::U32(vec![0, 1, 2, 0, 1, 3]));
+        spawn(&mut world, "broken", mesh, Vec3::ZERO);
+        let obj = export(&mut world);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 1);
+    }
+
+    #[test]
+    fn empty_scene_is_an_error() {
+        let mut world = scene();
+        let dir = tempfile::tempdir().unwrap();
+        let err = write_obj(&mut world, &dir.path().join("scene.obj"), false).unwrap_err();
+        assert_eq!(err, "No visible meshes to export");
+    }
+}
@@ -1,14 +1,35 @@
 //! Bevy GenPlugin — command processing, default scene, screenshot capture.
 
+use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::pbr::{
+    DirectionalLightShadowMap, NotShadowCaster, NotShadowReceiver, ScreenSpaceAmbientOcclusion,
+    ScreenSpaceAmbientOcclusionQualityLevel,
+};
 use bevy::prelude::*;
 use bevy::render::mesh::Indices;
+use bevy::render::primitives::Aabb;
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::PrimitiveTopology;
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+use bevy::time::TimeUpdateStrategy;
+use bevy::winit::{UpdateMode, WinitSettings};
+use std::time::Duration;
 
 use super::GenChannels;
+use super::animation;
 use super::commands::*;
+use super::duplicate;
+use super::instancing;
+use super::light_orbit::{self, LightOrbit};
+use super::obj_export;
+use super::raycast;
 use super::registry::*;
+use super::snapshot::{self, SceneSnapshots};
+use super::terrain::Heightfield;
+use super::text3d::{self, Text3dLayers};
+use super::textures;
+use super::weld;
 
 /// Bevy resource wrapping the channel endpoints.
 #[derive(Resource)]
@@ -25,15 +46,55 @@ impl GenChannelRes {
 /// Pending screenshot requests that need to wait N frames.
 #[derive(Resource, Default)]
 pub struct PendingScreenshots {
-    queue: Vec<PendingScreenshot>,
+    pub(super) queue: Vec<PendingScreenshot>,
 }
 
 #[allow(dead_code)]
-struct PendingScreenshot {
-    frames_remaining: u32,
-    width: u32,
-    height: u32,
-    path: Option<String>,
+pub(super) struct PendingScreenshot {
+    pub(super) frames_remaining: u32,
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) path: Option<String>,
+    pub(super) color_space: ExportColorSpace,
+    /// Part of a light orbit: reported to `LightOrbit`, not the agent.
+    pub(super) orbit_step: bool,
+}
+
+/// Currently active render quality preset.
+#[derive(Resource, Default)]
+pub struct RenderQuality {
+    pub level: QualityLevel,
+}
+
+/// Export settings applied to screenshot and export renders.
+#[derive(Resource, Default)]
+pub struct ActiveExportSettings {
+    pub settings: ExportSettings,
+}
+
+/// Highest `subdivisions` accepted for an icosphere primitive.
+const MAX_ICOSPHERE_SUBDIVISIONS: u32 = 10;
+
+/// Highest frame rate accepted for deterministic mode.
+const MAX_DETERMINISTIC_FRAME_RATE: f32 = 240.0;
+
+/// Fixed-step rendering. While enabled, every frame advances time by
+/// exactly one step and frames run even when the window is unfocused, so a
+/// screenshot's `wait_frames` is an exact number of simulated frames
+/// rather than however much wall-clock time they happened to take.
+#[derive(Resource)]
+pub struct DeterministicMode {
+    pub enabled: bool,
+    pub frame_rate: f32,
+}
+
+impl Default for DeterministicMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frame_rate: 60.0,
+        }
+    }
 }
 
 /// Marker component for the interactive fly camera.
@@ -43,6 +104,8 @@ struct FlyCam;
 /// Configuration for the fly camera controller.
 #[derive(Resource)]
 struct FlyCamConfig {
+    /// Whether keyboard/mouse input moves the camera.
+    enabled: bool,
     move_speed: f32,
     look_sensitivity: f32,
 }
@@ -50,6 +113,7 @@ struct FlyCamConfig {
 impl Default for FlyCamConfig {
     fn default() -> Self {
         Self {
+            enabled: true,
             move_speed: 5.0,
             look_sensitivity: 0.003,
         }
@@ -57,6 +121,7 @@ impl Default for FlyCamConfig {
 }
 
 /// Plugin that sets up the Gen 3D environment.
+#[allow(dead_code)] // main.rs calls setup_gen_app, which can take the channels
 pub struct GenPlugin {
     pub channels: GenChannels,
 }
@@ -76,13 +141,23 @@ pub fn setup_gen_app(app: &mut App, channels: GenChannels) {
     app.insert_resource(GenChannelRes::new(channels))
         .init_resource::<NameRegistry>()
         .init_resource::<PendingScreenshots>()
+        .init_resource::<RenderQuality>()
+        .init_resource::<ActiveExportSettings>()
         .init_resource::<FlyCamConfig>()
+        .init_resource::<SceneSnapshots>()
+        .init_resource::<LightOrbit>()
+        .init_resource::<DeterministicMode>()
+        .init_resource::<Text3dLayers>()
         .add_systems(Startup, setup_default_scene)
         .add_systems(
             Update,
             (
                 process_gen_commands,
-                process_pending_screenshots,
+                // Count a screenshot's wait frames from the frame after it was queued
+                process_pending_screenshots.after(process_gen_commands),
+                light_orbit::process_light_orbit,
+                animation::animate_entities,
+                text3d::face_camera,
                 fly_cam_movement,
                 fly_cam_look,
                 fly_cam_scroll_speed,
@@ -149,6 +224,13 @@ fn setup_default_scene(
     registry.insert("main_light".into(), light);
 }
 
+/// Name, tag and shadow-flag lookups, bundled into one system parameter.
+type EntityMetaQueries<'w, 's> = (
+    Query<'w, 's, &'static Name>,
+    Query<'w, 's, &'static GenTags>,
+    Query<'w, 's, (Has<NotShadowCaster>, Has<NotShadowReceiver>)>,
+);
+
 /// Poll the command channel each frame and dispatch.
 #[allow(clippy::too_many_arguments)]
 fn process_gen_commands(
@@ -160,11 +242,18 @@ fn process_gen_commands(
     mut pending_screenshots: ResMut<PendingScreenshots>,
     transforms: Query<&Transform>,
     gen_entities: Query<&GenEntity>,
-    names_query: Query<&Name>,
+    (names_query, tags_query, shadow_query): EntityMetaQueries,
     children_query: Query<&Children>,
     parent_query: Query<&Parent>,
     visibility_query: Query<&Visibility>,
     material_handles: Query<&MeshMaterial3d<StandardMaterial>>,
+    bounds_query: Query<(&GlobalTransform, Option<&Aabb>)>,
+    mut quality: (
+        ResMut<RenderQuality>,
+        ResMut<DirectionalLightShadowMap>,
+        ResMut<ActiveExportSettings>,
+    ),
+    mut fly_cam: (ResMut<FlyCamConfig>, Query<&Projection>),
 ) {
     while let Ok(cmd) = channel_res.channels.cmd_rx.try_recv() {
         let response = match cmd {
@@ -175,6 +264,9 @@ fn process_gen_commands(
                 &material_handles,
                 &materials,
             ),
+            GenCommand::SceneTree => {
+                handle_scene_tree(&registry, &gen_entities, &children_query, &parent_query)
+            }
             GenCommand::EntityInfo { name } => handle_entity_info(
                 &name,
                 &registry,
@@ -184,6 +276,28 @@ fn process_gen_commands(
                 &children_query,
                 &parent_query,
                 &visibility_query,
+                &tags_query,
+                &shadow_query,
+                &bounds_query,
+                &material_handles,
+                &materials,
+            ),
+            GenCommand::EntitiesInRegion { center, radius } => handle_entities_in_region(
+                Vec3::from_array(center),
+                radius,
+                &registry,
+                &transforms,
+                &gen_entities,
+                &bounds_query,
+                &material_handles,
+                &materials,
+            ),
+            GenCommand::EntitiesByTag { tag } => handle_entities_by_tag(
+                tag,
+                &registry,
+                &transforms,
+                &gen_entities,
+                &tags_query,
                 &material_handles,
                 &materials,
             ),
@@ -192,11 +306,15 @@ fn process_gen_commands(
                 height,
                 wait_frames,
             } => {
+                let export = resolve_export_settings(&quality.2.settings, quality.0.level);
+                apply_export_settings(&export, &mut commands, &registry);
                 pending_screenshots.queue.push(PendingScreenshot {
                     frames_remaining: wait_frames,
                     width,
                     height,
                     path: None,
+                    color_space: export.color_space,
+                    orbit_step: false,
                 });
                 // Response will be sent by process_pending_screenshots
                 continue;
@@ -215,6 +333,7 @@ fn process_gen_commands(
                 &mut materials,
                 &material_handles,
                 &transforms,
+                &tags_query,
             ),
             GenCommand::DeleteEntity { name } => {
                 handle_delete_entity(&name, &mut commands, &mut registry)
@@ -222,6 +341,31 @@ fn process_gen_commands(
             GenCommand::SetCamera(cmd) => handle_set_camera(cmd, &mut commands, &registry),
             GenCommand::SetLight(cmd) => handle_set_light(cmd, &mut commands, &mut registry),
             GenCommand::SetEnvironment(cmd) => handle_set_environment(cmd, &mut commands),
+            GenCommand::SetQuality { level } => handle_set_quality(
+                level,
+                &mut commands,
+                &registry,
+                &mut quality.0,
+                &mut quality.1,
+            ),
+            GenCommand::SetInteractiveCamera { enabled } => handle_set_interactive_camera(
+                enabled,
+                &registry,
+                &transforms,
+                &fly_cam.1,
+                &mut fly_cam.0,
+            ),
+            GenCommand::FrameScene { padding, entities } => handle_frame_scene(
+                padding,
+                entities,
+                &mut commands,
+                &registry,
+                &gen_entities,
+                &transforms,
+                &fly_cam.1,
+                &bounds_query,
+                &children_query,
+            ),
             GenCommand::SpawnMesh(cmd) => handle_spawn_mesh(
                 cmd,
                 &mut commands,
@@ -229,16 +373,158 @@ fn process_gen_commands(
                 &mut materials,
                 &mut registry,
             ),
+            GenCommand::SpawnTerrain(cmd) => handle_spawn_terrain(
+                cmd,
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &mut registry,
+            ),
+            // Instancing and snapshots need the whole world; run them once
+            // this system's commands are applied and respond from there.
+            GenCommand::SpawnInstanced(cmd) => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(instancing::spawn_instanced(world, cmd));
+                });
+                continue;
+            }
+            GenCommand::SpawnText3D(cmd) => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(text3d::spawn_text3d(world, cmd));
+                });
+                continue;
+            }
+            GenCommand::DuplicateEntity {
+                source,
+                new_name,
+                offset,
+                count,
+            } => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(duplicate::duplicate_entity(
+                        world, source, new_name, offset, count,
+                    ));
+                });
+                continue;
+            }
+            GenCommand::Raycast {
+                origin,
+                direction,
+                max_distance,
+            } => {
+                // Needs the mesh assets and every entity's global transform
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(raycast::raycast(world, origin, direction, max_distance));
+                });
+                continue;
+            }
+            GenCommand::SetMaterial(cmd) => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(textures::set_material(world, cmd));
+                });
+                continue;
+            }
+            GenCommand::AnimateEntity(cmd) => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(animation::start_animation(world, cmd));
+                });
+                continue;
+            }
+            GenCommand::StopAnimation { name } => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(animation::stop_animation(world, name));
+                });
+                continue;
+            }
+            GenCommand::SnapshotScene { label } => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(snapshot::snapshot_scene(world, label));
+                });
+                continue;
+            }
+            GenCommand::WeldMesh(cmd) => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(weld::weld_entity_mesh(world, cmd));
+                });
+                continue;
+            }
+            GenCommand::RestoreSnapshot { label } => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(snapshot::restore_snapshot(world, label));
+                });
+                continue;
+            }
+            GenCommand::ListSnapshots => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ =
+                        resp_tx.send(snapshot::list_snapshots(world.resource::<SceneSnapshots>()));
+                });
+                continue;
+            }
             GenCommand::ExportScreenshot {
                 path,
                 width,
                 height,
             } => {
+                let export = resolve_export_settings(&quality.2.settings, quality.0.level);
+                apply_export_settings(&export, &mut commands, &registry);
                 pending_screenshots.queue.push(PendingScreenshot {
                     frames_remaining: 3,
                     width,
                     height,
                     path: Some(path),
+                    color_space: export.color_space,
+                    orbit_step: false,
+                });
+                continue;
+            }
+            GenCommand::ExportObj {
+                path,
+                export_materials,
+            } => {
+                // Needs the mesh and material assets and global transforms
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(obj_export::export_obj(world, path, export_materials));
+                });
+                continue;
+            }
+            GenCommand::OrbitLight(cmd) => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    if let Err(message) =
+                        light_orbit::start_light_orbit(world, cmd, resp_tx.clone())
+                    {
+                        let _ = resp_tx.send(GenResponse::Error { message });
+                    }
+                });
+                continue;
+            }
+            GenCommand::SetExportSettings(settings) => handle_set_export_settings(
+                settings,
+                &mut commands,
+                &registry,
+                quality.0.level,
+                &mut quality.2,
+            ),
+            GenCommand::SetDeterministicMode {
+                enabled,
+                frame_rate,
+            } => {
+                let resp_tx = channel_res.channels.resp_tx.clone();
+                commands.queue(move |world: &mut World| {
+                    let _ = resp_tx.send(handle_set_deterministic_mode(world, enabled, frame_rate));
                 });
                 continue;
             }
@@ -250,6 +536,7 @@ fn process_gen_commands(
 
 /// Process pending screenshots that need frame delays.
 fn process_pending_screenshots(
+    mut commands: Commands,
     channel_res: ResMut<GenChannelRes>,
     mut pending: ResMut<PendingScreenshots>,
 ) {
@@ -279,18 +566,46 @@ fn process_pending_screenshots(
             tmp.to_string_lossy().into_owned()
         });
 
-        // TODO: Actual Bevy screenshot capture requires camera entity access
-        // and render-to-texture. For now, we create a placeholder and report
-        // the path. Full implementation needs Bevy's Screenshot observer or
-        // render target approach.
-        //
-        // In a full implementation:
-        //   commands.entity(camera).trigger(Screenshot::to_disk(path));
-        let response = GenResponse::Screenshot {
-            image_path: path.clone(),
-        };
-        let _ = channel_res.channels.resp_tx.send(response);
+        // Capture the window's next rendered frame. MSAA and tone mapping
+        // were applied to the camera when the request was queued. The
+        // response goes out once the file is written, so the path exists.
+        let resp_tx = channel_res.channels.resp_tx.clone();
+        let color_space = screenshot.color_space;
+        let orbit_step = screenshot.orbit_step;
+        commands.spawn(Screenshot::primary_window()).observe(
+            move |trigger: Trigger<ScreenshotCaptured>, mut light_orbit: ResMut<LightOrbit>| {
+                let saved = save_capture(trigger.event().0.clone(), &path, color_space);
+                if orbit_step {
+                    light_orbit.step_captured(saved.map(|()| path.clone()));
+                    return;
+                }
+                let response = match saved {
+                    Ok(()) => GenResponse::Screenshot {
+                        image_path: path.clone(),
+                    },
+                    Err(message) => GenResponse::Error { message },
+                };
+                let _ = resp_tx.send(response);
+            },
+        );
+    }
+}
+
+/// Write a captured frame to `path` (format from the extension), encoded
+/// per `color_space`. The window's frame is sRGB-encoded 8-bit color.
+fn save_capture(image: Image, path: &str, color_space: ExportColorSpace) -> Result<(), String> {
+    let mut pixels = image
+        .try_into_dynamic()
+        .map_err(|e| format!("Cannot convert screenshot: {}", e))?
+        .to_rgb8();
+    if color_space == ExportColorSpace::Linear {
+        for value in pixels.iter_mut() {
+            *value = (Srgba::gamma_function(*value as f32 / 255.0) * 255.0).round() as u8;
+        }
     }
+    pixels
+        .save(path)
+        .map_err(|e| format!("Cannot write screenshot {}: {}", path, e))
 }
 
 // ---------------------------------------------------------------------------
@@ -304,41 +619,268 @@ fn handle_scene_info(
     material_handles: &Query<&MeshMaterial3d<StandardMaterial>>,
     material_assets: &Assets<StandardMaterial>,
 ) -> GenResponse {
-    let mut entities = Vec::new();
+    let entities: Vec<EntitySummary> = registry
+        .all_names()
+        .map(|(name, entity)| {
+            entity_summary(
+                name,
+                entity,
+                transforms,
+                gen_entities,
+                material_handles,
+                material_assets,
+            )
+        })
+        .collect();
 
-    for (name, entity) in registry.all_names() {
-        let position = transforms
-            .get(entity)
-            .map(|t| t.translation.to_array())
-            .unwrap_or_default();
-        let scale = transforms
-            .get(entity)
-            .map(|t| t.scale.to_array())
-            .unwrap_or([1.0, 1.0, 1.0]);
-        let entity_type = gen_entities
+    GenResponse::SceneInfo(SceneInfoData {
+        entity_count: entities.len(),
+        entities,
+    })
+}
+
+fn handle_scene_tree(
+    registry: &NameRegistry,
+    gen_entities: &Query<&GenEntity>,
+    children_query: &Query<&Children>,
+    parent_query: &Query<&Parent>,
+) -> GenResponse {
+    let mut roots: Vec<SceneTreeNode> = registry
+        .all_names()
+        .filter(|(_, entity)| {
+            !parent_query
+                .get(*entity)
+                .is_ok_and(|p| registry.contains_entity(p.get()))
+        })
+        .map(|(name, entity)| scene_tree_node(name, entity, registry, gen_entities, children_query))
+        .collect();
+    roots.sort_by(|a, b| a.name.cmp(&b.name));
+
+    GenResponse::SceneTree(SceneTreeData {
+        entity_count: registry.len(),
+        roots,
+    })
+}
+
+fn scene_tree_node(
+    name: &str,
+    entity: Entity,
+    registry: &NameRegistry,
+    gen_entities: &Query<&GenEntity>,
+    children_query: &Query<&Children>,
+) -> SceneTreeNode {
+    let mut children = Vec::new();
+    let mut unnamed_children = 0;
+    if let Ok(child_entities) = children_query.get(entity) {
+        for &child in child_entities.iter() {
+            match registry.get_name(child) {
+                Some(child_name) => children.push(scene_tree_node(
+                    child_name,
+                    child,
+                    registry,
+                    gen_entities,
+                    children_query,
+                )),
+                None => unnamed_children += 1,
+            }
+        }
+    }
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+
+    SceneTreeNode {
+        name: name.to_string(),
+        entity_type: gen_entities
             .get(entity)
             .map(|g| g.entity_type.as_str().to_string())
-            .unwrap_or_else(|_| "unknown".to_string());
+            .unwrap_or_else(|_| "unknown".to_string()),
+        children,
+        unnamed_children,
+    }
+}
 
-        let color = material_handles
-            .get(entity)
-            .ok()
-            .and_then(|h| material_assets.get(&h.0))
-            .map(|mat| {
-                let c = mat.base_color.to_srgba();
-                [c.red, c.green, c.blue, c.alpha]
+fn entity_summary(
+    name: &str,
+    entity: Entity,
+    transforms: &Query<&Transform>,
+    gen_entities: &Query<&GenEntity>,
+    material_handles: &Query<&MeshMaterial3d<StandardMaterial>>,
+    material_assets: &Assets<StandardMaterial>,
+) -> EntitySummary {
+    let position = transforms
+        .get(entity)
+        .map(|t| t.translation.to_array())
+        .unwrap_or_default();
+    let scale = transforms
+        .get(entity)
+        .map(|t| t.scale.to_array())
+        .unwrap_or([1.0, 1.0, 1.0]);
+    let entity_type = gen_entities
+        .get(entity)
+        .map(|g| g.entity_type.as_str().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    let color = material_handles
+        .get(entity)
+        .ok()
+        .and_then(|h| material_assets.get(&h.0))
+        .map(|mat| {
+            let c = mat.base_color.to_srgba();
+            [c.red, c.green, c.blue, c.alpha]
+        });
+
+    EntitySummary {
+        name: name.to_string(),
+        entity_type,
+        position,
+        scale,
+        color,
+    }
+}
+
+/// World-space axis-aligned bounding box of an entity.
+///
+/// Transforms the corners of the mesh's local `Aabb` by the entity's
+/// `GlobalTransform`. Entities without a mesh (lights, cameras) collapse to
+/// a point at their world position.
+fn world_aabb(global: &GlobalTransform, aabb: Option<&Aabb>) -> (Vec3, Vec3) {
+    let Some(aabb) = aabb else {
+        let p = global.translation();
+        return (p, p);
+    };
+
+    let center = Vec3::from(aabb.center);
+    let half = Vec3::from(aabb.half_extents);
+    let mut min = Vec3::splat(f32::MAX);
+    let mut max = Vec3::splat(f32::MIN);
+    for corner in [
+        Vec3::new(-1.0, -1.0, -1.0),
+        Vec3::new(-1.0, -1.0, 1.0),
+        Vec3::new(-1.0, 1.0, -1.0),
+        Vec3::new(-1.0, 1.0, 1.0),
+        Vec3::new(1.0, -1.0, -1.0),
+        Vec3::new(1.0, -1.0, 1.0),
+        Vec3::new(1.0, 1.0, -1.0),
+        Vec3::new(1.0, 1.0, 1.0),
+    ] {
+        let p = global.transform_point(center + corner * half);
+        min = min.min(p);
+        max = max.max(p);
+    }
+    (min, max)
+}
+
+/// Smallest bounding radius `FrameScene` frames, so a lone point or a
+/// flat plane seen edge-on doesn't put the camera on top of it.
+const MIN_FRAME_RADIUS: f32 = 0.5;
+
+/// World-space bounds of an entity's mesh or, for an entity without one
+/// (groups, instanced fields), the union of its descendants' meshes. Falls
+/// back to the entity's position when there is no mesh at all.
+fn subtree_aabb(
+    entity: Entity,
+    bounds_query: &Query<(&GlobalTransform, Option<&Aabb>)>,
+    children_query: &Query<&Children>,
+) -> (Vec3, Vec3) {
+    let Ok((global, aabb)) = bounds_query.get(entity) else {
+        return (Vec3::ZERO, Vec3::ZERO);
+    };
+    if aabb.is_some() {
+        return world_aabb(global, aabb);
+    }
+
+    let mut bounds: Option<(Vec3, Vec3)> = None;
+    for descendant in children_query.iter_descendants(entity) {
+        if let Ok((global, Some(aabb))) = bounds_query.get(descendant) {
+            let (min, max) = world_aabb(global, Some(aabb));
+            bounds = Some(match bounds {
+                Some((lo, hi)) => (lo.min(min), hi.max(max)),
+                None => (min, max),
             });
+        }
+    }
+    bounds.unwrap_or_else(|| world_aabb(global, None))
+}
 
-        entities.push(EntitySummary {
-            name: name.to_string(),
-            entity_type,
-            position,
-            scale,
-            color,
+fn handle_entities_by_tag(
+    tag: String,
+    registry: &NameRegistry,
+    transforms: &Query<&Transform>,
+    gen_entities: &Query<&GenEntity>,
+    tags_query: &Query<&GenTags>,
+    material_handles: &Query<&MeshMaterial3d<StandardMaterial>>,
+    material_assets: &Assets<StandardMaterial>,
+) -> GenResponse {
+    let mut entities: Vec<EntitySummary> = registry
+        .all_names()
+        .filter(|(_, entity)| tags_query.get(*entity).is_ok_and(|t| t.has_tag(&tag)))
+        .map(|(name, entity)| {
+            entity_summary(
+                name,
+                entity,
+                transforms,
+                gen_entities,
+                material_handles,
+                material_assets,
+            )
+        })
+        .collect();
+
+    entities.sort_by(|a, b| a.name.cmp(&b.name));
+
+    GenResponse::EntitiesByTag(TaggedEntitiesData {
+        tag,
+        entity_count: entities.len(),
+        entities,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_entities_in_region(
+    center: Vec3,
+    radius: f32,
+    registry: &NameRegistry,
+    transforms: &Query<&Transform>,
+    gen_entities: &Query<&GenEntity>,
+    bounds_query: &Query<(&GlobalTransform, Option<&Aabb>)>,
+    material_handles: &Query<&MeshMaterial3d<StandardMaterial>>,
+    material_assets: &Assets<StandardMaterial>,
+) -> GenResponse {
+    if !radius.is_finite() || radius < 0.0 {
+        return GenResponse::Error {
+            message: format!("Invalid radius: {}", radius),
+        };
+    }
+
+    let mut entities = Vec::new();
+    for (name, entity) in registry.all_names() {
+        let Ok((global, aabb)) = bounds_query.get(entity) else {
+            continue;
+        };
+
+        // Sphere–box test: distance from the center to the closest point of the box
+        let (min, max) = world_aabb(global, aabb);
+        if center.clamp(min, max).distance(center) > radius {
+            continue;
+        }
+
+        entities.push(RegionEntitySummary {
+            summary: entity_summary(
+                name,
+                entity,
+                transforms,
+                gen_entities,
+                material_handles,
+                material_assets,
+            ),
+            distance: global.translation().distance(center),
         });
     }
 
-    GenResponse::SceneInfo(SceneInfoData {
+    entities.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+    GenResponse::EntitiesInRegion(RegionInfoData {
+        center: center.to_array(),
+        radius,
         entity_count: entities.len(),
         entities,
     })
@@ -354,6 +896,9 @@ fn handle_entity_info(
     children_query: &Query<&Children>,
     parent_query: &Query<&Parent>,
     visibility_query: &Query<&Visibility>,
+    tags_query: &Query<&GenTags>,
+    shadow_query: &Query<(Has<NotShadowCaster>, Has<NotShadowReceiver>)>,
+    bounds_query: &Query<(&GlobalTransform, Option<&Aabb>)>,
     material_handles: &Query<&MeshMaterial3d<StandardMaterial>>,
     material_assets: &Assets<StandardMaterial>,
 ) -> GenResponse {
@@ -376,6 +921,8 @@ fn handle_entity_info(
         .map(|v| *v != Visibility::Hidden)
         .unwrap_or(true);
 
+    let (not_caster, not_receiver) = shadow_query.get(entity).unwrap_or_default();
+
     let (color, metallic, roughness) = material_handles
         .get(entity)
         .ok()
@@ -409,6 +956,10 @@ fn handle_entity_info(
         .ok()
         .and_then(|p| registry.get_name(p.get()).map(|s| s.to_string()));
 
+    let GenTags { tags, metadata } = tags_query.get(entity).cloned().unwrap_or_default();
+
+    let (aabb_min, aabb_max) = subtree_aabb(entity, bounds_query, children_query);
+
     GenResponse::EntityInfo(EntityInfoData {
         name: name.to_string(),
         entity_id: entity.to_bits(),
@@ -424,8 +975,14 @@ fn handle_entity_info(
         metallic,
         roughness,
         visible,
+        casts_shadows: !not_caster,
+        receives_shadows: !not_receiver,
+        aabb_min: aabb_min.to_array(),
+        aabb_max: aabb_max.to_array(),
         children,
         parent,
+        tags,
+        metadata,
     })
 }
 
@@ -478,6 +1035,29 @@ fn handle_spawn_primitive(
             let z = cmd.dimensions.get("z").copied().unwrap_or(1.0);
             meshes.add(Plane3d::new(Vec3::Y, Vec2::new(x / 2.0, z / 2.0)))
         }
+        PrimitiveShape::Wedge => {
+            let width = cmd.dimensions.get("width").copied().unwrap_or(1.0);
+            let height = cmd.dimensions.get("height").copied().unwrap_or(1.0);
+            let depth = cmd.dimensions.get("depth").copied().unwrap_or(1.0);
+            meshes.add(wedge_mesh(width, height, depth))
+        }
+        PrimitiveShape::Icosphere => {
+            let radius = cmd.dimensions.get("radius").copied().unwrap_or(0.5);
+            let subdivisions = cmd
+                .dimensions
+                .get("subdivisions")
+                .map(|s| s.round().max(0.0) as u32)
+                .unwrap_or(3)
+                .min(MAX_ICOSPHERE_SUBDIVISIONS);
+            match Sphere::new(radius).mesh().ico(subdivisions) {
+                Ok(mesh) => meshes.add(mesh),
+                Err(e) => {
+                    return GenResponse::Error {
+                        message: format!("Failed to build icosphere: {}", e),
+                    };
+                }
+            }
+        }
     };
 
     let material = materials.add(StandardMaterial {
@@ -515,6 +1095,10 @@ fn handle_spawn_primitive(
             GenEntity {
                 entity_type: GenEntityType::Primitive,
             },
+            GenTags {
+                tags: cmd.tags,
+                metadata: cmd.metadata,
+            },
         ))
         .id();
 
@@ -534,6 +1118,77 @@ fn handle_spawn_primitive(
     }
 }
 
+/// A right-triangular prism centered on the origin. The right angle runs
+/// along the bottom back edge, so the sloped face rises from the front
+/// (+Z) to the top of the back face (-Z). Faces are flat shaded with their
+/// own UVs.
+fn wedge_mesh(width: f32, height: f32, depth: f32) -> Mesh {
+    let (x0, x1) = (-width / 2.0, width / 2.0);
+    let (y0, y1) = (-height / 2.0, height / 2.0);
+    let (z0, z1) = (-depth / 2.0, depth / 2.0);
+    // Profile corners: back bottom, front bottom, back top
+    let a = |x| [x, y0, z0];
+    let b = |x| [x, y0, z1];
+    let c = |x| [x, y1, z0];
+
+    // Each face lists its corners counter-clockwise seen from outside
+    let faces: [&[([f32; 3], [f32; 2])]; 5] = [
+        // Left and right ends
+        &[
+            (a(x0), [0.0, 1.0]),
+            (b(x0), [1.0, 1.0]),
+            (c(x0), [0.0, 0.0]),
+        ],
+        &[
+            (a(x1), [1.0, 1.0]),
+            (c(x1), [1.0, 0.0]),
+            (b(x1), [0.0, 1.0]),
+        ],
+        // Bottom
+        &[
+            (a(x0), [0.0, 0.0]),
+            (a(x1), [1.0, 0.0]),
+            (b(x1), [1.0, 1.0]),
+            (b(x0), [0.0, 1.0]),
+        ],
+        // Back
+        &[
+            (a(x0), [1.0, 1.0]),
+            (c(x0), [1.0, 0.0]),
+            (c(x1), [0.0, 0.0]),
+            (a(x1), [0.0, 1.0]),
+        ],
+        // Slope
+        &[
+            (b(x0), [0.0, 1.0]),
+            (b(x1), [1.0, 1.0]),
+            (c(x1), [1.0, 0.0]),
+            (c(x0), [0.0, 0.0]),
+        ],
+    ];
+
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    for face in faces {
+        // Fan-triangulate from the first corner
+        for i in 1..face.len() - 1 {
+            for (position, uv) in [face[0], face[i], face[i + 1]] {
+                positions.push(position);
+                uvs.push(uv);
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.compute_flat_normals();
+    mesh
+}
+
 fn handle_modify_entity(
     cmd: ModifyEntityCmd,
     commands: &mut Commands,
@@ -541,6 +1196,7 @@ fn handle_modify_entity(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     material_handles: &Query<&MeshMaterial3d<StandardMaterial>>,
     transforms: &Query<&Transform>,
+    tags_query: &Query<&GenTags>,
 ) -> GenResponse {
     let Some(entity) = registry.get_entity(&cmd.name) else {
         return GenResponse::Error {
@@ -614,6 +1270,42 @@ fn handle_modify_entity(
         });
     }
 
+    // Update shadow flags
+    match cmd.casts_shadows {
+        Some(true) => {
+            entity_commands.remove::<NotShadowCaster>();
+        }
+        Some(false) => {
+            entity_commands.insert(NotShadowCaster);
+        }
+        None => {}
+    }
+    match cmd.receives_shadows {
+        Some(true) => {
+            entity_commands.remove::<NotShadowReceiver>();
+        }
+        Some(false) => {
+            entity_commands.insert(NotShadowReceiver);
+        }
+        None => {}
+    }
+
+    // Update tags and metadata
+    if cmd.tags.is_some() || cmd.metadata.is_some() {
+        let mut tags = tags_query.get(entity).cloned().unwrap_or_default();
+        if let Some(new_tags) = cmd.tags {
+            tags.tags = new_tags;
+        }
+        for (key, value) in cmd.metadata.into_iter().flatten() {
+            if value.is_empty() {
+                tags.metadata.remove(&key);
+            } else {
+                tags.metadata.insert(key, value);
+            }
+        }
+        entity_commands.insert(tags);
+    }
+
     // Update parent
     if let Some(parent_opt) = cmd.parent {
         match parent_opt {
@@ -676,6 +1368,133 @@ fn handle_set_camera(
     GenResponse::CameraSet
 }
 
+fn handle_set_interactive_camera(
+    enabled: bool,
+    registry: &NameRegistry,
+    transforms: &Query<&Transform>,
+    projections: &Query<&Projection>,
+    config: &mut FlyCamConfig,
+) -> GenResponse {
+    let Some(camera_entity) = registry.get_entity("main_camera") else {
+        return GenResponse::Error {
+            message: "main_camera not found in registry".to_string(),
+        };
+    };
+
+    let Ok(transform) = transforms.get(camera_entity) else {
+        return GenResponse::Error {
+            message: "main_camera has no transform".to_string(),
+        };
+    };
+
+    config.enabled = enabled;
+
+    // Report the pose so the agent can pick up where the human left off
+    let fov_degrees = match projections.get(camera_entity) {
+        Ok(Projection::Perspective(p)) => p.fov.to_degrees(),
+        _ => PerspectiveProjection::default().fov.to_degrees(),
+    };
+    let look_at = transform.translation + transform.forward().as_vec3();
+
+    GenResponse::InteractiveCamera(InteractiveCameraData {
+        enabled,
+        pose: CameraCmd {
+            position: transform.translation.to_array(),
+            look_at: look_at.to_array(),
+            fov_degrees,
+        },
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_frame_scene(
+    padding: f32,
+    entities: Option<Vec<String>>,
+    commands: &mut Commands,
+    registry: &NameRegistry,
+    gen_entities: &Query<&GenEntity>,
+    transforms: &Query<&Transform>,
+    projections: &Query<&Projection>,
+    bounds_query: &Query<(&GlobalTransform, Option<&Aabb>)>,
+    children_query: &Query<&Children>,
+) -> GenResponse {
+    let Some(camera_entity) = registry.get_entity("main_camera") else {
+        return GenResponse::Error {
+            message: "main_camera not found in registry".to_string(),
+        };
+    };
+    if !padding.is_finite() || padding < 0.0 {
+        return GenResponse::Error {
+            message: "padding must be zero or positive".to_string(),
+        };
+    }
+
+    let targets: Vec<Entity> = match entities {
+        Some(names) => {
+            let mut targets = Vec::with_capacity(names.len());
+            for name in &names {
+                let Some(entity) = registry.get_entity(name) else {
+                    return GenResponse::Error {
+                        message: format!("Entity '{}' not found", name),
+                    };
+                };
+                targets.push(entity);
+            }
+            targets
+        }
+        // Cameras and lights have no visible extent of their own
+        None => registry
+            .all_names()
+            .map(|(_, entity)| entity)
+            .filter(|entity| {
+                gen_entities.get(*entity).is_ok_and(|g| {
+                    !matches!(g.entity_type, GenEntityType::Camera | GenEntityType::Light)
+                })
+            })
+            .collect(),
+    };
+    let Some((min, max)) = targets
+        .iter()
+        .map(|entity| subtree_aabb(*entity, bounds_query, children_query))
+        .reduce(|(lo, hi), (min, max)| (lo.min(min), hi.max(max)))
+    else {
+        return GenResponse::Error {
+            message: "No entities to frame".to_string(),
+        };
+    };
+
+    let perspective = match projections.get(camera_entity) {
+        Ok(Projection::Perspective(p)) => p.clone(),
+        _ => PerspectiveProjection::default(),
+    };
+    // Fit the bounding sphere inside the narrower of the two view angles
+    let half_fov = perspective.fov / 2.0;
+    let half_fov = half_fov.min((half_fov.tan() * perspective.aspect_ratio).atan());
+    let center = (min + max) / 2.0;
+    let radius = ((max - min).length() / 2.0).max(MIN_FRAME_RADIUS) * (1.0 + padding);
+    let distance = radius / half_fov.sin();
+
+    // Keep the current view direction so framing only moves the camera
+    let forward = transforms
+        .get(camera_entity)
+        .map(|t| t.forward().as_vec3())
+        .unwrap_or(Vec3::NEG_ONE.normalize());
+    let position = center - forward * distance;
+    commands
+        .entity(camera_entity)
+        .insert(Transform::from_translation(position).looking_at(center, Vec3::Y));
+
+    GenResponse::SceneFramed(FrameSceneData {
+        pose: CameraCmd {
+            position: position.to_array(),
+            look_at: center.to_array(),
+            fov_degrees: perspective.fov.to_degrees(),
+        },
+        entities: targets.len(),
+        radius,
+    })
+}
+
 fn handle_set_light(
     cmd: SetLightCmd,
     commands: &mut Commands,
@@ -777,6 +1596,183 @@ fn handle_set_environment(cmd: EnvironmentCmd, commands: &mut Commands) -> GenRe
     GenResponse::EnvironmentSet
 }
 
+fn handle_set_quality(
+    level: QualityLevel,
+    commands: &mut Commands,
+    registry: &NameRegistry,
+    quality: &mut RenderQuality,
+    shadow_map: &mut DirectionalLightShadowMap,
+) -> GenResponse {
+    let Some(camera_entity) = registry.get_entity("main_camera") else {
+        return GenResponse::Error {
+            message: "main_camera not found in registry".to_string(),
+        };
+    };
+
+    let mut camera = commands.entity(camera_entity);
+    match level {
+        QualityLevel::Low => {
+            camera
+                .remove::<ScreenSpaceAmbientOcclusion>()
+                .insert((Msaa::default(), Tonemapping::default()));
+            shadow_map.size = 2048;
+        }
+        QualityLevel::Medium => {
+            // SSAO requires MSAA to be off
+            camera.insert((
+                Msaa::Off,
+                ScreenSpaceAmbientOcclusion {
+                    quality_level: ScreenSpaceAmbientOcclusionQualityLevel::Medium,
+                    ..default()
+                },
+                Tonemapping::TonyMcMapface,
+            ));
+            shadow_map.size = 4096;
+        }
+        QualityLevel::High => {
+            camera.insert((
+                Msaa::Off,
+                ScreenSpaceAmbientOcclusion {
+                    quality_level: ScreenSpaceAmbientOcclusionQualityLevel::High,
+                    ..default()
+                },
+                Tonemapping::AcesFitted,
+            ));
+            shadow_map.size = 8192;
+        }
+    }
+
+    quality.level = level;
+    GenResponse::QualitySet { level }
+}
+
+fn handle_set_export_settings(
+    settings: ExportSettings,
+    commands: &mut Commands,
+    registry: &NameRegistry,
+    quality: QualityLevel,
+    active: &mut ActiveExportSettings,
+) -> GenResponse {
+    if let Some(samples) = settings.msaa_samples {
+        if !matches!(samples, 1 | 2 | 4 | 8) {
+            return GenResponse::Error {
+                message: format!("Invalid msaa_samples {}: expected 1, 2, 4 or 8", samples),
+            };
+        }
+        if samples > 1 && quality.msaa_samples() == 1 {
+            return GenResponse::Error {
+                message: format!(
+                    "MSAA is unavailable at quality '{}' (ambient occlusion requires it off); set quality to low first",
+                    quality.as_str()
+                ),
+            };
+        }
+    }
+
+    active.settings = settings;
+    let export = resolve_export_settings(&settings, quality);
+    apply_export_settings(&export, commands, registry);
+    GenResponse::ExportSettingsSet(export)
+}
+
+fn handle_set_deterministic_mode(
+    world: &mut World,
+    enabled: Option<bool>,
+    frame_rate: Option<f32>,
+) -> GenResponse {
+    if let Some(rate) = frame_rate
+        && !(rate.is_finite() && rate > 0.0 && rate <= MAX_DETERMINISTIC_FRAME_RATE)
+    {
+        return GenResponse::Error {
+            message: format!(
+                "Invalid frame_rate {}: expected a value in (0, {}]",
+                rate, MAX_DETERMINISTIC_FRAME_RATE
+            ),
+        };
+    }
+
+    let mut mode = world.resource_mut::<DeterministicMode>();
+    if let Some(enabled) = enabled {
+        mode.enabled = enabled;
+    }
+    if let Some(rate) = frame_rate {
+        mode.frame_rate = rate;
+    }
+    let data = DeterministicModeData {
+        enabled: mode.enabled,
+        frame_rate: mode.frame_rate,
+    };
+    if enabled.is_none() && frame_rate.is_none() {
+        return GenResponse::DeterministicMode(data);
+    }
+
+    if data.enabled {
+        world.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / data.frame_rate,
+        )));
+        world.insert_resource(WinitSettings {
+            focused_mode: UpdateMode::Continuous,
+            unfocused_mode: UpdateMode::Continuous,
+        });
+    } else {
+        world.insert_resource(TimeUpdateStrategy::Automatic);
+        world.insert_resource(WinitSettings::default());
+    }
+    GenResponse::DeterministicMode(data)
+}
+
+/// Fill unset export settings from the quality preset. MSAA stays off
+/// whenever the preset does, since SSAO cannot run with it.
+pub(super) fn resolve_export_settings(
+    settings: &ExportSettings,
+    quality: QualityLevel,
+) -> ExportSettingsData {
+    let preset_samples = quality.msaa_samples();
+    ExportSettingsData {
+        msaa_samples: if preset_samples == 1 {
+            1
+        } else {
+            settings.msaa_samples.unwrap_or(preset_samples)
+        },
+        tonemapping: settings.tonemapping.unwrap_or(quality.tonemapping()),
+        color_space: settings.color_space,
+        quality,
+    }
+}
+
+fn apply_export_settings(
+    export: &ExportSettingsData,
+    commands: &mut Commands,
+    registry: &NameRegistry,
+) {
+    let Some(camera_entity) = registry.get_entity("main_camera") else {
+        return;
+    };
+    commands
+        .entity(camera_entity)
+        .insert(export_camera_components(export));
+}
+
+/// Camera components that realize the export settings.
+pub(super) fn export_camera_components(export: &ExportSettingsData) -> (Msaa, Tonemapping) {
+    let msaa = match export.msaa_samples {
+        2 => Msaa::Sample2,
+        4 => Msaa::Sample4,
+        8 => Msaa::Sample8,
+        _ => Msaa::Off,
+    };
+    let tonemapping = match export.tonemapping {
+        TonemappingMode::None => Tonemapping::None,
+        TonemappingMode::Reinhard => Tonemapping::Reinhard,
+        TonemappingMode::ReinhardLuminance => Tonemapping::ReinhardLuminance,
+        TonemappingMode::AcesFitted => Tonemapping::AcesFitted,
+        TonemappingMode::Agx => Tonemapping::AgX,
+        TonemappingMode::TonyMcMapface => Tonemapping::TonyMcMapface,
+        TonemappingMode::BlenderFilmic => Tonemapping::BlenderFilmic,
+    };
+    (msaa, tonemapping)
+}
+
 fn handle_spawn_mesh(
     cmd: RawMeshCmd,
     commands: &mut Commands,
@@ -842,6 +1838,76 @@ fn handle_spawn_mesh(
     }
 }
 
+fn handle_spawn_terrain(
+    cmd: SpawnTerrainCmd,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    registry: &mut ResMut<NameRegistry>,
+) -> GenResponse {
+    if registry.contains_name(&cmd.name) {
+        return GenResponse::Error {
+            message: format!("Entity '{}' already exists", cmd.name),
+        };
+    }
+
+    if cmd.size[0] <= 0.0 || cmd.size[1] <= 0.0 {
+        return GenResponse::Error {
+            message: "Terrain size must be positive".to_string(),
+        };
+    }
+
+    let heightfield = match Heightfield::generate(&cmd.heightmap, cmd.size, cmd.resolution) {
+        Ok(h) => h,
+        Err(e) => {
+            return GenResponse::Error {
+                message: e.to_string(),
+            };
+        }
+    };
+    let (min_height, max_height) = heightfield.height_range();
+    let data = heightfield.to_mesh_data(cmd.size);
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, data.positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, data.normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, data.uvs);
+    mesh.insert_indices(Indices::U32(data.indices));
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgba(cmd.color[0], cmd.color[1], cmd.color[2], cmd.color[3]),
+        perceptual_roughness: cmd.roughness,
+        ..default()
+    });
+
+    let entity = commands
+        .spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(material),
+            Transform::from_translation(Vec3::from_array(cmd.position)),
+            Name::new(cmd.name.clone()),
+            GenEntity {
+                entity_type: GenEntityType::Mesh,
+            },
+        ))
+        .id();
+
+    let entity_id = entity.to_bits();
+    registry.insert(cmd.name.clone(), entity);
+
+    GenResponse::TerrainSpawned(TerrainInfoData {
+        name: cmd.name,
+        entity_id,
+        resolution: heightfield.resolution,
+        triangle_count: heightfield.triangle_count(),
+        min_height,
+        max_height,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Fly camera systems
 // ---------------------------------------------------------------------------
@@ -853,6 +1919,10 @@ fn fly_cam_movement(
     config: Res<FlyCamConfig>,
     mut query: Query<&mut Transform, With<FlyCam>>,
 ) {
+    if !config.enabled {
+        return;
+    }
+
     let Ok(mut transform) = query.get_single_mut() else {
         return;
     };
@@ -893,7 +1963,7 @@ fn fly_cam_look(
     mut query: Query<&mut Transform, With<FlyCam>>,
 ) {
     let delta: Vec2 = motion_reader.read().map(|e| e.delta).sum();
-    if delta == Vec2::ZERO || !mouse.pressed(MouseButton::Right) {
+    if !config.enabled || delta == Vec2::ZERO || !mouse.pressed(MouseButton::Right) {
         return;
     }
 
@@ -927,6 +1997,73 @@ fn fly_cam_scroll_speed(
     mut config: ResMut<FlyCamConfig>,
 ) {
     for event in scroll_reader.read() {
+        if !config.enabled {
+            continue;
+        }
         config.move_speed = (config.move_speed * (1.0 + event.y * 0.1)).clamp(0.5, 100.0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::mesh::VertexAttributeValues;
+
+    fn positions(mesh: &Mesh) -> Vec<Vec3> {
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("mesh has no positions");
+        };
+        positions.iter().copied().map(Vec3::from_array).collect()
+    }
+
+    #[test]
+    fn wedge_fills_its_bounding_box() {
+        let mesh = wedge_mesh(2.0, 1.0, 4.0);
+        let positions = positions(&mesh);
+        // Two end triangles and three quads, flat shaded
+        assert_eq!(positions.len(), (2 + 3 * 2) * 3);
+
+        let min = positions.iter().copied().reduce(Vec3::min).unwrap();
+        let max = positions.iter().copied().reduce(Vec3::max).unwrap();
+        assert_eq!(min, Vec3::new(-1.0, -0.5, -2.0));
+        assert_eq!(max, Vec3::new(1.0, 0.5, 2.0));
+    }
+
+    #[test]
+    fn wedge_faces_point_outward() {
+        let mesh = wedge_mesh(2.0, 1.0, 4.0);
+        // Centroid of the triangular profile
+        let inside = Vec3::new(0.0, -0.5 + 1.0 / 3.0, -2.0 + 4.0 / 3.0);
+        for triangle in positions(&mesh).chunks_exact(3) {
+            let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+            let center = (triangle[0] + triangle[1] + triangle[2]) / 3.0;
+            assert!(normal.dot(center - inside) > 0.0, "{:?}", triangle);
+        }
+
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("wedge has no normals");
+        };
+        assert!(
+            normals
+                .iter()
+                .all(|&n| (Vec3::from_array(n).length() - 1.0).abs() < 1e-5)
+        );
+    }
+
+    #[test]
+    fn icosphere_builds_at_the_subdivision_cap() {
+        let mesh = Sphere::new(0.5)
+            .mesh()
+            .ico(MAX_ICOSPHERE_SUBDIVISIONS)
+            .unwrap();
+        assert!(
+            positions(&mesh)
+                .iter()
+                .all(|p| (p.length() - 0.5).abs() < 1e-4)
+        );
+    }
+}
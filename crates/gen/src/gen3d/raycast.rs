@@ -0,0 +1,324 @@
+//! Ray casts against the triangles of every visible mesh in the scene.
+//!
+//! Each mesh is tested in its own local space: the ray is transformed by
+//! the inverse of the entity's global transform, culled against the mesh's
+//! AABB, then intersected with each triangle (both sides count). Distances
+//! stay in world units because the ray direction is transformed along with
+//! its origin. Hits on unregistered children, such as the copies in an
+//! instanced field, are reported under the nearest registered ancestor.
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
+use bevy::render::primitives::Aabb;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use super::commands::{GenResponse, RayHit, RaycastData};
+use super::registry::NameRegistry;
+
+pub fn raycast(
+    world: &mut World,
+    origin: [f32; 3],
+    direction: [f32; 3],
+    max_distance: f32,
+) -> GenResponse {
+    let origin = Vec3::from_array(origin);
+    let direction = Vec3::from_array(direction).normalize_or_zero();
+    if !origin.is_finite() || direction == Vec3::ZERO {
+        return GenResponse::Error {
+            message: "Ray needs a finite origin and a non-zero direction".to_string(),
+        };
+    }
+    if !max_distance.is_finite() || max_distance <= 0.0 {
+        return GenResponse::Error {
+            message: "max_distance must be positive".to_string(),
+        };
+    }
+
+    let mut best: Option<(Entity, f32, Vec3)> = None;
+    let mut candidates = world.query::<(
+        Entity,
+        &Mesh3d,
+        &GlobalTransform,
+        Option<&Aabb>,
+        Option<&InheritedVisibility>,
+    )>();
+    let meshes = world.resource::<Assets<Mesh>>();
+    for (entity, mesh, global, aabb, visibility) in candidates.iter(world) {
+        if visibility.is_some_and(|v| !v.get()) {
+            continue;
+        }
+        let Some(mesh) = meshes.get(&mesh.0) else {
+            continue;
+        };
+
+        let to_local = global.compute_matrix().inverse();
+        let local_origin = to_local.transform_point3(origin);
+        let local_direction = to_local.transform_vector3(direction);
+        let limit = best.map_or(max_distance, |(_, t, _)| t);
+        if let Some(aabb) = aabb
+            && !ray_hits_aabb(local_origin, local_direction, aabb, limit)
+        {
+            continue;
+        }
+
+        if let Some((t, local_normal)) = intersect_mesh(mesh, local_origin, local_direction, limit)
+        {
+            // Normals transform by the inverse transpose
+            let normal = to_local
+                .transpose()
+                .transform_vector3(local_normal)
+                .normalize_or_zero();
+            best = Some((entity, t, normal));
+        }
+    }
+
+    let hit = best.and_then(|(entity, distance, normal)| {
+        let name = registered_ancestor(world, entity)?;
+        // Report the side the ray came from
+        let normal = if normal.dot(direction) > 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        Some(RayHit {
+            name,
+            point: (origin + direction * distance).to_array(),
+            normal: normal.to_array(),
+            distance,
+        })
+    });
+
+    GenResponse::Raycast(RaycastData { hit })
+}
+
+/// Name of `entity` or of its nearest registered ancestor.
+pub(super) fn registered_ancestor(world: &World, mut entity: Entity) -> Option<String> {
+    let registry = world.resource::<NameRegistry>();
+    loop {
+        if let Some(name) = registry.get_name(entity) {
+            return Some(name.to_string());
+        }
+        entity = world.get::<Parent>(entity)?.get();
+    }
+}
+
+/// Slab test: whether the ray enters `aabb` within `limit`.
+fn ray_hits_aabb(origin: Vec3, direction: Vec3, aabb: &Aabb, limit: f32) -> bool {
+    let min = Vec3::from(aabb.min());
+    let max = Vec3::from(aabb.max());
+    let inverse = direction.recip();
+    let t1 = (min - origin) * inverse;
+    let t2 = (max - origin) * inverse;
+    let near = t1.min(t2).max_element();
+    let far = t1.max(t2).min_element();
+    // NaN from a zero direction component on a slab boundary fails open
+    !(near > far || far < 0.0 || near > limit)
+}
+
+/// Nearest triangle hit within `limit`: its distance and the triangle's
+/// local-space normal.
+fn intersect_mesh(mesh: &Mesh, origin: Vec3, direction: Vec3, limit: f32) -> Option<(f32, Vec3)> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+
+    let corner = |i: usize| positions.get(i).copied().map(Vec3::from);
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.iter().map(|&i| i as usize).collect(),
+        Some(Indices::U32(indices)) => indices.iter().map(|&i| i as usize).collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    let mut best: Option<(f32, Vec3)> = None;
+    for tri in indices.chunks_exact(3) {
+        let (Some(a), Some(b), Some(c)) = (corner(tri[0]), corner(tri[1]), corner(tri[2])) else {
+            continue;
+        };
+        let corners = [a, b, c];
+        let limit = best.map_or(limit, |(t, _)| t);
+        if let Some(t) = intersect_triangle(origin, direction, corners)
+            && t <= limit
+        {
+            let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+            best = Some((t, normal));
+        }
+    }
+    best
+}
+
+/// Möller–Trumbore ray/triangle intersection, ignoring winding.
+fn intersect_triangle(origin: Vec3, direction: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    const EPSILON: f32 = 1e-7;
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = origin - a;
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(q) * inv_det;
+    (t >= 0.0).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_asset::RenderAssetUsages;
+
+    fn unit_box() -> Aabb {
+        Aabb::from_min_max(Vec3::splat(-1.0), Vec3::splat(1.0))
+    }
+
+    #[test]
+    fn ray_hits_aabb_in_front() {
+        assert!(ray_hits_aabb(
+            Vec3::new(0.0, 0.0, -5.0),
+            Vec3::Z,
+            &unit_box(),
+            10.0
+        ));
+        assert!(ray_hits_aabb(
+            Vec3::new(-5.0, -5.0, -5.0),
+            Vec3::ONE.normalize(),
+            &unit_box(),
+            10.0
+        ));
+        // Starting inside
+        assert!(ray_hits_aabb(Vec3::ZERO, Vec3::X, &unit_box(), 0.5));
+    }
+
+    #[test]
+    fn ray_misses_aabb() {
+        // Passes beside it
+        assert!(!ray_hits_aabb(
+            Vec3::new(3.0, 0.0, -5.0),
+            Vec3::Z,
+            &unit_box(),
+            10.0
+        ));
+        // Points away from it
+        assert!(!ray_hits_aabb(
+            Vec3::new(0.0, 0.0, 5.0),
+            Vec3::Z,
+            &unit_box(),
+            10.0
+        ));
+        // Too short to reach it
+        assert!(!ray_hits_aabb(
+            Vec3::new(0.0, 0.0, -5.0),
+            Vec3::Z,
+            &unit_box(),
+            3.0
+        ));
+    }
+
+    const TRIANGLE: [Vec3; 3] = [
+        Vec3::new(-1.0, -1.0, 0.0),
+        Vec3::new(1.0, -1.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+    ];
+
+    #[test]
+    fn ray_hits_triangle_from_either_side() {
+        let t = intersect_triangle(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, TRIANGLE).unwrap();
+        assert!((t - 5.0).abs() < 1e-5);
+        let t = intersect_triangle(Vec3::new(0.0, 0.0, 2.0), Vec3::NEG_Z, TRIANGLE).unwrap();
+        assert!((t - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_misses_triangle() {
+        // Outside the edges
+        assert!(intersect_triangle(Vec3::new(2.0, 0.0, -5.0), Vec3::Z, TRIANGLE).is_none());
+        // Behind the origin
+        assert!(intersect_triangle(Vec3::new(0.0, 0.0, 5.0), Vec3::Z, TRIANGLE).is_none());
+        // Parallel to the plane
+        assert!(intersect_triangle(Vec3::new(0.0, 0.0, -1.0), Vec3::X, TRIANGLE).is_none());
+    }
+
+    fn quad_mesh() -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![
+                [-1.0, -1.0, 0.0],
+                [1.0, -1.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [-1.0, 1.0, 0.0],
+            ],
+        );
+        mesh.insert_indices(Indices::U32(vec![0, 1, 2, 0, 2, 3]));
+        mesh
+    }
+
+    #[test]
+    fn intersect_mesh_respects_limit() {
+        let mesh = quad_mesh();
+        let origin = Vec3::new(-0.5, 0.5, -3.0);
+        let (t, normal) = intersect_mesh(&mesh, origin, Vec3::Z, 10.0).unwrap();
+        assert!((t - 3.0).abs() < 1e-5);
+        assert!(normal.normalize().abs_diff_eq(Vec3::Z, 1e-5));
+        assert!(intersect_mesh(&mesh, origin, Vec3::Z, 2.0).is_none());
+    }
+
+    #[test]
+    fn raycast_reports_registered_ancestor_in_world_units() {
+        let mut world = World::new();
+        world.init_resource::<NameRegistry>();
+        world.init_resource::<Assets<Mesh>>();
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(quad_mesh());
+
+        // A scaled, moved child of a registered group
+        let group = world.spawn(GlobalTransform::IDENTITY).id();
+        world
+            .resource_mut::<NameRegistry>()
+            .insert("wall".to_string(), group);
+        let child_transform = Transform::from_xyz(0.0, 0.0, 4.0).with_scale(Vec3::splat(2.0));
+        let child = world
+            .spawn((
+                Mesh3d(mesh),
+                GlobalTransform::from(child_transform),
+                // Nothing propagates visibility without an app
+                InheritedVisibility::VISIBLE,
+            ))
+            .id();
+        world.entity_mut(group).add_child(child);
+
+        let GenResponse::Raycast(RaycastData { hit: Some(hit) }) =
+            raycast(&mut world, [1.5, 0.0, 0.0], [0.0, 0.0, 1.0], 100.0)
+        else {
+            panic!("expected a hit");
+        };
+        assert_eq!(hit.name, "wall");
+        assert!((hit.distance - 4.0).abs() < 1e-4);
+        assert!(Vec3::from_array(hit.point).abs_diff_eq(Vec3::new(1.5, 0.0, 4.0), 1e-4));
+        assert!(Vec3::from_array(hit.normal).abs_diff_eq(Vec3::NEG_Z, 1e-4));
+
+        // Scaled to 4x4, so x = 2.5 misses
+        let GenResponse::Raycast(RaycastData { hit }) =
+            raycast(&mut world, [2.5, 0.0, 0.0], [0.0, 0.0, 1.0], 100.0)
+        else {
+            panic!("expected a raycast response");
+        };
+        assert!(hit.is_none());
+    }
+}
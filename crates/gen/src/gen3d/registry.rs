@@ -13,6 +13,19 @@ pub struct GenEntity {
     pub entity_type: GenEntityType,
 }
 
+/// User-assigned tags and key/value metadata on a Gen entity.
+#[derive(Component, Debug, Clone, Default)]
+pub struct GenTags {
+    pub tags: Vec<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+impl GenTags {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GenEntityType {
     Primitive,
@@ -20,6 +33,7 @@ pub enum GenEntityType {
     Camera,
     Mesh,
     Group,
+    Text,
 }
 
 impl GenEntityType {
@@ -30,6 +44,7 @@ impl GenEntityType {
             Self::Camera => "camera",
             Self::Mesh => "mesh",
             Self::Group => "group",
+            Self::Text => "text",
         }
     }
 }
@@ -64,6 +79,7 @@ impl NameRegistry {
         }
     }
 
+    #[allow(dead_code)]
     pub fn remove_by_entity(&mut self, entity: Entity) -> Option<String> {
         if let Some(name) = self.entity_to_name.remove(&entity) {
             self.name_to_entity.remove(&name);
@@ -77,6 +93,10 @@ impl NameRegistry {
         self.name_to_entity.contains_key(name)
     }
 
+    pub fn contains_entity(&self, entity: Entity) -> bool {
+        self.entity_to_name.contains_key(&entity)
+    }
+
     pub fn all_names(&self) -> impl Iterator<Item = (&str, Entity)> {
         self.name_to_entity.iter().map(|(k, v)| (k.as_str(), *v))
     }
@@ -85,6 +105,7 @@ impl NameRegistry {
         self.name_to_entity.len()
     }
 
+    #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
         self.name_to_entity.is_empty()
     }
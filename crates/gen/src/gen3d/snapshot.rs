@@ -0,0 +1,273 @@
+//! In-memory scene snapshots for branch-and-compare workflows.
+//!
+//! A snapshot records every registered entity's transform, visibility,
+//! parent, mesh/material handles and light settings, plus the clear color
+//! and ambient light. Mesh and material assets are never mutated in place
+//! (modify_entity swaps in a new material), so holding the handles is
+//! enough to restore them exactly.
+//!
+//! Instanced fields keep their baked mesh in an `InstancedField` component,
+//! and text labels their renderer in a `Text3dLabel`, so their
+//! unregistered children are rebuilt from it on restore.
+//!
+//! Restoring despawns the current entities and respawns the snapshot's.
+//! The camera entity is kept (it carries render-quality components) and
+//! only gets its pose and projection back.
+
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::commands::{GenResponse, SnapshotInfo};
+use super::instancing::InstancedField;
+use super::registry::{GenEntity, GenEntityType, GenTags, NameRegistry};
+use super::text3d::Text3dLabel;
+
+/// Saved snapshots by label.
+#[derive(Resource, Default)]
+pub struct SceneSnapshots {
+    snapshots: HashMap<String, SceneSnapshot>,
+}
+
+struct SceneSnapshot {
+    entities: Vec<EntitySnapshot>,
+    clear_color: Option<ClearColor>,
+    ambient_light: Option<AmbientLight>,
+}
+
+struct EntitySnapshot {
+    name: String,
+    entity_type: GenEntityType,
+    transform: Transform,
+    visibility: Option<Visibility>,
+    parent: Option<String>,
+    mesh: Option<Mesh3d>,
+    material: Option<MeshMaterial3d<StandardMaterial>>,
+    light: Option<LightSnapshot>,
+    projection: Option<Projection>,
+    instances: Option<InstancedField>,
+    text: Option<Text3dLabel>,
+    tags: Option<GenTags>,
+    casts_shadows: bool,
+    receives_shadows: bool,
+}
+
+enum LightSnapshot {
+    Directional(DirectionalLight),
+    Point(PointLight),
+    Spot(SpotLight),
+}
+
+pub fn snapshot_scene(world: &mut World, label: String) -> GenResponse {
+    let registry = world.resource::<NameRegistry>();
+
+    let mut entities = Vec::with_capacity(registry.len());
+    for (name, entity) in registry.all_names() {
+        let Ok(entity_ref) = world.get_entity(entity) else {
+            continue;
+        };
+        let Some(gen_entity) = entity_ref.get::<GenEntity>() else {
+            continue;
+        };
+
+        let light = if let Some(l) = entity_ref.get::<DirectionalLight>() {
+            Some(LightSnapshot::Directional(l.clone()))
+        } else if let Some(l) = entity_ref.get::<PointLight>() {
+            Some(LightSnapshot::Point(*l))
+        } else {
+            entity_ref
+                .get::<SpotLight>()
+                .map(|l| LightSnapshot::Spot(*l))
+        };
+
+        entities.push(EntitySnapshot {
+            name: name.to_string(),
+            entity_type: gen_entity.entity_type,
+            transform: entity_ref.get::<Transform>().copied().unwrap_or_default(),
+            visibility: entity_ref.get::<Visibility>().copied(),
+            parent: entity_ref
+                .get::<Parent>()
+                .and_then(|p| registry.get_name(p.get()))
+                .map(str::to_string),
+            mesh: entity_ref.get::<Mesh3d>().cloned(),
+            material: entity_ref
+                .get::<MeshMaterial3d<StandardMaterial>>()
+                .cloned(),
+            light,
+            projection: entity_ref.get::<Projection>().cloned(),
+            instances: entity_ref.get::<InstancedField>().cloned(),
+            text: entity_ref.get::<Text3dLabel>().cloned(),
+            tags: entity_ref.get::<GenTags>().cloned(),
+            casts_shadows: !entity_ref.contains::<NotShadowCaster>(),
+            receives_shadows: !entity_ref.contains::<NotShadowReceiver>(),
+        });
+    }
+    entities.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let snapshot = SceneSnapshot {
+        entities,
+        clear_color: world.get_resource::<ClearColor>().cloned(),
+        ambient_light: world.get_resource::<AmbientLight>().cloned(),
+    };
+    let info = SnapshotInfo {
+        label: label.clone(),
+        entity_count: snapshot.entities.len(),
+    };
+
+    world
+        .resource_mut::<SceneSnapshots>()
+        .snapshots
+        .insert(label, snapshot);
+
+    GenResponse::SnapshotSaved(info)
+}
+
+pub fn restore_snapshot(world: &mut World, label: String) -> GenResponse {
+    // Take the snapshot out while the world is rebuilt; it goes back after.
+    let Some(snapshot) = world
+        .resource_mut::<SceneSnapshots>()
+        .snapshots
+        .remove(&label)
+    else {
+        return GenResponse::Error {
+            message: format!("Snapshot '{}' not found", label),
+        };
+    };
+
+    // Despawn everything except cameras
+    let current: Vec<(String, Entity)> = world
+        .resource::<NameRegistry>()
+        .all_names()
+        .map(|(name, entity)| (name.to_string(), entity))
+        .collect();
+    for (name, entity) in current {
+        let is_camera = world
+            .get::<GenEntity>(entity)
+            .is_some_and(|g| g.entity_type == GenEntityType::Camera);
+        if is_camera {
+            continue;
+        }
+        world.resource_mut::<NameRegistry>().remove_by_name(&name);
+        if let Ok(entity_mut) = world.get_entity_mut(entity) {
+            entity_mut.despawn_recursive();
+        }
+    }
+
+    // Respawn, then reattach parents once every entity exists
+    for saved in &snapshot.entities {
+        if saved.entity_type == GenEntityType::Camera {
+            if let Some(camera) = world.resource::<NameRegistry>().get_entity(&saved.name)
+                && let Ok(mut camera) = world.get_entity_mut(camera)
+            {
+                camera.insert(saved.transform);
+                if let Some(ref projection) = saved.projection {
+                    camera.insert(projection.clone());
+                }
+                match saved.tags {
+                    Some(ref tags) => {
+                        camera.insert(tags.clone());
+                    }
+                    None => {
+                        camera.remove::<GenTags>();
+                    }
+                }
+            }
+            continue;
+        }
+
+        let mut entity = world.spawn((
+            saved.transform,
+            Name::new(saved.name.clone()),
+            GenEntity {
+                entity_type: saved.entity_type,
+            },
+        ));
+        if let Some(visibility) = saved.visibility {
+            entity.insert(visibility);
+        }
+        if let Some(ref mesh) = saved.mesh {
+            entity.insert(mesh.clone());
+        }
+        if let Some(ref material) = saved.material {
+            entity.insert(material.clone());
+        }
+        if let Some(ref tags) = saved.tags {
+            entity.insert(tags.clone());
+        }
+        if !saved.casts_shadows {
+            entity.insert(NotShadowCaster);
+        }
+        if !saved.receives_shadows {
+            entity.insert(NotShadowReceiver);
+        }
+        match saved.light {
+            Some(LightSnapshot::Directional(ref l)) => {
+                entity.insert(l.clone());
+            }
+            Some(LightSnapshot::Point(ref l)) => {
+                entity.insert(*l);
+            }
+            Some(LightSnapshot::Spot(ref l)) => {
+                entity.insert(*l);
+            }
+            None => {}
+        }
+
+        let id = entity.id();
+        if let Some(ref field) = saved.instances {
+            field.spawn_instances(world, id);
+            world.entity_mut(id).insert(field.clone());
+        }
+        if let Some(ref label) = saved.text {
+            label.spawn_renderer(world, id);
+            world.entity_mut(id).insert(label.clone());
+        }
+        world
+            .resource_mut::<NameRegistry>()
+            .insert(saved.name.clone(), id);
+    }
+
+    for saved in &snapshot.entities {
+        let Some(ref parent_name) = saved.parent else {
+            continue;
+        };
+        let registry = world.resource::<NameRegistry>();
+        if let (Some(child), Some(parent)) = (
+            registry.get_entity(&saved.name),
+            registry.get_entity(parent_name),
+        ) {
+            world.entity_mut(child).set_parent(parent);
+        }
+    }
+
+    if let Some(ref clear_color) = snapshot.clear_color {
+        world.insert_resource(clear_color.clone());
+    }
+    if let Some(ref ambient_light) = snapshot.ambient_light {
+        world.insert_resource(ambient_light.clone());
+    }
+
+    let info = SnapshotInfo {
+        label: label.clone(),
+        entity_count: snapshot.entities.len(),
+    };
+    world
+        .resource_mut::<SceneSnapshots>()
+        .snapshots
+        .insert(label, snapshot);
+
+    GenResponse::SnapshotRestored(info)
+}
+
+pub fn list_snapshots(snapshots: &SceneSnapshots) -> GenResponse {
+    let mut snapshots: Vec<SnapshotInfo> = snapshots
+        .snapshots
+        .iter()
+        .map(|(label, snapshot)| SnapshotInfo {
+            label: label.clone(),
+            entity_count: snapshot.entities.len(),
+        })
+        .collect();
+    snapshots.sort_by(|a, b| a.label.cmp(&b.label));
+    GenResponse::Snapshots { snapshots }
+}
@@ -0,0 +1,366 @@
+//! Terrain generation — heightfields from procedural noise or grayscale
+//! images, triangulated into a subdivided plane with smooth normals.
+
+use std::path::Path;
+
+use super::commands::HeightmapSource;
+
+/// Maximum quads per side. 512² quads ≈ 263k vertices / 524k triangles.
+pub const MAX_TERRAIN_RESOLUTION: u32 = 512;
+
+/// Grid of heights sampled at `(resolution + 1)²` vertices, row-major
+/// from -Z to +Z and -X to +X.
+pub struct Heightfield {
+    pub resolution: u32,
+    pub heights: Vec<f32>,
+}
+
+impl Heightfield {
+    /// Sample a heightfield for a terrain of `size` world units.
+    pub fn generate(
+        source: &HeightmapSource,
+        size: [f32; 2],
+        resolution: u32,
+    ) -> anyhow::Result<Self> {
+        let resolution = resolution.clamp(1, MAX_TERRAIN_RESOLUTION);
+        let verts = (resolution + 1) as usize;
+
+        let heights = match source {
+            HeightmapSource::Noise {
+                seed,
+                octaves,
+                amplitude,
+                frequency,
+            } => {
+                let noise = Perlin::new(*seed);
+                let octaves = (*octaves).clamp(1, 8);
+                let mut heights = Vec::with_capacity(verts * verts);
+                for row in 0..verts {
+                    for col in 0..verts {
+                        let (x, z) = grid_to_world(col, row, resolution, size);
+                        heights.push(noise.fbm(x * frequency, z * frequency, octaves) * amplitude);
+                    }
+                }
+                heights
+            }
+            HeightmapSource::Image { path, amplitude } => {
+                let image = load_grayscale(Path::new(path))?;
+                let mut heights = Vec::with_capacity(verts * verts);
+                for row in 0..verts {
+                    for col in 0..verts {
+                        let u = col as f32 / resolution as f32;
+                        let v = row as f32 / resolution as f32;
+                        heights.push(image.sample(u, v) * amplitude);
+                    }
+                }
+                heights
+            }
+        };
+
+        Ok(Self {
+            resolution,
+            heights,
+        })
+    }
+
+    pub fn height_range(&self) -> (f32, f32) {
+        self.heights
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| {
+                (lo.min(h), hi.max(h))
+            })
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        (self.resolution as usize).pow(2) * 2
+    }
+
+    /// Build vertex positions, smooth normals, UVs and triangle indices,
+    /// centered on the origin.
+    pub fn to_mesh_data(&self, size: [f32; 2]) -> TerrainMeshData {
+        let res = self.resolution as usize;
+        let verts = res + 1;
+        let step_x = size[0] / res as f32;
+        let step_z = size[1] / res as f32;
+        let height = |col: usize, row: usize| self.heights[row * verts + col];
+
+        let mut positions = Vec::with_capacity(verts * verts);
+        let mut normals = Vec::with_capacity(verts * verts);
+        let mut uvs = Vec::with_capacity(verts * verts);
+
+        for row in 0..verts {
+            for col in 0..verts {
+                let (x, z) = grid_to_world(col, row, self.resolution, size);
+                positions.push([x, height(col, row), z]);
+                uvs.push([col as f32 / res as f32, row as f32 / res as f32]);
+
+                // Central differences (one-sided at the edges)
+                let (left, right) = (col.saturating_sub(1), (col + 1).min(res));
+                let (back, front) = (row.saturating_sub(1), (row + 1).min(res));
+                let dx =
+                    (height(right, row) - height(left, row)) / ((right - left) as f32 * step_x);
+                let dz =
+                    (height(col, front) - height(col, back)) / ((front - back) as f32 * step_z);
+                let n = [-dx, 1.0, -dz];
+                let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+                normals.push([n[0] / len, n[1] / len, n[2] / len]);
+            }
+        }
+
+        let mut indices = Vec::with_capacity(self.triangle_count() * 3);
+        for row in 0..res {
+            for col in 0..res {
+                let i0 = (row * verts + col) as u32;
+                let i1 = i0 + 1;
+                let i2 = i0 + verts as u32;
+                let i3 = i2 + 1;
+                // Counter-clockwise when viewed from above (+Y)
+                indices.extend_from_slice(&[i0, i2, i1, i1, i2, i3]);
+            }
+        }
+
+        TerrainMeshData {
+            positions,
+            normals,
+            uvs,
+            indices,
+        }
+    }
+}
+
+pub struct TerrainMeshData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+fn grid_to_world(col: usize, row: usize, resolution: u32, size: [f32; 2]) -> (f32, f32) {
+    let x = (col as f32 / resolution as f32 - 0.5) * size[0];
+    let z = (row as f32 / resolution as f32 - 0.5) * size[1];
+    (x, z)
+}
+
+// ---------------------------------------------------------------------------
+// Image heightmaps
+// ---------------------------------------------------------------------------
+
+struct GrayImage {
+    width: usize,
+    height: usize,
+    /// Normalized luminance in 0.0..=1.0
+    pixels: Vec<f32>,
+}
+
+fn load_grayscale(path: &Path) -> anyhow::Result<GrayImage> {
+    let image = image::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to load heightmap {}: {}", path.display(), e))?
+        .to_luma16();
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        anyhow::bail!("Heightmap {} is empty", path.display());
+    }
+    Ok(GrayImage {
+        width: width as usize,
+        height: height as usize,
+        pixels: image
+            .into_raw()
+            .into_iter()
+            .map(|p| p as f32 / u16::MAX as f32)
+            .collect(),
+    })
+}
+
+impl GrayImage {
+    /// Bilinear sample at normalized coordinates.
+    fn sample(&self, u: f32, v: f32) -> f32 {
+        let x = u.clamp(0.0, 1.0) * (self.width - 1) as f32;
+        let y = v.clamp(0.0, 1.0) * (self.height - 1) as f32;
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+
+        let px = |x: usize, y: usize| self.pixels[y * self.width + x];
+        let top = px(x0, y0) + (px(x1, y0) - px(x0, y0)) * tx;
+        let bottom = px(x0, y1) + (px(x1, y1) - px(x0, y1)) * tx;
+        top + (bottom - top) * ty
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Perlin noise
+// ---------------------------------------------------------------------------
+
+/// Seeded 2D Perlin gradient noise.
+struct Perlin {
+    perm: [u8; 512],
+}
+
+impl Perlin {
+    fn new(seed: u32) -> Self {
+        let mut table: [u8; 256] = std::array::from_fn(|i| i as u8);
+
+        // Fisher–Yates shuffle driven by a xorshift PRNG
+        let mut state = seed as u64 ^ 0x9E37_79B9_7F4A_7C15;
+        for i in (1..256).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            table.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+
+        let mut perm = [0u8; 512];
+        for (i, slot) in perm.iter_mut().enumerate() {
+            *slot = table[i & 255];
+        }
+        Self { perm }
+    }
+
+    /// Noise in roughly -1.0..=1.0.
+    fn noise(&self, x: f32, y: f32) -> f32 {
+        let (xf, yf) = (x.floor(), y.floor());
+        let (xi, yi) = ((xf as i32 & 255) as usize, (yf as i32 & 255) as usize);
+        let (x, y) = (x - xf, y - yf);
+        let (u, v) = (fade(x), fade(y));
+
+        let p = &self.perm;
+        let aa = p[p[xi] as usize + yi];
+        let ab = p[p[xi] as usize + yi + 1];
+        let ba = p[p[xi + 1] as usize + yi];
+        let bb = p[p[xi + 1] as usize + yi + 1];
+
+        let x1 = lerp(grad(aa, x, y), grad(ba, x - 1.0, y), u);
+        let x2 = lerp(grad(ab, x, y - 1.0), grad(bb, x - 1.0, y - 1.0), u);
+        lerp(x1, x2, v)
+    }
+
+    /// Fractal Brownian motion: `octaves` layers at doubling frequency and
+    /// halving amplitude, normalized back to roughly -1.0..=1.0.
+    fn fbm(&self, x: f32, y: f32, octaves: u32) -> f32 {
+        let (mut sum, mut amp, mut freq, mut norm) = (0.0, 1.0, 1.0, 0.0);
+        for _ in 0..octaves {
+            sum += self.noise(x * freq, y * freq) * amp;
+            norm += amp;
+            amp *= 0.5;
+            freq *= 2.0;
+        }
+        sum / norm
+    }
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn grad(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perlin_permutation_is_a_doubled_permutation() {
+        for seed in [0, 1, 42, u32::MAX] {
+            let perlin = Perlin::new(seed);
+            let mut seen = [false; 256];
+            for &value in &perlin.perm[..256] {
+                assert!(!seen[value as usize], "seed {} repeats {}", seed, value);
+                seen[value as usize] = true;
+            }
+            assert_eq!(perlin.perm[..256], perlin.perm[256..]);
+        }
+    }
+
+    #[test]
+    fn perlin_noise_stays_in_bounds_at_extreme_coordinates() {
+        let perlin = Perlin::new(7);
+        for &(x, y) in &[
+            (0.0, 0.0),
+            (255.999, 255.999),
+            (256.5, -0.5),
+            (-1.0e-3, -255.5),
+            (1.0e6, -1.0e6),
+            (-12345.678, 9876.543),
+        ] {
+            let n = perlin.noise(x, y);
+            assert!(
+                n.is_finite() && n.abs() <= 2.0,
+                "noise({}, {}) = {}",
+                x,
+                y,
+                n
+            );
+            let f = perlin.fbm(x, y, 8);
+            assert!(f.is_finite() && f.abs() <= 2.0, "fbm({}, {}) = {}", x, y, f);
+        }
+    }
+
+    #[test]
+    fn perlin_noise_is_zero_on_lattice_points_and_seeded() {
+        let perlin = Perlin::new(3);
+        for (x, y) in [(0.0, 0.0), (5.0, -3.0), (255.0, 256.0)] {
+            assert_eq!(perlin.noise(x, y), 0.0);
+        }
+        assert_eq!(perlin.noise(0.3, 0.7), Perlin::new(3).noise(0.3, 0.7));
+        assert_ne!(perlin.perm, Perlin::new(4).perm);
+    }
+
+    fn noise_source() -> HeightmapSource {
+        HeightmapSource::Noise {
+            seed: 1,
+            octaves: 4,
+            amplitude: 2.0,
+            frequency: 0.1,
+        }
+    }
+
+    #[test]
+    fn heightfield_resolution_is_clamped() {
+        let field = Heightfield::generate(&noise_source(), [10.0, 10.0], 0).unwrap();
+        assert_eq!(field.resolution, 1);
+        assert_eq!(field.heights.len(), 4);
+
+        let field = Heightfield::generate(&noise_source(), [10.0, 10.0], 100_000).unwrap();
+        assert_eq!(field.resolution, MAX_TERRAIN_RESOLUTION);
+        assert_eq!(
+            field.heights.len(),
+            (MAX_TERRAIN_RESOLUTION as usize + 1).pow(2)
+        );
+    }
+
+    #[test]
+    fn flat_heightfield_mesh() {
+        let field = Heightfield {
+            resolution: 2,
+            heights: vec![0.0; 9],
+        };
+        let data = field.to_mesh_data([4.0, 2.0]);
+        assert_eq!(data.positions.len(), 9);
+        assert_eq!(data.indices.len(), field.triangle_count() * 3);
+        assert!(
+            data.indices
+                .iter()
+                .all(|&i| (i as usize) < data.positions.len())
+        );
+        assert_eq!(data.positions[0], [-2.0, 0.0, -1.0]);
+        assert_eq!(data.positions[8], [2.0, 0.0, 1.0]);
+        assert!(data.normals.iter().all(|&n| n == [0.0, 1.0, 0.0]));
+        assert_eq!(data.uvs[8], [1.0, 1.0]);
+        assert_eq!(field.height_range(), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,314 @@
+//! 3D text labels — text rendered into a texture shown on a quad.
+//!
+//! Bevy has no 3D text, so each label owns an offscreen 2D camera that
+//! renders a `Text2d` into an image on a render layer of its own. The
+//! registered label entity is an unlit, double-sided quad textured with
+//! that image; the camera and text are unregistered children, so deleting
+//! the label removes them too. The text is drawn white and tinted by the
+//! material's base color, so modify_entity's `color` recolors it.
+//!
+//! Billboarded labels turn to face the main camera every frame.
+
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::view::RenderLayers;
+
+use super::commands::{GenResponse, SpawnText3DCmd};
+use super::registry::{GenEntity, GenEntityType, NameRegistry};
+
+/// Maximum number of characters in one label.
+pub const MAX_TEXT3D_CHARS: usize = 1000;
+
+/// Font size, in pixels, the text is rasterized at.
+const GLYPH_PX: f32 = 64.0;
+
+/// Largest texture side a label renders into.
+const MAX_TEXTURE_SIZE: u32 = 4096;
+
+/// Next free render layer for a label's text. Layer 0 is the main scene.
+#[derive(Resource)]
+pub struct Text3dLayers {
+    next: usize,
+}
+
+impl Default for Text3dLayers {
+    fn default() -> Self {
+        Self { next: 1 }
+    }
+}
+
+impl Text3dLayers {
+    pub fn allocate(&mut self) -> usize {
+        let layer = self.next;
+        self.next += 1;
+        layer
+    }
+}
+
+/// Everything needed to (re)build a label's text renderer. Kept on the
+/// label entity so snapshots can restore it.
+#[derive(Component, Clone)]
+pub struct Text3dLabel {
+    pub text: String,
+    pub image: Handle<Image>,
+    pub layer: usize,
+    pub billboard: bool,
+}
+
+impl Text3dLabel {
+    /// Spawn the camera and text that render into `image` under `parent`.
+    pub fn spawn_renderer(&self, world: &mut World, parent: Entity) {
+        let layers = RenderLayers::layer(self.layer);
+        let camera = world
+            .spawn((
+                Camera2d,
+                Camera {
+                    target: RenderTarget::Image(self.image.clone()),
+                    // Render before the main camera samples the texture
+                    order: -1,
+                    clear_color: ClearColorConfig::Custom(Color::NONE),
+                    ..default()
+                },
+                layers.clone(),
+            ))
+            .id();
+        let text = world
+            .spawn((
+                Text2d::new(self.text.clone()),
+                TextFont {
+                    font_size: GLYPH_PX,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(JustifyText::Center),
+                layers,
+            ))
+            .id();
+        world.entity_mut(parent).add_children(&[camera, text]);
+    }
+
+    /// A copy of this label on a new texture and render layer, with a copy
+    /// of `material` showing that texture.
+    pub fn duplicate(
+        &self,
+        world: &mut World,
+        material: Option<&MeshMaterial3d<StandardMaterial>>,
+    ) -> (Self, Option<MeshMaterial3d<StandardMaterial>>) {
+        let size = world
+            .resource::<Assets<Image>>()
+            .get(&self.image)
+            .map(|image| image.size())
+            .unwrap_or_else(|| texture_size(&self.text));
+        let image = world
+            .resource_mut::<Assets<Image>>()
+            .add(render_target(size));
+
+        let material = material.and_then(|m| {
+            let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+            let copy = StandardMaterial {
+                base_color_texture: Some(image.clone()),
+                ..materials.get(&m.0)?.clone()
+            };
+            Some(MeshMaterial3d(materials.add(copy)))
+        });
+        let label = Self {
+            text: self.text.clone(),
+            image,
+            layer: world.resource_mut::<Text3dLayers>().allocate(),
+            billboard: self.billboard,
+        };
+        (label, material)
+    }
+}
+
+pub fn spawn_text3d(world: &mut World, cmd: SpawnText3DCmd) -> GenResponse {
+    if world.resource::<NameRegistry>().contains_name(&cmd.name) {
+        return GenResponse::Error {
+            message: format!("Entity '{}' already exists", cmd.name),
+        };
+    }
+    if cmd.text.trim().is_empty() {
+        return GenResponse::Error {
+            message: "Text must not be empty".to_string(),
+        };
+    }
+    if cmd.text.chars().count() > MAX_TEXT3D_CHARS {
+        return GenResponse::Error {
+            message: format!(
+                "Text exceeds the maximum of {} characters",
+                MAX_TEXT3D_CHARS
+            ),
+        };
+    }
+    if !cmd.font_size.is_finite() || cmd.font_size <= 0.0 {
+        return GenResponse::Error {
+            message: "font_size must be positive".to_string(),
+        };
+    }
+
+    let size = texture_size(&cmd.text);
+    let image = world
+        .resource_mut::<Assets<Image>>()
+        .add(render_target(size));
+    let quad = size.as_vec2() / GLYPH_PX * cmd.font_size;
+    let mesh = world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Rectangle::new(quad.x, quad.y));
+    let material = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: Color::srgba(cmd.color[0], cmd.color[1], cmd.color[2], cmd.color[3]),
+            base_color_texture: Some(image.clone()),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        });
+
+    let label = Text3dLabel {
+        text: cmd.text,
+        image,
+        layer: world.resource_mut::<Text3dLayers>().allocate(),
+        billboard: cmd.billboard,
+    };
+    let rotation = Quat::from_euler(
+        EulerRot::XYZ,
+        cmd.rotation_degrees[0].to_radians(),
+        cmd.rotation_degrees[1].to_radians(),
+        cmd.rotation_degrees[2].to_radians(),
+    );
+
+    let entity = world
+        .spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::from_translation(Vec3::from_array(cmd.position)).with_rotation(rotation),
+            Name::new(cmd.name.clone()),
+            GenEntity {
+                entity_type: GenEntityType::Text,
+            },
+            NotShadowCaster,
+        ))
+        .id();
+    label.spawn_renderer(world, entity);
+    world.entity_mut(entity).insert(label);
+
+    if let Some(ref parent_name) = cmd.parent
+        && let Some(parent) = world.resource::<NameRegistry>().get_entity(parent_name)
+    {
+        world.entity_mut(entity).set_parent(parent);
+    }
+    world
+        .resource_mut::<NameRegistry>()
+        .insert(cmd.name.clone(), entity);
+
+    GenResponse::Spawned {
+        name: cmd.name,
+        entity_id: entity.to_bits(),
+    }
+}
+
+/// Turn billboarded labels to face the main camera.
+pub fn face_camera(
+    camera: Query<&GlobalTransform, With<Camera3d>>,
+    mut labels: Query<(&Text3dLabel, &mut Transform, Option<&Parent>)>,
+    parents: Query<&GlobalTransform>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    let camera_rotation = camera.compute_transform().rotation;
+    for (label, mut transform, parent) in &mut labels {
+        if !label.billboard {
+            continue;
+        }
+        let parent_rotation = parent
+            .and_then(|p| parents.get(p.get()).ok())
+            .map(|p| p.compute_transform().rotation)
+            .unwrap_or(Quat::IDENTITY);
+        // The quad faces +Z, as the camera's back does
+        let rotation = parent_rotation.inverse() * camera_rotation;
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+/// Texture size that fits `text` at [`GLYPH_PX`], estimated from its line
+/// count and longest line.
+fn texture_size(text: &str) -> UVec2 {
+    let lines = text.lines().count().max(1);
+    let longest = text
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let width = (longest as f32 * GLYPH_PX * 0.6 + GLYPH_PX).ceil() as u32;
+    let height = (lines as f32 * GLYPH_PX * 1.2 + GLYPH_PX * 0.5).ceil() as u32;
+    UVec2::new(width.min(MAX_TEXTURE_SIZE), height.min(MAX_TEXTURE_SIZE))
+}
+
+/// A transparent image a camera can render into.
+fn render_target(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texture_size_grows_with_longest_line_and_line_count() {
+        assert_eq!(texture_size(""), UVec2::new(103, 109));
+        assert_eq!(texture_size("a"), UVec2::new(103, 109));
+        assert_eq!(texture_size("ab\nlonger line"), UVec2::new(487, 186));
+        // Characters, not bytes
+        assert_eq!(texture_size("\u{e9}\u{e9}"), texture_size("ee"));
+    }
+
+    #[test]
+    fn texture_size_is_capped() {
+        let long = "x".repeat(MAX_TEXT3D_CHARS);
+        assert_eq!(texture_size(&long).x, MAX_TEXTURE_SIZE);
+        let tall = "x\n".repeat(200);
+        assert_eq!(texture_size(&tall).y, MAX_TEXTURE_SIZE);
+    }
+
+    #[test]
+    fn render_target_is_a_transparent_attachment() {
+        let image = render_target(UVec2::new(8, 4));
+        assert_eq!(image.size(), UVec2::new(8, 4));
+        assert!(
+            image
+                .texture_descriptor
+                .usage
+                .contains(TextureUsages::RENDER_ATTACHMENT)
+        );
+        assert!(image.data.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn layers_start_after_the_main_scene() {
+        let mut layers = Text3dLayers::default();
+        assert_eq!(layers.allocate(), 1);
+        assert_eq!(layers.allocate(), 2);
+    }
+}
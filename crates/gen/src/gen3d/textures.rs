@@ -0,0 +1,110 @@
+//! Textured PBR materials loaded from image files.
+//!
+//! `set_material` decodes the images, adds them as repeating textures and
+//! gives the entity a copy of its current material with the textures
+//! assigned, so entities sharing the old material keep it. Normal maps
+//! need mesh tangents, so the entity also gets a copy of its mesh with
+//! tangents generated. Paths arrive already resolved and scoped by the
+//! tool.
+
+use bevy::image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::math::Affine2;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use std::path::Path;
+
+use super::commands::{GenResponse, SetMaterialCmd};
+use super::registry::NameRegistry;
+
+pub fn set_material(world: &mut World, cmd: SetMaterialCmd) -> GenResponse {
+    match apply_material(world, &cmd) {
+        Ok(()) => GenResponse::MaterialSet { name: cmd.name },
+        Err(message) => GenResponse::Error { message },
+    }
+}
+
+fn apply_material(world: &mut World, cmd: &SetMaterialCmd) -> Result<(), String> {
+    let Some(entity) = world.resource::<NameRegistry>().get_entity(&cmd.name) else {
+        return Err(format!("Entity '{}' not found", cmd.name));
+    };
+    let Some(material) = world
+        .get::<MeshMaterial3d<StandardMaterial>>(entity)
+        .map(|m| m.0.clone())
+    else {
+        return Err(format!("Entity '{}' has no material", cmd.name));
+    };
+    if !cmd.tiling.iter().all(|t| t.is_finite() && *t > 0.0) {
+        return Err(format!("tiling {:?} must be positive", cmd.tiling));
+    }
+
+    // Load everything before touching the entity, so a bad file changes nothing
+    let base_color = load_texture(cmd.base_color_texture.as_deref(), true)?;
+    let normal = load_texture(cmd.normal_texture.as_deref(), false)?;
+    let metallic_roughness = load_texture(cmd.metallic_roughness_texture.as_deref(), false)?;
+
+    if normal.is_some() {
+        let Some(mesh) = world.get::<Mesh3d>(entity).map(|m| m.0.clone()) else {
+            return Err(format!("Entity '{}' has no mesh", cmd.name));
+        };
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let Some(mut mesh) = meshes.get(&mesh).cloned() else {
+            return Err(format!("Entity '{}' has no mesh", cmd.name));
+        };
+        mesh.generate_tangents().map_err(|e| {
+            format!(
+                "Can't apply a normal map to '{}': {} (the mesh needs UVs and normals)",
+                cmd.name, e
+            )
+        })?;
+        let mesh = meshes.add(mesh);
+        world.entity_mut(entity).insert(Mesh3d(mesh));
+    }
+
+    let mut images = world.resource_mut::<Assets<Image>>();
+    let base_color = base_color.map(|image| images.add(image));
+    let normal = normal.map(|image| images.add(image));
+    let metallic_roughness = metallic_roughness.map(|image| images.add(image));
+
+    let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+    let mut material = materials.get(&material).cloned().unwrap_or_default();
+    if let Some(texture) = base_color {
+        // The texture supplies the color; modify_entity's color tints it
+        material.base_color = Color::WHITE;
+        material.base_color_texture = Some(texture);
+    }
+    if let Some(texture) = normal {
+        material.normal_map_texture = Some(texture);
+    }
+    if let Some(texture) = metallic_roughness {
+        // The factors scale the texture's channels
+        material.metallic = 1.0;
+        material.perceptual_roughness = 1.0;
+        material.metallic_roughness_texture = Some(texture);
+    }
+    material.uv_transform = Affine2::from_scale(Vec2::from_array(cmd.tiling));
+    let material = materials.add(material);
+    world.entity_mut(entity).insert(MeshMaterial3d(material));
+
+    Ok(())
+}
+
+/// Decode `path` into a repeating texture. Color textures are sRGB; data
+/// textures (normals, metallic/roughness) are linear.
+fn load_texture(path: Option<&str>, srgb: bool) -> Result<Option<Image>, String> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    if !Path::new(path).is_file() {
+        return Err(format!("Texture file not found: {}", path));
+    }
+    let decoded =
+        image::open(path).map_err(|e| format!("Failed to load texture {}: {}", path, e))?;
+
+    let mut texture = Image::from_dynamic(decoded, srgb, RenderAssetUsages::default());
+    texture.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..ImageSamplerDescriptor::linear()
+    });
+    Ok(Some(texture))
+}
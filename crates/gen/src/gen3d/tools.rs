@@ -3,6 +3,8 @@
 //! Each tool sends a `GenCommand` through the `GenBridge` and formats
 //! the `GenResponse` as a string for the LLM.
 
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -14,20 +16,43 @@ use super::commands::*;
 use localgpt_core::agent::ToolSchema;
 use localgpt_core::agent::tools::Tool;
 
-/// Create all gen tools backed by the given bridge.
-pub fn create_gen_tools(bridge: Arc<GenBridge>) -> Vec<Box<dyn Tool>> {
+/// Create all gen tools backed by the given bridge. Texture and heightmap
+/// files are read from, and exported models written to, `workspace`.
+pub fn create_gen_tools(bridge: Arc<GenBridge>, workspace: PathBuf) -> Vec<Box<dyn Tool>> {
     vec![
         Box::new(GenSceneInfoTool::new(bridge.clone())),
+        Box::new(GenSceneTreeTool::new(bridge.clone())),
         Box::new(GenScreenshotTool::new(bridge.clone())),
         Box::new(GenEntityInfoTool::new(bridge.clone())),
+        Box::new(GenEntitiesInRegionTool::new(bridge.clone())),
+        Box::new(GenEntitiesByTagTool::new(bridge.clone())),
+        Box::new(GenRaycastTool::new(bridge.clone())),
         Box::new(GenSpawnPrimitiveTool::new(bridge.clone())),
         Box::new(GenModifyEntityTool::new(bridge.clone())),
         Box::new(GenDeleteEntityTool::new(bridge.clone())),
+        Box::new(GenSetMaterialTool::new(bridge.clone(), workspace.clone())),
         Box::new(GenSetCameraTool::new(bridge.clone())),
         Box::new(GenSetLightTool::new(bridge.clone())),
         Box::new(GenSetEnvironmentTool::new(bridge.clone())),
+        Box::new(GenSetQualityTool::new(bridge.clone())),
+        Box::new(GenSetInteractiveCameraTool::new(bridge.clone())),
+        Box::new(GenFrameSceneTool::new(bridge.clone())),
         Box::new(GenSpawnMeshTool::new(bridge.clone())),
-        Box::new(GenExportScreenshotTool::new(bridge)),
+        Box::new(GenSpawnTerrainTool::new(bridge.clone(), workspace.clone())),
+        Box::new(GenSpawnText3DTool::new(bridge.clone())),
+        Box::new(GenWeldMeshTool::new(bridge.clone())),
+        Box::new(GenSpawnInstancedTool::new(bridge.clone())),
+        Box::new(GenDuplicateEntityTool::new(bridge.clone())),
+        Box::new(GenAnimateEntityTool::new(bridge.clone())),
+        Box::new(GenStopAnimationTool::new(bridge.clone())),
+        Box::new(GenSnapshotSceneTool::new(bridge.clone())),
+        Box::new(GenRestoreSnapshotTool::new(bridge.clone())),
+        Box::new(GenListSnapshotsTool::new(bridge.clone())),
+        Box::new(GenSetExportSettingsTool::new(bridge.clone())),
+        Box::new(GenOrbitLightTool::new(bridge.clone(), workspace.clone())),
+        Box::new(GenSetDeterministicModeTool::new(bridge.clone())),
+        Box::new(GenExportScreenshotTool::new(bridge.clone())),
+        Box::new(GenExportObjTool::new(bridge, workspace)),
     ]
 }
 
@@ -72,6 +97,63 @@ impl Tool for GenSceneInfoTool {
     }
 }
 
+// ===========================================================================
+// gen_scene_tree
+// ===========================================================================
+
+struct GenSceneTreeTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenSceneTreeTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenSceneTreeTool {
+    fn name(&self) -> &str {
+        "gen_scene_tree"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_scene_tree".into(),
+            description: "Show the parent/child hierarchy of the scene as an indented outline of entity names and types. Use to understand groups and assemblies at a glance.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "format": {
+                        "type": "string",
+                        "enum": ["text", "json"],
+                        "default": "text",
+                        "description": "Indented outline or nested JSON"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments).unwrap_or_default();
+        let as_json = args["format"].as_str() == Some("json");
+
+        match self.bridge.send(GenCommand::SceneTree).await? {
+            GenResponse::SceneTree(data) if as_json => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::SceneTree(data) => {
+                let mut out = format!("{} entities\n", data.entity_count);
+                for root in &data.roots {
+                    root.write_outline(0, &mut out);
+                }
+                Ok(out)
+            }
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
 // ===========================================================================
 // gen_screenshot
 // ===========================================================================
@@ -166,7 +248,7 @@ impl Tool for GenEntityInfoTool {
     fn schema(&self) -> ToolSchema {
         ToolSchema {
             name: "gen_entity_info".into(),
-            description: "Get detailed information about a specific entity by name.".into(),
+            description: "Get detailed information about a specific entity by name, including its world-space bounding box (aabb_min/aabb_max) for checking overlaps and fit.".into(),
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -195,6 +277,196 @@ impl Tool for GenEntityInfoTool {
     }
 }
 
+// ===========================================================================
+// gen_entities_in_region
+// ===========================================================================
+
+struct GenEntitiesInRegionTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenEntitiesInRegionTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenEntitiesInRegionTool {
+    fn name(&self) -> &str {
+        "gen_entities_in_region"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_entities_in_region".into(),
+            description: "List entities whose bounding box intersects a sphere, sorted by distance from its center. Use to find what is near a point.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "center": {
+                        "type": "array",
+                        "items": { "type": "number" },
+                        "description": "Sphere center [x, y, z]"
+                    },
+                    "radius": {
+                        "type": "number",
+                        "description": "Sphere radius"
+                    }
+                },
+                "required": ["center", "radius"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let center = parse_opt_f32_array(&args["center"])
+            .ok_or_else(|| anyhow::anyhow!("Missing center"))?;
+        let radius = args["radius"]
+            .as_f64()
+            .ok_or_else(|| anyhow::anyhow!("Missing radius"))? as f32;
+
+        match self
+            .bridge
+            .send(GenCommand::EntitiesInRegion { center, radius })
+            .await?
+        {
+            GenResponse::EntitiesInRegion(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_entities_by_tag
+// ===========================================================================
+
+struct GenEntitiesByTagTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenEntitiesByTagTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenEntitiesByTagTool {
+    fn name(&self) -> &str {
+        "gen_entities_by_tag"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_entities_by_tag".into(),
+            description: "List entities carrying a tag (set via gen_spawn_primitive or gen_modify_entity), sorted by name.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "tag": {
+                        "type": "string",
+                        "description": "Tag to look up"
+                    }
+                },
+                "required": ["tag"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let tag = args["tag"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing tag"))?
+            .to_string();
+
+        match self.bridge.send(GenCommand::EntitiesByTag { tag }).await? {
+            GenResponse::EntitiesByTag(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_raycast
+// ===========================================================================
+
+struct GenRaycastTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenRaycastTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenRaycastTool {
+    fn name(&self) -> &str {
+        "gen_raycast"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_raycast".into(),
+            description: "Cast a ray and report the first mesh surface it hits: entity name, hit point and surface normal. Use to place objects on floors and tables, e.g. cast straight down ([0, -1, 0]) from above the target spot.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "origin": {
+                        "type": "array",
+                        "items": { "type": "number" },
+                        "description": "Ray start [x, y, z]"
+                    },
+                    "direction": {
+                        "type": "array",
+                        "items": { "type": "number" },
+                        "description": "Ray direction [x, y, z]; need not be normalized"
+                    },
+                    "max_distance": {
+                        "type": "number",
+                        "default": 1000.0
+                    }
+                },
+                "required": ["origin", "direction"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let origin = parse_opt_f32_array(&args["origin"])
+            .ok_or_else(|| anyhow::anyhow!("Missing origin"))?;
+        let direction = parse_opt_f32_array(&args["direction"])
+            .ok_or_else(|| anyhow::anyhow!("Missing direction"))?;
+        let max_distance = args["max_distance"].as_f64().unwrap_or(1000.0) as f32;
+
+        match self
+            .bridge
+            .send(GenCommand::Raycast {
+                origin,
+                direction,
+                max_distance,
+            })
+            .await?
+        {
+            GenResponse::Raycast(RaycastData { hit: Some(hit) }) => {
+                Ok(serde_json::to_string_pretty(&hit)?)
+            }
+            GenResponse::Raycast(RaycastData { hit: None }) => {
+                Ok(format!("No hit within {} units", max_distance))
+            }
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
 // ===========================================================================
 // gen_spawn_primitive
 // ===========================================================================
@@ -228,12 +500,12 @@ impl Tool for GenSpawnPrimitiveTool {
                     },
                     "shape": {
                         "type": "string",
-                        "enum": ["Cuboid", "Sphere", "Cylinder", "Cone", "Capsule", "Torus", "Plane"],
+                        "enum": ["Cuboid", "Sphere", "Cylinder", "Cone", "Capsule", "Torus", "Plane", "Wedge", "Icosphere"],
                         "description": "Primitive shape type"
                     },
                     "dimensions": {
                         "type": "object",
-                        "description": "Shape-specific dimensions. Cuboid: {x,y,z}. Sphere: {radius}. Cylinder: {radius, height}. Cone: {radius, height}. Torus: {major_radius, minor_radius}."
+                        "description": "Shape-specific dimensions. Cuboid: {x,y,z}. Sphere: {radius}. Cylinder: {radius, height}. Cone: {radius, height}. Torus: {major_radius, minor_radius}. Wedge: {width, height, depth} (ramp along Z, rising toward -Z). Icosphere: {radius, subdivisions} (subdivisions 0-10, default 3)."
                     },
                     "position": {
                         "type": "array",
@@ -280,6 +552,16 @@ impl Tool for GenSpawnPrimitiveTool {
                     "parent": {
                         "type": "string",
                         "description": "Name of parent entity for hierarchy. Omit for root-level."
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Tags for grouping and lookup (e.g., ['furniture', 'kitchen'])"
+                    },
+                    "metadata": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "Arbitrary string key/value pairs"
                     }
                 },
                 "required": ["name", "shape"]
@@ -313,6 +595,8 @@ impl Tool for GenSpawnPrimitiveTool {
             roughness: args["roughness"].as_f64().unwrap_or(0.5) as f32,
             emissive: parse_f32_4(&args["emissive"], [0.0, 0.0, 0.0, 0.0]),
             parent: args["parent"].as_str().map(|s| s.to_string()),
+            tags: parse_opt_string_array(&args["tags"]).unwrap_or_default(),
+            metadata: parse_opt_string_map(&args["metadata"]).unwrap_or_default(),
         };
 
         match self.bridge.send(GenCommand::SpawnPrimitive(cmd)).await? {
@@ -382,9 +666,27 @@ impl Tool for GenModifyEntityTool {
                         "type": "boolean",
                         "description": "Show/hide entity"
                     },
+                    "casts_shadows": {
+                        "type": "boolean",
+                        "description": "Whether the entity casts shadows (default: true)"
+                    },
+                    "receives_shadows": {
+                        "type": "boolean",
+                        "description": "Whether shadows fall on the entity (default: true)"
+                    },
                     "parent": {
                         "type": "string",
                         "description": "Reparent to named entity, or null to unparent"
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Replace the entity's tags"
+                    },
+                    "metadata": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "Merge into the entity's metadata; an empty string removes a key"
                     }
                 },
                 "required": ["name"]
@@ -413,6 +715,10 @@ impl Tool for GenModifyEntityTool {
             } else {
                 None
             },
+            tags: parse_opt_string_array(&args["tags"]),
+            metadata: parse_opt_string_map(&args["metadata"]),
+            casts_shadows: args["casts_shadows"].as_bool(),
+            receives_shadows: args["receives_shadows"].as_bool(),
         };
 
         match self.bridge.send(GenCommand::ModifyEntity(cmd)).await? {
@@ -475,6 +781,104 @@ impl Tool for GenDeleteEntityTool {
     }
 }
 
+// ===========================================================================
+// gen_set_material
+// ===========================================================================
+
+struct GenSetMaterialTool {
+    bridge: Arc<GenBridge>,
+    workspace: PathBuf,
+}
+
+impl GenSetMaterialTool {
+    fn new(bridge: Arc<GenBridge>, workspace: PathBuf) -> Self {
+        Self { bridge, workspace }
+    }
+
+    /// Resolve a texture path against the workspace and fail unless it
+    /// stays inside it.
+    fn resolve_texture(&self, path: &str) -> Result<String> {
+        let expanded = PathBuf::from(shellexpand::tilde(path).to_string());
+        let full = self.workspace.join(expanded);
+        let resolved = full
+            .canonicalize()
+            .map_err(|_| anyhow::anyhow!("Texture file not found: {}", path))?;
+        let workspace = self
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace.clone());
+        if !resolved.starts_with(&workspace) {
+            anyhow::bail!("Texture {} is outside the workspace", path);
+        }
+        Ok(resolved.to_string_lossy().into_owned())
+    }
+}
+
+#[async_trait]
+impl Tool for GenSetMaterialTool {
+    fn name(&self) -> &str {
+        "gen_set_material"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_set_material".into(),
+            description: "Give an entity a textured PBR material from image files in the workspace (PNG, JPEG, ...). Omitted textures are left as they are. Other material settings are kept; gen_modify_entity's color tints the base color texture.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "base_color_texture": {
+                        "type": "string",
+                        "description": "Color (albedo) image, relative to the workspace"
+                    },
+                    "normal_texture": {
+                        "type": "string",
+                        "description": "Tangent-space normal map"
+                    },
+                    "metallic_roughness_texture": {
+                        "type": "string",
+                        "description": "glTF-style map: roughness in green, metallic in blue"
+                    },
+                    "tiling": {
+                        "type": "array",
+                        "default": [1, 1],
+                        "description": "How often the textures repeat across the surface [u, v]"
+                    }
+                },
+                "required": ["name"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let mut cmd: SetMaterialCmd = serde_json::from_str(arguments)
+            .map_err(|e| anyhow::anyhow!("Invalid material arguments: {}", e))?;
+        for path in [
+            &mut cmd.base_color_texture,
+            &mut cmd.normal_texture,
+            &mut cmd.metallic_roughness_texture,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *path = self.resolve_texture(path)?;
+        }
+        if cmd.base_color_texture.is_none()
+            && cmd.normal_texture.is_none()
+            && cmd.metallic_roughness_texture.is_none()
+        {
+            anyhow::bail!("Provide at least one texture");
+        }
+
+        match self.bridge.send(GenCommand::SetMaterial(cmd)).await? {
+            GenResponse::MaterialSet { name } => Ok(format!("Material set on '{}'", name)),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
 // ===========================================================================
 // gen_set_camera
 // ===========================================================================
@@ -698,55 +1102,226 @@ impl Tool for GenSetEnvironmentTool {
 }
 
 // ===========================================================================
-// gen_spawn_mesh
+// gen_set_quality
 // ===========================================================================
 
-struct GenSpawnMeshTool {
+struct GenSetQualityTool {
     bridge: Arc<GenBridge>,
 }
 
-impl GenSpawnMeshTool {
+impl GenSetQualityTool {
     fn new(bridge: Arc<GenBridge>) -> Self {
         Self { bridge }
     }
 }
 
 #[async_trait]
-impl Tool for GenSpawnMeshTool {
+impl Tool for GenSetQualityTool {
     fn name(&self) -> &str {
-        "gen_spawn_mesh"
+        "gen_set_quality"
     }
 
     fn schema(&self) -> ToolSchema {
         ToolSchema {
-            name: "gen_spawn_mesh".into(),
-            description:
-                "Create custom geometry from raw vertex data. Use when primitives are insufficient."
-                    .into(),
+            name: "gen_set_quality".into(),
+            description: "Set render quality for subsequent screenshots/exports. low = fast default; medium adds ambient occlusion and sharper shadows; high adds filmic tone mapping and the largest shadow maps.".into(),
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "name": {"type": "string"},
-                    "vertices": {
-                        "type": "array",
-                        "items": {"type": "array"},
-                        "description": "Array of [x,y,z] vertex positions"
-                    },
-                    "indices": {
-                        "type": "array",
-                        "items": {"type": "integer"},
-                        "description": "Triangle indices (groups of 3)"
-                    },
-                    "normals": {
-                        "type": "array",
-                        "items": {"type": "array"},
-                        "description": "Per-vertex normals [x,y,z]. Auto-computed if omitted."
-                    },
-                    "uvs": {
-                        "type": "array",
-                        "items": {"type": "array"},
-                        "description": "Per-vertex UV coordinates [u,v]"
-                    },
+                    "level": {
+                        "type": "string",
+                        "enum": ["low", "medium", "high"],
+                        "description": "Quality level"
+                    }
+                },
+                "required": ["level"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let level: QualityLevel = serde_json::from_value(args["level"].clone())
+            .map_err(|_| anyhow::anyhow!("Invalid level: expected low, medium or high"))?;
+
+        match self.bridge.send(GenCommand::SetQuality { level }).await? {
+            GenResponse::QualitySet { level } => {
+                Ok(format!("Render quality set to {}", level.as_str()))
+            }
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_set_interactive_camera
+// ===========================================================================
+
+struct GenSetInteractiveCameraTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenSetInteractiveCameraTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenSetInteractiveCameraTool {
+    fn name(&self) -> &str {
+        "gen_set_interactive_camera"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_set_interactive_camera".into(),
+            description: "Hand the camera to a human watching the window (WASD/Space/Shift to fly, right-drag to look, scroll for speed) or take it back. Returns the current camera pose; after disabling, pass it to gen_set_camera to continue from where the human left off.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "enabled": {
+                        "type": "boolean",
+                        "description": "true to enable manual fly controls, false to lock the camera"
+                    }
+                },
+                "required": ["enabled"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let enabled = args["enabled"]
+            .as_bool()
+            .ok_or_else(|| anyhow::anyhow!("Missing enabled"))?;
+
+        match self
+            .bridge
+            .send(GenCommand::SetInteractiveCamera { enabled })
+            .await?
+        {
+            GenResponse::InteractiveCamera(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_frame_scene
+// ===========================================================================
+
+struct GenFrameSceneTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenFrameSceneTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenFrameSceneTool {
+    fn name(&self) -> &str {
+        "gen_frame_scene"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_frame_scene".into(),
+            description: "Move the camera so the whole scene, or the listed entities, fits in view. Keeps the current view direction and FOV. Returns the chosen camera pose; adjust it with gen_set_camera.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "entities": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Entities to frame (default: every entity except cameras and lights)"
+                    },
+                    "padding": {
+                        "type": "number",
+                        "description": "Extra margin as a fraction of the bounding radius (default: 0.1)"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let padding = args["padding"].as_f64().unwrap_or(0.1) as f32;
+        let entities = args["entities"].as_array().map(|names| {
+            names
+                .iter()
+                .filter_map(|n| n.as_str().map(String::from))
+                .collect()
+        });
+
+        match self
+            .bridge
+            .send(GenCommand::FrameScene { padding, entities })
+            .await?
+        {
+            GenResponse::SceneFramed(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_spawn_mesh
+// ===========================================================================
+
+struct GenSpawnMeshTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenSpawnMeshTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenSpawnMeshTool {
+    fn name(&self) -> &str {
+        "gen_spawn_mesh"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_spawn_mesh".into(),
+            description:
+                "Create custom geometry from raw vertex data. Use when primitives are insufficient."
+                    .into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "vertices": {
+                        "type": "array",
+                        "items": {"type": "array"},
+                        "description": "Array of [x,y,z] vertex positions"
+                    },
+                    "indices": {
+                        "type": "array",
+                        "items": {"type": "integer"},
+                        "description": "Triangle indices (groups of 3)"
+                    },
+                    "normals": {
+                        "type": "array",
+                        "items": {"type": "array"},
+                        "description": "Per-vertex normals [x,y,z]. Auto-computed if omitted."
+                    },
+                    "uvs": {
+                        "type": "array",
+                        "items": {"type": "array"},
+                        "description": "Per-vertex UV coordinates [u,v]"
+                    },
                     "color": {
                         "type": "array",
                         "default": [0.8, 0.8, 0.8, 1.0]
@@ -764,53 +1339,785 @@ impl Tool for GenSpawnMeshTool {
         }
     }
 
-    async fn execute(&self, arguments: &str) -> Result<String> {
-        let args: Value = serde_json::from_str(arguments)?;
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+
+        let vertices: Vec<[f32; 3]> = args["vertices"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Missing vertices"))?
+            .iter()
+            .map(parse_json_vec3)
+            .collect();
+
+        let indices: Vec<u32> = args["indices"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Missing indices"))?
+            .iter()
+            .filter_map(|v| v.as_u64().map(|n| n as u32))
+            .collect();
+
+        let normals: Option<Vec<[f32; 3]>> = args
+            .get("normals")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().map(parse_json_vec3).collect());
+
+        let uvs: Option<Vec<[f32; 2]>> = args
+            .get("uvs")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().map(parse_json_vec2).collect());
+
+        let cmd = RawMeshCmd {
+            name: args["name"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing name"))?
+                .to_string(),
+            vertices,
+            indices,
+            normals,
+            uvs,
+            color: parse_f32_4(&args["color"], [0.8, 0.8, 0.8, 1.0]),
+            metallic: args["metallic"].as_f64().unwrap_or(0.0) as f32,
+            roughness: args["roughness"].as_f64().unwrap_or(0.5) as f32,
+            position: parse_f32_array(&args["position"], [0.0, 0.0, 0.0]),
+        };
+
+        match self.bridge.send(GenCommand::SpawnMesh(cmd)).await? {
+            GenResponse::Spawned { name, entity_id } => Ok(format!(
+                "Spawned mesh '{}' (entity_id: {})",
+                name, entity_id
+            )),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_spawn_terrain
+// ===========================================================================
+
+struct GenSpawnTerrainTool {
+    bridge: Arc<GenBridge>,
+    workspace: PathBuf,
+}
+
+impl GenSpawnTerrainTool {
+    fn new(bridge: Arc<GenBridge>, workspace: PathBuf) -> Self {
+        Self { bridge, workspace }
+    }
+
+    /// Resolve a heightmap image path against the workspace and fail unless
+    /// it stays inside it.
+    fn resolve_heightmap(&self, path: &str) -> Result<String> {
+        let expanded = PathBuf::from(shellexpand::tilde(path).to_string());
+        let full = self.workspace.join(expanded);
+        let resolved = full
+            .canonicalize()
+            .map_err(|_| anyhow::anyhow!("Heightmap file not found: {}", path))?;
+        let workspace = self
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace.clone());
+        if !resolved.starts_with(&workspace) {
+            anyhow::bail!("Heightmap {} is outside the workspace", path);
+        }
+        Ok(resolved.to_string_lossy().into_owned())
+    }
+}
+
+#[async_trait]
+impl Tool for GenSpawnTerrainTool {
+    fn name(&self) -> &str {
+        "gen_spawn_terrain"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_spawn_terrain".into(),
+            description: "Generate a terrain mesh (subdivided plane with smooth normals) from procedural Perlin noise or a grayscale heightmap image.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "size": {
+                        "type": "array",
+                        "default": [20, 20],
+                        "description": "Extent [x, z] in world units"
+                    },
+                    "resolution": {
+                        "type": "integer",
+                        "default": 128,
+                        "description": format!("Quads per side (max {})", super::terrain::MAX_TERRAIN_RESOLUTION)
+                    },
+                    "heightmap": {
+                        "type": "object",
+                        "description": "Either {\"type\": \"noise\", \"seed\", \"octaves\" (default 4), \"amplitude\" (default 2), \"frequency\" (default 0.1)} or {\"type\": \"image\", \"path\" (in the workspace), \"amplitude\"}",
+                        "properties": {
+                            "type": {"type": "string", "enum": ["noise", "image"]},
+                            "seed": {"type": "integer"},
+                            "octaves": {"type": "integer"},
+                            "amplitude": {"type": "number"},
+                            "frequency": {"type": "number"},
+                            "path": {"type": "string"}
+                        },
+                        "required": ["type"]
+                    },
+                    "position": {
+                        "type": "array",
+                        "default": [0, 0, 0],
+                        "description": "World position [x, y, z] of the terrain center"
+                    },
+                    "color": {
+                        "type": "array",
+                        "default": [0.35, 0.5, 0.3, 1.0]
+                    },
+                    "roughness": {"type": "number", "default": 0.9}
+                },
+                "required": ["name", "heightmap"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let mut cmd: SpawnTerrainCmd = serde_json::from_str(arguments)
+            .map_err(|e| anyhow::anyhow!("Invalid terrain arguments: {}", e))?;
+        if let HeightmapSource::Image { path, .. } = &mut cmd.heightmap {
+            *path = self.resolve_heightmap(path)?;
+        }
+
+        match self.bridge.send(GenCommand::SpawnTerrain(cmd)).await? {
+            GenResponse::TerrainSpawned(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_spawn_text
+// ===========================================================================
+
+struct GenSpawnText3DTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenSpawnText3DTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenSpawnText3DTool {
+    fn name(&self) -> &str {
+        "gen_spawn_text"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_spawn_text".into(),
+            description: "Place a text label or sign in the scene. The text is drawn on a flat, unlit panel that faces +Z (or the camera, if billboarded) and can be moved, recolored or deleted like any other entity.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "text": {
+                        "type": "string",
+                        "description": format!("Label text; use \\n for multiple lines (max {} characters)", super::text3d::MAX_TEXT3D_CHARS)
+                    },
+                    "font_size": {
+                        "type": "number",
+                        "default": 0.5,
+                        "description": "Glyph size in world units, roughly the height of one line"
+                    },
+                    "color": {
+                        "type": "array",
+                        "default": [1.0, 1.0, 1.0, 1.0],
+                        "description": "RGBA text color"
+                    },
+                    "position": {
+                        "type": "array",
+                        "default": [0, 0, 0],
+                        "description": "World position [x, y, z] of the label center"
+                    },
+                    "rotation_degrees": {
+                        "type": "array",
+                        "default": [0, 0, 0],
+                        "description": "Euler rotation [x, y, z] in degrees; ignored when billboarded"
+                    },
+                    "billboard": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Keep the text turned toward the camera"
+                    },
+                    "parent": {"type": "string"}
+                },
+                "required": ["name", "text"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let cmd: SpawnText3DCmd = serde_json::from_str(arguments)
+            .map_err(|e| anyhow::anyhow!("Invalid text arguments: {}", e))?;
+
+        match self.bridge.send(GenCommand::SpawnText3D(cmd)).await? {
+            GenResponse::Spawned { name, entity_id } => {
+                Ok(format!("Spawned '{}' (entity_id: {})", name, entity_id))
+            }
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_weld_mesh
+// ===========================================================================
+
+struct GenWeldMeshTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenWeldMeshTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenWeldMeshTool {
+    fn name(&self) -> &str {
+        "gen_weld_mesh"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_weld_mesh".into(),
+            description: "Merge duplicate vertices of an entity's mesh (e.g. from gen_spawn_mesh with split edges) and recompute smooth shared normals. Improves shading and memory use. Returns vertex and triangle counts before and after.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Entity whose mesh to weld"
+                    },
+                    "tolerance": {
+                        "type": "number",
+                        "default": 0.0001,
+                        "description": "Merge vertices closer than this distance"
+                    },
+                    "preserve_uv_seams": {
+                        "type": "boolean",
+                        "default": true,
+                        "description": "Keep vertices with different UVs separate so textures don't smear across seams"
+                    }
+                },
+                "required": ["name"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let cmd: WeldMeshCmd = serde_json::from_str(arguments)
+            .map_err(|e| anyhow::anyhow!("Invalid weld arguments: {}", e))?;
+
+        match self.bridge.send(GenCommand::WeldMesh(cmd)).await? {
+            GenResponse::MeshWelded(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_spawn_instanced
+// ===========================================================================
+
+struct GenSpawnInstancedTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenSpawnInstancedTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenSpawnInstancedTool {
+    fn name(&self) -> &str {
+        "gen_spawn_instanced"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_spawn_instanced".into(),
+            description: "Tile copies of an existing entity's mesh and material across a grid, baked into a single static mesh drawn in one call. Use for floors, walls, fences or forests instead of spawning many entities. The result is one entity: gen_modify_entity / gen_delete_entity on it affects the whole field, and individual copies can't be edited.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "Name of the entity to copy (keeps its rotation and scale)"
+                    },
+                    "name": {"type": "string"},
+                    "grid": {
+                        "type": "array",
+                        "description": format!("Copies along [x, y, z], e.g. [20, 1, 20] (max {} total)", super::instancing::MAX_INSTANCES)
+                    },
+                    "spacing": {
+                        "type": "array",
+                        "default": [1, 1, 1],
+                        "description": "Distance between copies [x, y, z]"
+                    },
+                    "jitter": {
+                        "type": "number",
+                        "default": 0.0,
+                        "description": "Random offset per copy as a fraction (0-1) of spacing, for natural-looking placement"
+                    },
+                    "seed": {"type": "integer", "default": 0},
+                    "position": {
+                        "type": "array",
+                        "default": [0, 0, 0],
+                        "description": "World position of the field; the grid is centered on it in X/Z and stacks up in Y"
+                    }
+                },
+                "required": ["source", "name", "grid"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let cmd: SpawnInstancedCmd = serde_json::from_str(arguments)
+            .map_err(|e| anyhow::anyhow!("Invalid instancing arguments: {}", e))?;
+
+        match self.bridge.send(GenCommand::SpawnInstanced(cmd)).await? {
+            GenResponse::InstancedSpawned(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_duplicate_entity
+// ===========================================================================
+
+struct GenDuplicateEntityTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenDuplicateEntityTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenDuplicateEntityTool {
+    fn name(&self) -> &str {
+        "gen_duplicate_entity"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_duplicate_entity".into(),
+            description: "Copy an existing entity (transform, mesh, material, tags) into one or more new, independently editable entities. Use for rows of columns, fence posts or tiles; for hundreds of identical copies prefer gen_spawn_instanced.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "Name of the entity to copy"
+                    },
+                    "new_name": {
+                        "type": "string",
+                        "description": "Name of the copy; with count > 1 the copies are named new_name_1, new_name_2, ..."
+                    },
+                    "offset": {
+                        "type": "array",
+                        "default": [0, 0, 0],
+                        "description": "Translation [x, y, z] from the source to the first copy, and between consecutive copies"
+                    },
+                    "count": {
+                        "type": "integer",
+                        "default": 1,
+                        "description": format!("Number of copies (max {})", super::duplicate::MAX_DUPLICATES)
+                    }
+                },
+                "required": ["source", "new_name"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let source = args["source"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing source"))?
+            .to_string();
+        let new_name = args["new_name"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing new_name"))?
+            .to_string();
+
+        let cmd = GenCommand::DuplicateEntity {
+            source,
+            new_name,
+            offset: parse_f32_array(&args["offset"], [0.0, 0.0, 0.0]),
+            count: args["count"]
+                .as_u64()
+                .map(|n| n.min(u32::MAX as u64) as u32),
+        };
+        match self.bridge.send(cmd).await? {
+            GenResponse::Duplicated(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_animate_entity
+// ===========================================================================
+
+struct GenAnimateEntityTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenAnimateEntityTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenAnimateEntityTool {
+    fn name(&self) -> &str {
+        "gen_animate_entity"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_animate_entity".into(),
+            description: "Animate an entity's position, rotation, scale or color through keyframes. One animation runs per property; animating a property again replaces its animation. Stop with gen_stop_animation.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "property": {
+                        "type": "string",
+                        "enum": ["position", "rotation", "scale", "color"]
+                    },
+                    "keyframes": {
+                        "type": "array",
+                        "description": format!("2 to {} keyframes: {{\"t_seconds\", \"value\"}}. value is [x, y, z] for position and scale, Euler degrees [x, y, z] for rotation (720 spins twice), [r, g, b] or [r, g, b, a] for color", super::animation::MAX_KEYFRAMES),
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "t_seconds": {"type": "number"},
+                                "value": {"type": "array", "items": {"type": "number"}}
+                            },
+                            "required": ["t_seconds", "value"]
+                        }
+                    },
+                    "loop": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Restart from the first keyframe after the last; otherwise hold the last value"
+                    },
+                    "easing": {
+                        "type": "string",
+                        "enum": ["linear", "ease_in", "ease_out", "ease_in_out", "step"],
+                        "default": "linear"
+                    }
+                },
+                "required": ["name", "property", "keyframes"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let cmd: AnimateEntityCmd = serde_json::from_str(arguments)
+            .map_err(|e| anyhow::anyhow!("Invalid animation arguments: {}", e))?;
+
+        match self.bridge.send(GenCommand::AnimateEntity(cmd)).await? {
+            GenResponse::Animated(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_stop_animation
+// ===========================================================================
+
+struct GenStopAnimationTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenStopAnimationTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenStopAnimationTool {
+    fn name(&self) -> &str {
+        "gen_stop_animation"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_stop_animation".into(),
+            description:
+                "Stop all animations on an entity. It stays wherever the animation left it.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"}
+                },
+                "required": ["name"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let name = args["name"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing name"))?
+            .to_string();
+
+        match self.bridge.send(GenCommand::StopAnimation { name }).await? {
+            GenResponse::AnimationStopped { name, stopped } => {
+                Ok(format!("Stopped {} animation(s) on '{}'", stopped, name))
+            }
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_snapshot_scene
+// ===========================================================================
+
+struct GenSnapshotSceneTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenSnapshotSceneTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenSnapshotSceneTool {
+    fn name(&self) -> &str {
+        "gen_snapshot_scene"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_snapshot_scene".into(),
+            description: "Save the whole scene (entities, lights, camera pose, environment) in memory under a label, so you can try a variation and gen_restore_snapshot back to compare. Reusing a label overwrites it.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "label": {
+                        "type": "string",
+                        "description": "Snapshot name, e.g. \"before_lighting\""
+                    }
+                },
+                "required": ["label"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let label = args["label"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing label"))?
+            .to_string();
+
+        match self
+            .bridge
+            .send(GenCommand::SnapshotScene { label })
+            .await?
+        {
+            GenResponse::SnapshotSaved(info) => Ok(format!(
+                "Saved snapshot '{}' ({} entities)",
+                info.label, info.entity_count
+            )),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_restore_snapshot
+// ===========================================================================
+
+struct GenRestoreSnapshotTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenRestoreSnapshotTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenRestoreSnapshotTool {
+    fn name(&self) -> &str {
+        "gen_restore_snapshot"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_restore_snapshot".into(),
+            description: "Replace the current scene with a snapshot saved by gen_snapshot_scene. The snapshot is kept and can be restored again.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "label": {
+                        "type": "string",
+                        "description": "Snapshot to restore"
+                    }
+                },
+                "required": ["label"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let label = args["label"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing label"))?
+            .to_string();
+
+        match self
+            .bridge
+            .send(GenCommand::RestoreSnapshot { label })
+            .await?
+        {
+            GenResponse::SnapshotRestored(info) => Ok(format!(
+                "Restored snapshot '{}' ({} entities)",
+                info.label, info.entity_count
+            )),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_list_snapshots
+// ===========================================================================
+
+struct GenListSnapshotsTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenListSnapshotsTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenListSnapshotsTool {
+    fn name(&self) -> &str {
+        "gen_list_snapshots"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_list_snapshots".into(),
+            description: "List scene snapshots saved with gen_snapshot_scene.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        }
+    }
+
+    async fn execute(&self, _arguments: &str) -> Result<String> {
+        match self.bridge.send(GenCommand::ListSnapshots).await? {
+            GenResponse::Snapshots { snapshots } => Ok(serde_json::to_string_pretty(&snapshots)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
 
-        let vertices: Vec<[f32; 3]> = args["vertices"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Missing vertices"))?
-            .iter()
-            .map(parse_json_vec3)
-            .collect();
+// ===========================================================================
+// gen_set_export_settings
+// ===========================================================================
 
-        let indices: Vec<u32> = args["indices"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Missing indices"))?
-            .iter()
-            .filter_map(|v| v.as_u64().map(|n| n as u32))
-            .collect();
+struct GenSetExportSettingsTool {
+    bridge: Arc<GenBridge>,
+}
 
-        let normals: Option<Vec<[f32; 3]>> = args
-            .get("normals")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter().map(parse_json_vec3).collect());
+impl GenSetExportSettingsTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
 
-        let uvs: Option<Vec<[f32; 2]>> = args
-            .get("uvs")
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter().map(parse_json_vec2).collect());
+#[async_trait]
+impl Tool for GenSetExportSettingsTool {
+    fn name(&self) -> &str {
+        "gen_set_export_settings"
+    }
 
-        let cmd = RawMeshCmd {
-            name: args["name"]
-                .as_str()
-                .ok_or_else(|| anyhow::anyhow!("Missing name"))?
-                .to_string(),
-            vertices,
-            indices,
-            normals,
-            uvs,
-            color: parse_f32_4(&args["color"], [0.8, 0.8, 0.8, 1.0]),
-            metallic: args["metallic"].as_f64().unwrap_or(0.0) as f32,
-            roughness: args["roughness"].as_f64().unwrap_or(0.5) as f32,
-            position: parse_f32_array(&args["position"], [0.0, 0.0, 0.0]),
-        };
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_set_export_settings".into(),
+            description: "Set antialiasing, tone mapping and color space for subsequent screenshots/exports. Omitted fields follow the render quality preset (gen_set_quality). Returns the settings in effect.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "msaa_samples": {
+                        "type": "integer",
+                        "enum": [1, 2, 4, 8],
+                        "description": "MSAA sample count; 1 disables. Only available at quality low (ambient occlusion requires MSAA off)"
+                    },
+                    "tonemapping": {
+                        "type": "string",
+                        "enum": ["none", "reinhard", "reinhard_luminance", "aces_fitted", "agx", "tony_mc_mapface", "blender_filmic"],
+                        "description": "Tone mapping curve"
+                    },
+                    "color_space": {
+                        "type": "string",
+                        "enum": ["srgb", "linear"],
+                        "default": "srgb",
+                        "description": "srgb for viewing, linear for compositing"
+                    }
+                }
+            }),
+        }
+    }
 
-        match self.bridge.send(GenCommand::SpawnMesh(cmd)).await? {
-            GenResponse::Spawned { name, entity_id } => Ok(format!(
-                "Spawned mesh '{}' (entity_id: {})",
-                name, entity_id
-            )),
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let settings: ExportSettings = serde_json::from_str(arguments)
+            .map_err(|e| anyhow::anyhow!("Invalid export settings: {}", e))?;
+
+        match self
+            .bridge
+            .send(GenCommand::SetExportSettings(settings))
+            .await?
+        {
+            GenResponse::ExportSettingsSet(data) => Ok(serde_json::to_string_pretty(&data)?),
             GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
             other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
         }
@@ -889,6 +2196,270 @@ impl Tool for GenExportScreenshotTool {
     }
 }
 
+// ===========================================================================
+// gen_export_obj
+// ===========================================================================
+
+struct GenExportObjTool {
+    bridge: Arc<GenBridge>,
+    workspace: PathBuf,
+}
+
+impl GenExportObjTool {
+    fn new(bridge: Arc<GenBridge>, workspace: PathBuf) -> Self {
+        Self { bridge, workspace }
+    }
+
+    /// Resolve an output path against the workspace and fail unless its
+    /// directory exists inside it.
+    fn resolve_output(&self, path: &str) -> Result<String> {
+        let expanded = PathBuf::from(shellexpand::tilde(path).to_string());
+        let full = self.workspace.join(expanded);
+        if full.extension().is_none_or(|ext| ext != "obj") {
+            anyhow::bail!("Output path {} must end in .obj", path);
+        }
+        let (Some(dir), Some(file_name)) = (full.parent(), full.file_name()) else {
+            anyhow::bail!("Invalid output path: {}", path);
+        };
+        let dir = dir
+            .canonicalize()
+            .map_err(|_| anyhow::anyhow!("Output directory not found: {}", dir.display()))?;
+        let workspace = self
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace.clone());
+        if !dir.starts_with(&workspace) {
+            anyhow::bail!("Output path {} is outside the workspace", path);
+        }
+        Ok(dir.join(file_name).to_string_lossy().into_owned())
+    }
+}
+
+#[async_trait]
+impl Tool for GenExportObjTool {
+    fn name(&self) -> &str {
+        "gen_export_obj"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_export_obj".into(),
+            description: "Export the visible meshes of the scene as a Wavefront OBJ file, in world space, optionally with a .mtl material file beside it. Textures are not included.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Output .obj path, relative to the workspace (default: a temporary file)"
+                    },
+                    "export_materials": {
+                        "type": "boolean",
+                        "default": true,
+                        "description": "Also write a .mtl file with the materials' colors"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let path = match args["path"].as_str() {
+            Some(path) => Some(self.resolve_output(path)?),
+            None => None,
+        };
+        let export_materials = args["export_materials"].as_bool().unwrap_or(true);
+
+        match self
+            .bridge
+            .send(GenCommand::ExportObj {
+                path,
+                export_materials,
+            })
+            .await?
+        {
+            GenResponse::Exported { path } => Ok(format!("Exported scene to: {}", path)),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_orbit_light
+// ===========================================================================
+
+struct GenOrbitLightTool {
+    bridge: Arc<GenBridge>,
+    workspace: PathBuf,
+}
+
+impl GenOrbitLightTool {
+    fn new(bridge: Arc<GenBridge>, workspace: PathBuf) -> Self {
+        Self { bridge, workspace }
+    }
+
+    /// Resolve the screenshot directory against the workspace and fail
+    /// unless it lies inside it. The directory may not exist yet, but its
+    /// parent must.
+    fn resolve_output_dir(&self, path: &str) -> Result<String> {
+        let expanded = PathBuf::from(shellexpand::tilde(path).to_string());
+        let full = self.workspace.join(expanded);
+        let dir = match full.canonicalize() {
+            Ok(dir) => dir,
+            Err(_) => {
+                let (Some(parent), Some(dir_name)) = (full.parent(), full.file_name()) else {
+                    anyhow::bail!("Invalid output directory: {}", path);
+                };
+                parent
+                    .canonicalize()
+                    .map_err(|_| {
+                        anyhow::anyhow!("Output directory not found: {}", parent.display())
+                    })?
+                    .join(dir_name)
+            }
+        };
+        let workspace = self
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace.clone());
+        if !dir.starts_with(&workspace) {
+            anyhow::bail!("Output directory {} is outside the workspace", path);
+        }
+        Ok(dir.to_string_lossy().into_owned())
+    }
+}
+
+#[async_trait]
+impl Tool for GenOrbitLightTool {
+    fn name(&self) -> &str {
+        "gen_orbit_light"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_orbit_light".into(),
+            description: "Move a light in a circle around the scene center, exporting a screenshot at each step, then put it back. Use to study materials and shadows from every side. Returns the image paths.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Name of the light (directional, point or spot)"
+                    },
+                    "frames": {
+                        "type": "integer",
+                        "default": 8,
+                        "description": "Number of evenly spaced steps (1-360)"
+                    },
+                    "radius": {
+                        "type": "number",
+                        "description": "Horizontal distance from the scene center (default: the light's current distance)"
+                    },
+                    "height": {
+                        "type": "number",
+                        "description": "Height above the scene center (default: the light's current height)"
+                    },
+                    "output_dir": {
+                        "type": "string",
+                        "description": "Directory in the workspace for the screenshots (created if missing; its parent must exist)"
+                    }
+                },
+                "required": ["name", "output_dir"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let cmd = OrbitLightCmd {
+            name: args["name"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing name"))?
+                .to_string(),
+            frames: u32::try_from(args["frames"].as_u64().unwrap_or(8)).map_err(|_| {
+                anyhow::anyhow!(
+                    "frames must be between 1 and {}",
+                    super::light_orbit::MAX_ORBIT_FRAMES
+                )
+            })?,
+            radius: args["radius"].as_f64().map(|v| v as f32),
+            height: args["height"].as_f64().map(|v| v as f32),
+            output_dir: self.resolve_output_dir(
+                args["output_dir"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Missing output_dir"))?,
+            )?,
+        };
+
+        match self.bridge.send(GenCommand::OrbitLight(cmd)).await? {
+            GenResponse::LightOrbited(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
+// ===========================================================================
+// gen_set_deterministic_mode
+// ===========================================================================
+
+struct GenSetDeterministicModeTool {
+    bridge: Arc<GenBridge>,
+}
+
+impl GenSetDeterministicModeTool {
+    fn new(bridge: Arc<GenBridge>) -> Self {
+        Self { bridge }
+    }
+}
+
+#[async_trait]
+impl Tool for GenSetDeterministicModeTool {
+    fn name(&self) -> &str {
+        "gen_set_deterministic_mode"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema {
+            name: "gen_set_deterministic_mode".into(),
+            description: "Query or set deterministic rendering. When enabled, every frame advances the scene by exactly 1/frame_rate seconds and frames keep running with the window unfocused, so gen_screenshot's wait_frames renders exactly that many frames before capturing. Call with no arguments to report the current mode.".into(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "enabled": {
+                        "type": "boolean",
+                        "description": "true for fixed-step frames, false for normal real-time rendering"
+                    },
+                    "frame_rate": {
+                        "type": "number",
+                        "description": "Simulated frames per second while enabled (default: 60, max 240)"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<String> {
+        let args: Value = serde_json::from_str(arguments)?;
+        let enabled = args["enabled"].as_bool();
+        let frame_rate = args["frame_rate"].as_f64().map(|v| v as f32);
+
+        match self
+            .bridge
+            .send(GenCommand::SetDeterministicMode {
+                enabled,
+                frame_rate,
+            })
+            .await?
+        {
+            GenResponse::DeterministicMode(data) => Ok(serde_json::to_string_pretty(&data)?),
+            GenResponse::Error { message } => Err(anyhow::anyhow!("{}", message)),
+            other => Err(anyhow::anyhow!("Unexpected response: {:?}", other)),
+        }
+    }
+}
+
 // ===========================================================================
 // JSON parsing helpers
 // ===========================================================================
@@ -971,3 +2542,19 @@ fn parse_json_vec2(val: &Value) -> [f32; 2] {
         arr.get(1).and_then(|x| x.as_f64()).unwrap_or(0.0) as f32,
     ]
 }
+
+fn parse_opt_string_array(val: &Value) -> Option<Vec<String>> {
+    val.as_array().map(|arr| {
+        arr.iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect()
+    })
+}
+
+fn parse_opt_string_map(val: &Value) -> Option<HashMap<String, String>> {
+    val.as_object().map(|obj| {
+        obj.iter()
+            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+            .collect()
+    })
+}
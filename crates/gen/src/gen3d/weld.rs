@@ -0,0 +1,321 @@
+//! Vertex welding — merge vertices closer than a tolerance into one, so
+//! triangles that were split along an edge share it again and get smooth
+//! shared normals.
+//!
+//! Positions are bucketed in a spatial hash with cells as wide as the
+//! tolerance, so each vertex is only compared against its 27 neighbouring
+//! cells. Vertices at the same position but with different UVs can be kept
+//! apart to preserve texture seams.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+
+use super::commands::{GenResponse, WeldMeshCmd, WeldMeshData};
+use super::registry::NameRegistry;
+
+/// UVs closer than this count as the same texture coordinate.
+const UV_EPSILON: f32 = 1e-5;
+
+/// Smallest spatial hash cell, so a zero tolerance still buckets exact
+/// duplicates together.
+const MIN_CELL_SIZE: f32 = 1e-6;
+
+/// Result of welding a mesh.
+pub struct WeldedMesh {
+    pub mesh: Mesh,
+    pub vertices_before: usize,
+    pub vertices_after: usize,
+    pub triangles_before: usize,
+    pub triangles_after: usize,
+}
+
+/// Weld the vertices of a triangle-list mesh. Only positions and the first
+/// UV set carry over; normals are recomputed as smooth shared normals, and
+/// triangles that collapse to a line or point are dropped.
+pub fn weld_mesh(
+    mesh: &Mesh,
+    tolerance: f32,
+    preserve_uv_seams: bool,
+) -> Result<WeldedMesh, String> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return Err("Only triangle-list meshes can be welded".to_string());
+    }
+    if !(tolerance.is_finite() && tolerance >= 0.0) {
+        return Err(format!(
+            "Invalid tolerance {}: must be zero or positive",
+            tolerance
+        ));
+    }
+
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return Err("Mesh has no vertex positions".to_string());
+    };
+    let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => Some(uvs),
+        _ => None,
+    };
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(indices) => indices.iter().collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    let cell_size = tolerance.max(MIN_CELL_SIZE);
+    let cell_of = |p: [f32; 3]| {
+        [
+            (p[0] / cell_size).floor() as i64,
+            (p[1] / cell_size).floor() as i64,
+            (p[2] / cell_size).floor() as i64,
+        ]
+    };
+
+    // Map every original vertex to a welded one
+    let mut grid: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+    let mut welded_positions: Vec<[f32; 3]> = Vec::new();
+    let mut welded_uvs: Vec<[f32; 2]> = Vec::new();
+    let mut remap = Vec::with_capacity(positions.len());
+    for (i, &position) in positions.iter().enumerate() {
+        let uv = uvs.map(|uvs| uvs[i]);
+        let cell = cell_of(position);
+
+        let mut found = None;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let key = [cell[0] + dx, cell[1] + dy, cell[2] + dz];
+                    for &candidate in grid.get(&key).into_iter().flatten() {
+                        let other = welded_positions[candidate as usize];
+                        if Vec3::from_array(other).distance(Vec3::from_array(position)) > tolerance
+                        {
+                            continue;
+                        }
+                        if preserve_uv_seams
+                            && let Some(uv) = uv
+                            && Vec2::from_array(welded_uvs[candidate as usize])
+                                .distance(Vec2::from_array(uv))
+                                > UV_EPSILON
+                        {
+                            continue;
+                        }
+                        found = Some(candidate);
+                        break 'search;
+                    }
+                }
+            }
+        }
+
+        let index = found.unwrap_or_else(|| {
+            let index = welded_positions.len() as u32;
+            welded_positions.push(position);
+            if let Some(uv) = uv {
+                welded_uvs.push(uv);
+            }
+            grid.entry(cell).or_default().push(index);
+            index
+        });
+        remap.push(index);
+    }
+
+    // Remap triangles, dropping the ones welding collapsed
+    let mut welded_indices = Vec::with_capacity(indices.len());
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [remap[triangle[0]], remap[triangle[1]], remap[triangle[2]]];
+        if a != b && b != c && a != c {
+            welded_indices.extend([a, b, c]);
+        }
+    }
+
+    let normals = smooth_normals(&welded_positions, &welded_indices);
+
+    let vertices_after = welded_positions.len();
+    let triangles_after = welded_indices.len() / 3;
+    let mut welded = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    welded.insert_attribute(Mesh::ATTRIBUTE_POSITION, welded_positions);
+    welded.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    if uvs.is_some() {
+        welded.insert_attribute(Mesh::ATTRIBUTE_UV_0, welded_uvs);
+    }
+    welded.insert_indices(Indices::U32(welded_indices));
+
+    Ok(WeldedMesh {
+        mesh: welded,
+        vertices_before: positions.len(),
+        vertices_after,
+        triangles_before: indices.len() / 3,
+        triangles_after,
+    })
+}
+
+/// Area-weighted average of the normals of every triangle using a vertex.
+fn smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [
+            triangle[0] as usize,
+            triangle[1] as usize,
+            triangle[2] as usize,
+        ];
+        let pa = Vec3::from_array(positions[a]);
+        let pb = Vec3::from_array(positions[b]);
+        let pc = Vec3::from_array(positions[c]);
+        // Unnormalized, so larger triangles weigh more
+        let face = (pb - pa).cross(pc - pa);
+        normals[a] += face;
+        normals[b] += face;
+        normals[c] += face;
+    }
+    normals
+        .into_iter()
+        .map(|n| n.try_normalize().unwrap_or(Vec3::Y).to_array())
+        .collect()
+}
+
+/// Weld the mesh of a named entity in place and report the vertex counts.
+pub fn weld_entity_mesh(world: &mut World, cmd: WeldMeshCmd) -> GenResponse {
+    let Some(entity) = world.resource::<NameRegistry>().get_entity(&cmd.name) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' not found", cmd.name),
+        };
+    };
+    let Some(handle) = world.get::<Mesh3d>(entity).map(|m| m.0.clone()) else {
+        return GenResponse::Error {
+            message: format!("Entity '{}' has no mesh", cmd.name),
+        };
+    };
+
+    let mut meshes = world.resource_mut::<Assets<Mesh>>();
+    let Some(mesh) = meshes.get(&handle) else {
+        return GenResponse::Error {
+            message: format!("Mesh for '{}' is not loaded", cmd.name),
+        };
+    };
+    let welded = match weld_mesh(mesh, cmd.tolerance, cmd.preserve_uv_seams) {
+        Ok(welded) => welded,
+        Err(message) => return GenResponse::Error { message },
+    };
+
+    // A new asset, so other entities sharing the old mesh are unaffected
+    let new_handle = meshes.add(welded.mesh);
+    world.entity_mut(entity).insert(Mesh3d(new_handle));
+
+    GenResponse::MeshWelded(WeldMeshData {
+        name: cmd.name,
+        vertices_before: welded.vertices_before,
+        vertices_after: welded.vertices_after,
+        triangles_before: welded.triangles_before,
+        triangles_after: welded.triangles_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two triangles forming a quad, with the shared edge's vertices
+    /// duplicated and the duplicates nudged by `gap` along X.
+    fn split_quad(gap: f32, uvs: Option<Vec<[f32; 2]>>) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [gap, 0.0, 0.0],
+                [1.0 + gap, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ],
+        );
+        if let Some(uvs) = uvs {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        }
+        mesh
+    }
+
+    #[test]
+    fn weld_merges_vertices_within_tolerance() {
+        let welded = weld_mesh(&split_quad(1e-4, None), 1e-3, false).unwrap();
+        assert_eq!(welded.vertices_before, 6);
+        assert_eq!(welded.vertices_after, 4);
+        assert_eq!(welded.triangles_after, 2);
+    }
+
+    #[test]
+    fn weld_keeps_vertices_beyond_tolerance() {
+        let welded = weld_mesh(&split_quad(1e-4, None), 1e-5, false).unwrap();
+        assert_eq!(welded.vertices_after, 6);
+    }
+
+    #[test]
+    fn zero_tolerance_merges_exact_duplicates() {
+        let welded = weld_mesh(&split_quad(0.0, None), 0.0, false).unwrap();
+        assert_eq!(welded.vertices_after, 4);
+        let welded = weld_mesh(&split_quad(1e-6, None), 0.0, false).unwrap();
+        assert_eq!(welded.vertices_after, 6);
+    }
+
+    #[test]
+    fn uv_seams_are_kept_apart_when_asked() {
+        let uvs = vec![
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [1.0, 1.0],
+            [0.5, 0.0],
+            [0.5, 1.0],
+            [0.0, 1.0],
+        ];
+        let mesh = split_quad(0.0, Some(uvs));
+        assert_eq!(weld_mesh(&mesh, 1e-3, true).unwrap().vertices_after, 6);
+        assert_eq!(weld_mesh(&mesh, 1e-3, false).unwrap().vertices_after, 4);
+    }
+
+    #[test]
+    fn collapsed_triangles_are_dropped() {
+        let mut mesh = split_quad(0.0, None);
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [0.0005, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ],
+        );
+        let welded = weld_mesh(&mesh, 1e-3, false).unwrap();
+        assert_eq!(welded.triangles_before, 2);
+        assert_eq!(welded.triangles_after, 1);
+    }
+
+    #[test]
+    fn welded_normals_are_smooth_and_unit_length() {
+        let welded = weld_mesh(&split_quad(0.0, None), 1e-3, false).unwrap();
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            welded.mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("welded mesh has no normals");
+        };
+        for &n in normals {
+            assert!(Vec3::from_array(n).abs_diff_eq(Vec3::Z, 1e-5));
+        }
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        assert!(weld_mesh(&split_quad(0.0, None), -1.0, false).is_err());
+        assert!(weld_mesh(&split_quad(0.0, None), f32::NAN, false).is_err());
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default());
+        assert!(weld_mesh(&lines, 1e-3, false).is_err());
+    }
+}
@@ -111,7 +111,10 @@ async fn run_agent_loop(
 
     // Create safe tools + gen tools
     let mut tools = create_safe_tools(&config, Some(memory.clone()))?;
-    tools.extend(gen3d::tools::create_gen_tools(bridge));
+    tools.extend(gen3d::tools::create_gen_tools(
+        bridge,
+        config.workspace_path(),
+    ));
 
     // Create agent with combined tools
     let mut agent = Agent::new_with_tools(config.clone(), agent_id, memory, tools)?;
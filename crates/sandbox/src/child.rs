@@ -3,7 +3,7 @@ use crate::policy::{SandboxLevel, SandboxPolicy};
 /// Entry point for the sandbox child process.
 ///
 /// Called when the binary detects argv[0] ends with "localgpt-sandbox".
-/// This function never returns — it either execs bash or exits.
+/// This function never returns — it either execs the shell or exits.
 ///
 /// argv layout:
 ///   argv[0] = "localgpt-sandbox" (already consumed by dispatch)
@@ -41,8 +41,8 @@ pub fn sandbox_child_main() -> ! {
         std::process::exit(1);
     }
 
-    // exec bash -c <command>
-    exec_bash(command);
+    // exec <shell> -c <command>
+    exec_shell(&policy, command);
 }
 
 /// Apply resource limits using setrlimit.
@@ -91,26 +91,30 @@ fn apply_platform_sandbox(policy: &SandboxPolicy) -> Result<(), String> {
     }
 }
 
-/// Exec bash with the given command, replacing the current process.
-/// On macOS, if sandbox-exec profile is set, uses sandbox-exec instead.
-fn exec_bash(command: &str) -> ! {
+/// Exec the policy's shell with the given command, replacing the current
+/// process. On macOS, if sandbox-exec profile is set, uses sandbox-exec instead.
+fn exec_shell(policy: &SandboxPolicy, command: &str) -> ! {
     #[cfg(target_os = "macos")]
     {
         // On macOS, apply_sandbox sets _LOCALGPT_SBPL_PROFILE env var.
-        // If set, exec through sandbox-exec instead of plain bash.
+        // If set, exec through sandbox-exec instead of the plain shell.
         if std::env::var("_LOCALGPT_SBPL_PROFILE").is_ok() {
-            crate::macos::exec_sandboxed(command);
+            crate::macos::exec_sandboxed(policy, command);
         }
     }
 
     use std::os::unix::process::CommandExt;
 
-    let err = std::process::Command::new("/bin/bash")
-        .arg("-c")
+    let err = std::process::Command::new(&policy.shell)
+        .arg(&policy.shell_flag)
         .arg(command)
         .exec();
 
     // exec() only returns on error
-    eprintln!("localgpt-sandbox: failed to exec bash: {}", err);
+    eprintln!(
+        "localgpt-sandbox: failed to exec {}: {}",
+        policy.shell.display(),
+        err
+    );
     std::process::exit(1);
 }
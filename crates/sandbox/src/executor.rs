@@ -1,7 +1,31 @@
 use anyhow::Result;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use localgpt_core::agent::sanitize::Truncation;
+
 use crate::policy::SandboxPolicy;
+use crate::privdrop::{RunAs, run_as_user};
+
+/// Per-invocation options for a sandboxed command.
+#[derive(Debug, Clone, Default)]
+pub struct ExecOptions {
+    /// Working directory for the command. Defaults to the policy workspace.
+    pub cwd: Option<PathBuf>,
+
+    /// Extra environment variables set for the command.
+    pub env: Vec<(String, String)>,
+
+    /// Inherited environment variables to remove before running the command.
+    pub env_remove: Vec<String>,
+
+    /// Start the command in a new, empty network namespace (Linux only).
+    pub isolate_network: bool,
+
+    /// Run the command as this user instead of our own (Unix only). The
+    /// command is refused if the switch fails or isn't supported.
+    pub run_as: Option<RunAs>,
+}
 
 /// Run a shell command inside the sandbox.
 ///
@@ -9,11 +33,22 @@ use crate::policy::SandboxPolicy;
 /// 1. Serializes the policy to JSON
 /// 2. Re-execs the current binary with argv[0]="localgpt-sandbox"
 /// 3. Passes policy + command as arguments
-/// 4. Collects output and enforces timeout
+/// 4. Collects output up to `policy.max_output_bytes` and enforces timeout
 pub async fn run_sandboxed(
     command: &str,
     policy: &SandboxPolicy,
     timeout_ms: u64,
+) -> Result<(String, i32)> {
+    run_sandboxed_with(command, policy, timeout_ms, &ExecOptions::default()).await
+}
+
+/// Run a shell command inside the sandbox with a custom working directory
+/// and environment. See [`run_sandboxed`].
+pub async fn run_sandboxed_with(
+    command: &str,
+    policy: &SandboxPolicy,
+    timeout_ms: u64,
+    options: &ExecOptions,
 ) -> Result<(String, i32)> {
     let policy_json = serde_json::to_string(policy)?;
 
@@ -24,57 +59,194 @@ pub async fn run_sandboxed(
     // argv[0] = "localgpt-sandbox" (sentinel for dispatch)
     // argv[1] = policy JSON
     // argv[2] = shell command to execute
-    let timeout_duration = Duration::from_millis(timeout_ms);
-
-    let output = tokio::time::timeout(
-        timeout_duration,
-        tokio::process::Command::new(&exe_path)
-            .arg0("localgpt-sandbox")
-            .arg(&policy_json)
-            .arg(command)
-            .current_dir(&policy.workspace_path)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .output(),
-    )
-    .await
-    .map_err(|_| anyhow::anyhow!("Sandboxed command timed out after {}ms", timeout_ms))??;
+    let cwd = options.cwd.as_ref().unwrap_or(&policy.workspace_path);
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut cmd = tokio::process::Command::new(&exe_path);
+    cmd.arg0("localgpt-sandbox")
+        .arg(&policy_json)
+        .arg(command)
+        .current_dir(cwd);
+    for key in &options.env_remove {
+        cmd.env_remove(key);
+    }
+    cmd.envs(options.env.iter().map(|(k, v)| (k, v)))
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    if options.isolate_network {
+        crate::netns::isolate_network(&mut cmd);
+    }
+    if let Some(run_as) = options.run_as
+        && !run_as_user(&mut cmd, run_as)
+    {
+        anyhow::bail!("Running commands as another user is not supported on this platform");
+    }
+
+    cmd.stdin(std::process::Stdio::null()).kill_on_drop(true);
+    let child = cmd.spawn().map_err(|e| match options.run_as {
+        Some(run_as) => anyhow::anyhow!(
+            "Refusing to run command: could not switch to uid {} / gid {}: {}",
+            run_as.uid,
+            run_as.gid,
+            e
+        ),
+        None => e.into(),
+    })?;
 
-    let max_bytes = policy.max_output_bytes as usize;
+    let output = collect_capped_output(child, policy.max_output_bytes as usize, timeout_ms).await?;
+    Ok((output.render(), output.exit_code))
+}
+
+/// What a command printed, collected by [`collect_capped_output`].
+#[derive(Debug, Clone)]
+pub struct CappedOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// -1 if the command was killed or ended by a signal.
+    pub exit_code: i32,
+    /// Whether the byte cap was hit and the command killed.
+    pub truncated: bool,
+    /// Whether the command ran past its timeout and was killed.
+    pub timed_out: bool,
+    timeout_ms: u64,
+}
 
-    let mut result = String::new();
+impl CappedOutput {
+    /// Stdout, then stderr under a `STDERR:` header, then a notice if the
+    /// byte cap (a cut-off [`Truncation`]) or the timeout stopped the
+    /// command. A command that completed silently is reported by its exit
+    /// code.
+    pub fn render(&self) -> String {
+        let stdout = String::from_utf8_lossy(&self.stdout);
+        let stderr = String::from_utf8_lossy(&self.stderr);
 
-    if !stdout.is_empty() {
-        if stdout.len() > max_bytes {
-            result.push_str(&stdout[..max_bytes]);
+        let mut result = String::new();
+        if !stdout.is_empty() {
+            result.push_str(&stdout);
+        }
+        if !stderr.is_empty() {
+            if !result.is_empty() {
+                result.push_str("\n\nSTDERR:\n");
+            }
+            result.push_str(&stderr);
+        }
+
+        if self.timed_out {
             result.push_str(&format!(
-                "\n\n[Output truncated, {} bytes total]",
-                stdout.len()
+                "\n\n[Command timed out after {}ms and was killed; output so far is shown]",
+                self.timeout_ms
             ));
+        } else if self.truncated {
+            result = Truncation::cut_off(&result).render();
+        } else if result.is_empty() {
+            result = format!("Command completed with exit code: {}", self.exit_code);
+        }
+        result
+    }
+}
+
+/// Collect a spawned child's output as it arrives, up to `max_bytes` of
+/// stdout and stderr together (0 = unlimited), so the cap applies while
+/// the command is still running rather than after it exits.
+///
+/// The child must have been spawned with piped stdout and stderr. It is
+/// killed when it hits the cap or runs past `timeout_ms`; either way the
+/// output collected so far is returned, marked `truncated` or `timed_out`.
+pub async fn collect_capped_output(
+    mut child: tokio::process::Child,
+    max_bytes: usize,
+    timeout_ms: u64,
+) -> Result<CappedOutput> {
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let outcome = tokio::time::timeout(Duration::from_millis(timeout_ms), async {
+        let capped = read_capped_output(&mut child, max_bytes, &mut stdout, &mut stderr).await?;
+        if capped {
+            Ok::<_, std::io::Error>(None)
         } else {
-            result.push_str(&stdout);
+            child.wait().await.map(Some)
+        }
+    })
+    .await;
+
+    let (status, timed_out) = match outcome {
+        Ok(Ok(status)) => (status, false),
+        Ok(Err(e)) => {
+            let _ = child.kill().await;
+            return Err(e.into());
         }
+        Err(_) => (None, true),
+    };
+    let truncated = status.is_none() && !timed_out;
+    if status.is_none() {
+        let _ = child.kill().await;
     }
 
-    if !stderr.is_empty() {
-        if !result.is_empty() {
-            result.push_str("\n\nSTDERR:\n");
+    Ok(CappedOutput {
+        stdout,
+        stderr,
+        exit_code: status.and_then(|s| s.code()).unwrap_or(-1),
+        truncated,
+        timed_out,
+        timeout_ms,
+    })
+}
+
+/// Read a child's stdout and stderr as they arrive until both close, or
+/// until together they reach `max_bytes` (0 = unlimited). Returns `true`
+/// if the cap was hit; the child is then still running.
+async fn read_capped_output(
+    child: &mut tokio::process::Child,
+    max_bytes: usize,
+    stdout_buf: &mut Vec<u8>,
+    stderr_buf: &mut Vec<u8>,
+) -> std::io::Result<bool> {
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    let mut stdout_chunk = [0u8; 8192];
+    let mut stderr_chunk = [0u8; 8192];
+
+    while stdout.is_some() || stderr.is_some() {
+        let (from_stdout, n) = tokio::select! {
+            n = read_pipe_chunk(&mut stdout, &mut stdout_chunk) => (true, n?),
+            n = read_pipe_chunk(&mut stderr, &mut stderr_chunk) => (false, n?),
+        };
+        if n == 0 {
+            if from_stdout {
+                stdout = None;
+            } else {
+                stderr = None;
+            }
+            continue;
         }
-        let remaining = max_bytes.saturating_sub(result.len());
-        if stderr.len() > remaining && remaining > 0 {
-            result.push_str(&stderr[..remaining]);
-            result.push_str("\n[stderr truncated]");
+
+        let room = if max_bytes == 0 {
+            usize::MAX
         } else {
-            result.push_str(&stderr);
+            max_bytes.saturating_sub(stdout_buf.len() + stderr_buf.len())
+        };
+        let (buf, chunk) = if from_stdout {
+            (&mut *stdout_buf, &stdout_chunk[..n])
+        } else {
+            (&mut *stderr_buf, &stderr_chunk[..n])
+        };
+        buf.extend_from_slice(&chunk[..n.min(room)]);
+        if n > room {
+            return Ok(true);
         }
     }
+    Ok(false)
+}
 
-    let exit_code = output.status.code().unwrap_or(-1);
-
-    Ok((result, exit_code))
+/// Read the next chunk from a pipe; a closed (`None`) pipe never yields.
+async fn read_pipe_chunk<R: tokio::io::AsyncRead + Unpin>(
+    pipe: &mut Option<R>,
+    chunk: &mut [u8],
+) -> std::io::Result<usize> {
+    use tokio::io::AsyncReadExt;
+    match pipe {
+        Some(pipe) => pipe.read(chunk).await,
+        None => std::future::pending().await,
+    }
 }
 
 /// Trait extension for Command to set argv[0].
@@ -99,3 +271,59 @@ impl CommandExt for tokio::process::Command {
         self
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn spawn_sh(script: &str) -> tokio::process::Child {
+        tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_capped_output_kills_at_cap() {
+        let output = collect_capped_output(spawn_sh("yes"), 1000, 10_000)
+            .await
+            .unwrap();
+        assert!(output.truncated);
+        assert_eq!(output.stdout.len(), 1000);
+        assert!(output.render().ends_with(
+            "[...truncated after 1000 characters: output reached its size cap and the rest was not captured.]"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_capped_output_completed() {
+        let output = collect_capped_output(spawn_sh("echo out; echo err >&2"), 1000, 10_000)
+            .await
+            .unwrap();
+        assert!(!output.truncated);
+        assert_eq!(output.render(), "out\n\n\nSTDERR:\nerr\n");
+
+        let silent = collect_capped_output(spawn_sh("exit 3"), 1000, 10_000)
+            .await
+            .unwrap();
+        assert_eq!(silent.render(), "Command completed with exit code: 3");
+    }
+
+    #[tokio::test]
+    async fn test_capped_output_keeps_partial_output_on_timeout() {
+        let output = collect_capped_output(spawn_sh("echo started; exec sleep 10"), 1000, 300)
+            .await
+            .unwrap();
+        assert!(output.timed_out && !output.truncated);
+        assert_eq!(output.stdout, b"started\n");
+        assert_eq!(output.exit_code, -1);
+        let rendered = output.render();
+        assert!(rendered.starts_with("started\n"), "{}", rendered);
+        assert!(rendered.contains("timed out after 300ms"), "{}", rendered);
+    }
+}
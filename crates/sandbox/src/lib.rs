@@ -7,6 +7,10 @@
 //! Platform enforcement:
 //! - Linux: Landlock LSM (filesystem) + seccomp-bpf (network syscall deny)
 //! - macOS: Seatbelt SBPL profiles via sandbox-exec
+//!
+//! Optionally, on Linux, commands can also be started in an empty network
+//! namespace (see [`netns`]), and on Unix run as a less privileged user
+//! (see [`privdrop`]).
 
 #[cfg(unix)]
 pub mod child;
@@ -16,10 +20,16 @@ pub mod executor;
 pub mod linux;
 #[cfg(target_os = "macos")]
 pub mod macos;
+pub mod netns;
 pub mod policy;
+pub mod privdrop;
 
 #[cfg(unix)]
 pub use child::sandbox_child_main;
 pub use detect::{SandboxCapabilities, detect_capabilities};
-pub use executor::run_sandboxed;
+pub use executor::{
+    CappedOutput, ExecOptions, collect_capped_output, run_sandboxed, run_sandboxed_with,
+};
+pub use netns::{NETWORK_NAMESPACE_SUPPORTED, isolate_network};
 pub use policy::{NetworkPolicy, SandboxLevel, SandboxMode, SandboxPolicy, build_policy};
+pub use privdrop::{PRIVILEGE_DROP_SUPPORTED, RunAs, run_as_user};
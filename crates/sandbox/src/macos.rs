@@ -8,8 +8,8 @@ use crate::policy::{NetworkPolicy, SandboxPolicy};
 pub fn apply_sandbox(policy: &SandboxPolicy) -> Result<(), String> {
     let profile = generate_sbpl_profile(policy);
 
-    // Store profile in env for the child's exec_bash to pick up.
-    // On macOS, the child execs sandbox-exec instead of bash directly.
+    // Store profile in env for the child's exec_shell to pick up.
+    // On macOS, the child execs sandbox-exec instead of the shell directly.
     // SAFETY: called before spawning the sandboxed child process
     unsafe { std::env::set_var("_LOCALGPT_SBPL_PROFILE", &profile) };
 
@@ -17,26 +17,30 @@ pub fn apply_sandbox(policy: &SandboxPolicy) -> Result<(), String> {
 }
 
 /// Execute a command under sandbox-exec with the given SBPL profile.
-/// This replaces the standard exec_bash on macOS when sandbox is active.
-pub fn exec_sandboxed(command: &str) -> ! {
+/// This replaces the standard exec_shell on macOS when sandbox is active.
+pub fn exec_sandboxed(policy: &SandboxPolicy, command: &str) -> ! {
     use std::os::unix::process::CommandExt;
 
     let profile = std::env::var("_LOCALGPT_SBPL_PROFILE").unwrap_or_default();
 
     if profile.is_empty() {
-        let err = std::process::Command::new("/bin/bash")
-            .arg("-c")
+        let err = std::process::Command::new(&policy.shell)
+            .arg(&policy.shell_flag)
             .arg(command)
             .exec();
-        eprintln!("localgpt-sandbox: failed to exec bash: {}", err);
+        eprintln!(
+            "localgpt-sandbox: failed to exec {}: {}",
+            policy.shell.display(),
+            err
+        );
         std::process::exit(1);
     }
 
     let err = std::process::Command::new("/usr/bin/sandbox-exec")
         .arg("-p")
         .arg(&profile)
-        .arg("/bin/bash")
-        .arg("-c")
+        .arg(&policy.shell)
+        .arg(&policy.shell_flag)
         .arg(command)
         .exec();
 
@@ -163,6 +167,8 @@ mod tests {
             max_file_size_bytes: 52_428_800,
             max_processes: 64,
             level: SandboxLevel::Standard,
+            shell: PathBuf::from("/bin/bash"),
+            shell_flag: "-c".to_string(),
         }
     }
 
@@ -0,0 +1,112 @@
+//! Network namespace isolation for spawned commands.
+//!
+//! On Linux the child is moved into a fresh, empty network namespace right
+//! after fork, so it (and everything it spawns) sees only a downed loopback
+//! interface. Unprivileged processes can't create a network namespace on
+//! their own, so when plain `unshare(CLONE_NEWNET)` is refused we fall back
+//! to a user namespace that maps the caller's own uid/gid.
+//!
+//! Other platforms have no equivalent; [`isolate_network`] is a no-op there.
+
+/// Whether this platform supports per-command network namespaces.
+pub const NETWORK_NAMESPACE_SUPPORTED: bool = cfg!(target_os = "linux");
+
+/// Arrange for `cmd` to run in a new, empty network namespace.
+///
+/// Fails closed: if the namespace can't be created the spawn itself fails,
+/// rather than silently running the command with network access.
+/// Returns `false` (and leaves `cmd` untouched) on platforms without support.
+pub fn isolate_network(cmd: &mut tokio::process::Command) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        linux_impl::apply(cmd);
+        true
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = cmd;
+        false
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux_impl {
+    use nix::libc;
+    use std::ffi::CString;
+    use std::io;
+
+    /// Paths and contents for the user namespace id maps, built before fork
+    /// so the child only performs async-signal-safe syscalls.
+    struct IdMaps {
+        setgroups: CString,
+        uid_map: CString,
+        gid_map: CString,
+        uid_line: Vec<u8>,
+        gid_line: Vec<u8>,
+    }
+
+    impl IdMaps {
+        fn current() -> Self {
+            let uid = unsafe { libc::getuid() };
+            let gid = unsafe { libc::getgid() };
+            Self {
+                setgroups: CString::new("/proc/self/setgroups").unwrap(),
+                uid_map: CString::new("/proc/self/uid_map").unwrap(),
+                gid_map: CString::new("/proc/self/gid_map").unwrap(),
+                uid_line: format!("{} {} 1", uid, uid).into_bytes(),
+                gid_line: format!("{} {} 1", gid, gid).into_bytes(),
+            }
+        }
+    }
+
+    pub(super) fn apply(cmd: &mut tokio::process::Command) {
+        let maps = IdMaps::current();
+
+        // SAFETY: the closure runs between fork and exec and only issues
+        // raw syscalls on memory prepared in the parent.
+        unsafe {
+            cmd.pre_exec(move || unshare_network(&maps));
+        }
+    }
+
+    fn unshare_network(maps: &IdMaps) -> io::Result<()> {
+        // Privileged (CAP_SYS_ADMIN) path
+        if unsafe { libc::unshare(libc::CLONE_NEWNET) } == 0 {
+            return Ok(());
+        }
+
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::EPERM) {
+            return Err(err);
+        }
+
+        // Unprivileged path: a user namespace grants CAP_SYS_ADMIN over the
+        // new network namespace. Map our own ids so file ownership is unchanged.
+        if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        write_file(&maps.setgroups, b"deny")?;
+        write_file(&maps.uid_map, &maps.uid_line)?;
+        write_file(&maps.gid_map, &maps.gid_line)?;
+        Ok(())
+    }
+
+    fn write_file(path: &CString, contents: &[u8]) -> io::Result<()> {
+        let fd = unsafe { libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let written = unsafe { libc::write(fd, contents.as_ptr().cast(), contents.len()) };
+        let result = if written == contents.len() as isize {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        };
+
+        unsafe { libc::close(fd) };
+        result
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use localgpt_core::config::SandboxConfig;
 
@@ -70,6 +70,22 @@ pub struct SandboxPolicy {
 
     /// Enforcement level.
     pub level: SandboxLevel,
+
+    /// Shell the command is run with, as `<shell> <shell_flag> <command>`.
+    #[serde(default = "default_shell")]
+    pub shell: PathBuf,
+
+    /// Flag that makes `shell` run its next argument as a command.
+    #[serde(default = "default_shell_flag")]
+    pub shell_flag: String,
+}
+
+fn default_shell() -> PathBuf {
+    PathBuf::from("/bin/bash")
+}
+
+fn default_shell_flag() -> String {
+    "-c".to_string()
 }
 
 /// Default credential directories to deny access to.
@@ -185,20 +201,111 @@ pub fn build_policy(
         max_file_size_bytes: config.max_file_size_bytes,
         max_processes: config.max_processes,
         level,
+        shell: default_shell(),
+        shell_flag: default_shell_flag(),
     }
 }
 
 /// Check if a path falls within any of the credential deny paths.
-pub fn is_path_denied(path: &std::path::Path, policy: &SandboxPolicy) -> bool {
-    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+///
+/// Both `path` and every deny path are resolved with [`resolve_path`]
+/// before comparing, so a deny path that is itself a symlink, a symlink
+/// pointing into a denied directory, and a not-yet-created file under a
+/// symlinked directory all match the same way.
+///
+/// macOS and Windows filesystems are case-insensitive by default, and
+/// canonicalization there doesn't normalize case, so the prefix check
+/// ignores case on those platforms.
+///
+/// A path that can't be made absolute (no current directory) is treated
+/// as denied.
+pub fn is_path_denied(path: &Path, policy: &SandboxPolicy) -> bool {
+    let Ok(resolved) = resolve_path(path) else {
+        return true;
+    };
+    policy
+        .deny_paths
+        .iter()
+        .any(|deny| resolve_path(deny).is_ok_and(|deny| path_starts_with(&resolved, &deny)))
+}
+
+/// Check if any credential deny path lies at or under `path`, so moving or
+/// recursively deleting `path` would take a denied directory with it.
+///
+/// Each deny path counts both where it resolves to and where it sits (its
+/// resolved parent plus its own name), so a deny path that is a symlink is
+/// covered at both ends. Fails closed like [`is_path_denied`].
+pub fn contains_denied_path(path: &Path, policy: &SandboxPolicy) -> bool {
+    let Ok(resolved) = resolve_path(path) else {
+        return true;
+    };
+    policy.deny_paths.iter().any(|deny| {
+        let location = deny
+            .parent()
+            .zip(deny.file_name())
+            .and_then(|(parent, name)| resolve_path(parent).ok().map(|p| p.join(name)));
+        resolve_path(deny)
+            .ok()
+            .into_iter()
+            .chain(location)
+            .any(|deny| path_starts_with(&deny, &resolved))
+    })
+}
 
-    for deny in &policy.deny_paths {
-        let deny_canonical = deny.canonicalize().unwrap_or_else(|_| deny.to_path_buf());
-        if canonical.starts_with(&deny_canonical) {
-            return true;
+/// Resolve `path` to an absolute path with all symlinks followed.
+///
+/// Unlike [`Path::canonicalize`] this also works for paths that don't
+/// exist yet: the longest existing ancestor is canonicalized and the
+/// remaining components are appended (with `.`/`..` applied lexically).
+/// Relative paths are anchored at the current directory first, so the
+/// result is always absolute.
+pub fn resolve_path(path: &Path) -> std::io::Result<PathBuf> {
+    let path = std::path::absolute(path)?;
+    if let Ok(canonical) = path.canonicalize() {
+        return Ok(canonical);
+    }
+
+    for ancestor in path.ancestors().skip(1) {
+        if ancestor.as_os_str().is_empty() {
+            continue;
+        }
+        if let Ok(canonical) = ancestor.canonicalize() {
+            let rest = path.strip_prefix(ancestor).unwrap_or(&path);
+            return Ok(normalize_lexically(&canonical.join(rest)));
         }
     }
-    false
+
+    Ok(normalize_lexically(&path))
+}
+
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn path_starts_with(path: &Path, prefix: &Path) -> bool {
+    let mut components = path.components();
+    prefix.components().all(|p| {
+        components.next().is_some_and(|c| {
+            c.as_os_str().to_string_lossy().to_lowercase()
+                == p.as_os_str().to_string_lossy().to_lowercase()
+        })
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn path_starts_with(path: &Path, prefix: &Path) -> bool {
+    path.starts_with(prefix)
 }
 
 #[cfg(test)]
@@ -221,8 +328,10 @@ mod tests {
 
     #[test]
     fn test_build_policy_disabled() {
-        let mut config = SandboxConfig::default();
-        config.enabled = false;
+        let config = SandboxConfig {
+            enabled: false,
+            ..Default::default()
+        };
         let workspace = PathBuf::from("/home/user/project");
         let policy = build_policy(&config, &workspace, SandboxLevel::None);
 
@@ -274,8 +383,153 @@ mod tests {
         // These use the deny list which contains home-relative paths
         // We test with raw paths since canonicalize may fail for non-existent paths
         let ssh_key = home.join(".ssh/id_rsa");
-        // is_path_denied uses canonicalize which may fail for non-existent paths,
-        // but it falls back to the raw path — so starts_with still works
+        // is_path_denied resolves the longest existing ancestor for
+        // non-existent paths, so starts_with still works
         assert!(is_path_denied(&ssh_key, &policy));
     }
+
+    /// Scratch directory with symlinks resolved (macOS /tmp is itself a symlink).
+    #[cfg(unix)]
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("localgpt-policy-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[cfg(unix)]
+    fn policy_denying(path: PathBuf) -> SandboxPolicy {
+        let mut policy = build_policy(
+            &SandboxConfig::default(),
+            &PathBuf::from("/tmp/test"),
+            SandboxLevel::Standard,
+        );
+        policy.deny_paths = vec![path];
+        policy
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_is_path_denied_symlinked_deny_dir() {
+        use std::os::unix::fs::symlink;
+
+        let dir = scratch_dir("deny-link");
+        let real = dir.join("real-secrets");
+        std::fs::create_dir(&real).unwrap();
+        std::fs::write(real.join("key"), "x").unwrap();
+        let link = dir.join("secrets");
+        symlink(&real, &link).unwrap();
+
+        // Deny list names the symlink; access goes through the real path
+        let policy = policy_denying(link.clone());
+        assert!(is_path_denied(&real.join("key"), &policy));
+        assert!(is_path_denied(&link.join("key"), &policy));
+        // Not-yet-created files resolve through the symlink too
+        assert!(is_path_denied(&link.join("new-file"), &policy));
+        assert!(is_path_denied(&real.join("new-dir/new-file"), &policy));
+        // Siblings are unaffected
+        assert!(!is_path_denied(&dir.join("other"), &policy));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_is_path_denied_symlinked_file_into_deny_dir() {
+        use std::os::unix::fs::symlink;
+
+        let dir = scratch_dir("file-link");
+        let secrets = dir.join("secrets");
+        std::fs::create_dir(&secrets).unwrap();
+        std::fs::write(secrets.join("key"), "x").unwrap();
+        let workspace = dir.join("workspace");
+        std::fs::create_dir(&workspace).unwrap();
+        let alias = workspace.join("innocent.txt");
+        symlink(secrets.join("key"), &alias).unwrap();
+
+        let policy = policy_denying(secrets.clone());
+        assert!(is_path_denied(&alias, &policy));
+        // `..` escapes are resolved before comparing
+        assert!(is_path_denied(&workspace.join("../secrets/key"), &policy));
+        assert!(!is_path_denied(&workspace.join("notes.txt"), &policy));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_path_nonexistent_is_normalized() {
+        let resolved = resolve_path(Path::new("/nonexistent-localgpt/a/./b/../c")).unwrap();
+        assert!(resolved.ends_with("a/c"));
+    }
+
+    #[test]
+    fn test_is_path_denied_relative_path_through_missing_dir() {
+        let cwd = std::env::current_dir().unwrap().canonicalize().unwrap();
+        let deny = cwd.join(".localgpt-test-ssh");
+        let mut policy = build_policy(
+            &SandboxConfig::default(),
+            &PathBuf::from("/tmp/test"),
+            SandboxLevel::Standard,
+        );
+        policy.deny_paths = vec![deny.clone()];
+
+        // No ancestor of the relative path exists until it's anchored at cwd
+        let sneaky = Path::new("localgpt-missing-dir/../.localgpt-test-ssh/authorized_keys");
+        assert_eq!(resolve_path(sneaky).unwrap(), deny.join("authorized_keys"));
+        assert!(is_path_denied(sneaky, &policy));
+        assert!(!is_path_denied(
+            Path::new("localgpt-missing-dir/../notes.txt"),
+            &policy
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_contains_denied_path() {
+        use std::os::unix::fs::symlink;
+
+        let dir = scratch_dir("contains");
+        let home = dir.join("home");
+        let real = dir.join("real-ssh");
+        std::fs::create_dir_all(home.join(".config/gh")).unwrap();
+        std::fs::create_dir(&real).unwrap();
+        symlink(&real, home.join(".ssh")).unwrap();
+        let mut policy = policy_denying(home.join(".ssh"));
+        policy.deny_paths.push(home.join(".config/gh"));
+
+        // Ancestors of a deny path, however they are spelled
+        assert!(contains_denied_path(&home, &policy));
+        assert!(contains_denied_path(&dir, &policy));
+        assert!(contains_denied_path(&home.join(".config"), &policy));
+        assert!(contains_denied_path(&home.join("x/../.config"), &policy));
+        // The symlink's target is covered too
+        assert!(contains_denied_path(&real, &policy));
+        // Siblings and children of unrelated directories are not
+        assert!(!contains_denied_path(&home.join("projects"), &policy));
+        assert!(!contains_denied_path(&home.join(".config/other"), &policy));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_deny_prefix_matches_whole_components() {
+        let policy = {
+            let mut p = build_policy(
+                &SandboxConfig::default(),
+                &PathBuf::from("/tmp/test"),
+                SandboxLevel::Standard,
+            );
+            p.deny_paths = vec![PathBuf::from("/nonexistent-localgpt/ssh")];
+            p
+        };
+        assert!(is_path_denied(
+            Path::new("/nonexistent-localgpt/ssh/id_rsa"),
+            &policy
+        ));
+        assert!(!is_path_denied(
+            Path::new("/nonexistent-localgpt/ssh-backup/id_rsa"),
+            &policy
+        ));
+    }
 }
@@ -0,0 +1,81 @@
+//! Run spawned commands as a different, less privileged user.
+//!
+//! On Unix the child switches to the configured gid and uid right after
+//! fork, before it execs anything. The daemon needs the right to do so
+//! (root, or `CAP_SETUID` + `CAP_SETGID` on Linux).
+//!
+//! Fails closed: if any step of the switch fails, or the child could still
+//! regain root afterwards, the spawn itself fails and the command never
+//! runs as the daemon's own user.
+
+/// Whether this platform supports running commands as another user.
+pub const PRIVILEGE_DROP_SUPPORTED: bool = cfg!(unix);
+
+/// User and group to run a command as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunAs {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// Arrange for `cmd` to run as `run_as`, with no supplementary groups.
+///
+/// Returns `false` (and leaves `cmd` untouched) on platforms without
+/// support; callers must then refuse to run the command.
+pub fn run_as_user(cmd: &mut tokio::process::Command, run_as: RunAs) -> bool {
+    #[cfg(unix)]
+    {
+        unix_impl::apply(cmd, run_as);
+        true
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (cmd, run_as);
+        false
+    }
+}
+
+#[cfg(unix)]
+mod unix_impl {
+    use super::RunAs;
+    use nix::libc;
+    use std::io;
+
+    pub(super) fn apply(cmd: &mut tokio::process::Command, run_as: RunAs) {
+        // SAFETY: the closure runs between fork and exec and only issues
+        // raw syscalls; errors are raw OS errors, so nothing allocates.
+        unsafe {
+            cmd.pre_exec(move || switch_user(run_as));
+        }
+    }
+
+    fn switch_user(run_as: RunAs) -> io::Result<()> {
+        let uid = run_as.uid as libc::uid_t;
+        let gid = run_as.gid as libc::gid_t;
+
+        // Groups first: once the uid changes we lose the right to change them
+        if unsafe { libc::setgroups(1, &gid) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { libc::setgid(gid) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { libc::setuid(uid) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Real and effective ids must all have changed, and root must be
+        // out of reach for good
+        let switched = unsafe {
+            libc::getuid() == uid
+                && libc::geteuid() == uid
+                && libc::getgid() == gid
+                && libc::getegid() == gid
+        };
+        if !switched || (uid != 0 && unsafe { libc::setuid(0) } == 0) {
+            return Err(io::Error::from_raw_os_error(libc::EPERM));
+        }
+        Ok(())
+    }
+}
@@ -6,11 +6,13 @@
 use anyhow::Result;
 use axum::{
     Router,
+    body::{Body, HttpBody},
     extract::{
-        Path, Query, State,
+        ConnectInfo, Path, Query, State,
         ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
     },
-    http::{StatusCode, header},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::{self, Next},
     response::{
         IntoResponse, Json, Response,
         sse::{Event, Sse},
@@ -23,18 +25,22 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::convert::Infallible;
-use std::net::SocketAddr;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{debug, info};
 
-use localgpt_core::agent::{Agent, AgentConfig, StreamEvent, extract_tool_detail};
+use localgpt_core::agent::{
+    Agent, AgentConfig, StreamEvent, extract_tool_detail, providers, tools,
+};
 use localgpt_core::concurrency::{TurnGate, WorkspaceLock};
 use localgpt_core::config::Config;
 use localgpt_core::heartbeat::{HeartbeatStatus, get_last_heartbeat_event};
 use localgpt_core::memory::MemoryManager;
+use localgpt_core::security;
 
 /// Embedded UI assets
 #[derive(RustEmbed)]
@@ -50,6 +56,22 @@ const MAX_SESSIONS: usize = 100;
 /// Agent ID for HTTP sessions
 const HTTP_AGENT_ID: &str = "http";
 
+/// Largest response body buffered to compute an ETag
+const ETAG_MAX_BODY_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Default and maximum page size for `GET /api/audit`
+const AUDIT_DEFAULT_LIMIT: usize = 100;
+const AUDIT_MAX_LIMIT: usize = 1000;
+
+/// How often `GET /api/audit?follow=true` checks for new entries
+const AUDIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a `/health/ready` result is reused before re-running the checks
+const READINESS_CACHE_TTL: Duration = Duration::from_secs(2);
+
+/// Rate-limit buckets untouched for this long are dropped by the cleanup task
+const RATE_LIMIT_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 pub struct Server {
     config: Config,
     turn_gate: TurnGate,
@@ -71,6 +93,72 @@ struct AppState {
     turn_gate: TurnGate,
     /// Cross-process workspace lock
     workspace_lock: WorkspaceLock,
+    /// Per-client request budget for `/api/*` and `/health`
+    rate_limiter: RateLimiter,
+    /// Last `/health/ready` result, reused for `READINESS_CACHE_TTL`
+    readiness: std::sync::Mutex<Option<(Instant, ReadinessResponse)>>,
+    /// Built-in plus `security.secret_patterns` formats, for redacting
+    /// audit entries
+    secret_scanner: security::SecretScanner,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket per client IP. Each bucket holds up to one minute's worth
+/// of requests and refills continuously at `per_minute / 60` per second.
+struct RateLimiter {
+    per_minute: u32,
+    buckets: std::sync::Mutex<HashMap<IpAddr, TokenBucket>>,
+}
+
+impl RateLimiter {
+    fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            buckets: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take one token for `client`. On refusal, returns how long until the
+    /// next token is available.
+    fn check(&self, client: IpAddr) -> Result<(), Duration> {
+        if self.per_minute == 0 {
+            return Ok(());
+        }
+        let capacity = f64::from(self.per_minute);
+        let per_second = capacity / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(client).or_insert(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+
+    /// Drop buckets that have been idle long enough to be full again.
+    fn cleanup_idle(&self) {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let before_count = buckets.len();
+        buckets.retain(|_, bucket| bucket.last_refill.elapsed() < RATE_LIMIT_IDLE_TIMEOUT);
+        let removed = before_count - buckets.len();
+        if removed > 0 {
+            debug!("Dropped {} idle rate-limit buckets", removed);
+        }
+    }
 }
 
 impl Server {
@@ -97,12 +185,28 @@ impl Server {
 
         let workspace_lock = WorkspaceLock::new()?;
 
+        if let Some(ref profile) = self.config.active_profile {
+            info!("Using config profile: {}", profile);
+            if let Err(e) = security::append_audit_entry_with_detail(
+                &self.config.paths.state_dir,
+                security::AuditAction::ProfileActivated,
+                "",
+                "server",
+                Some(profile),
+            ) {
+                tracing::warn!("Failed to audit profile activation: {}", e);
+            }
+        }
+
         let state = Arc::new(AppState {
             config: self.config.clone(),
             sessions: Mutex::new(HashMap::new()),
             memory,
             turn_gate: self.turn_gate.clone(),
             workspace_lock,
+            rate_limiter: RateLimiter::new(self.config.server.rate_limit_per_minute),
+            readiness: std::sync::Mutex::new(None),
+            secret_scanner: security::SecretScanner::from_config(&self.config)?,
         });
 
         // Load persisted sessions on startup
@@ -117,6 +221,7 @@ impl Server {
             loop {
                 interval.tick().await;
                 cleanup_expired_sessions(&cleanup_state).await;
+                cleanup_state.rate_limiter.cleanup_idle();
             }
         });
 
@@ -144,6 +249,8 @@ impl Server {
             .route("/egui/{*path}", get(serve_egui_file))
             // API routes
             .route("/health", get(health_check))
+            .route("/health/live", get(health_live))
+            .route("/health/ready", get(health_ready))
             .route("/api/sessions", post(create_session))
             .route("/api/sessions", get(list_sessions))
             .route("/api/sessions/{session_id}", delete(delete_session))
@@ -158,6 +265,7 @@ impl Server {
             .route("/api/chat", post(chat))
             .route("/api/chat/stream", post(chat_stream))
             .route("/api/ws", get(websocket_handler))
+            .route("/api/tools", get(list_tools))
             .route("/api/memory/search", get(memory_search))
             .route("/api/memory/stats", get(memory_stats))
             .route("/api/memory/reindex", post(memory_reindex))
@@ -167,7 +275,17 @@ impl Server {
             .route("/api/saved-sessions", get(list_saved_sessions))
             .route("/api/saved-sessions/{session_id}", get(get_saved_session))
             .route("/api/logs/daemon", get(get_daemon_logs))
+            .layer(middleware::from_fn(conditional_get))
+            .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
             .layer(cors)
+            // Local-only routes, outside the permissive CORS layer
+            .merge(
+                Router::new()
+                    .route("/api/audit", get(get_audit))
+                    .layer(middleware::from_fn(require_local))
+                    .layer(middleware::from_fn(conditional_get))
+                    .layer(middleware::from_fn_with_state(state.clone(), rate_limit)),
+            )
             .with_state(state);
 
         let addr: SocketAddr =
@@ -176,7 +294,11 @@ impl Server {
         info!("Starting HTTP server on http://{}", addr);
 
         let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(listener, app).await?;
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
 
         Ok(())
     }
@@ -338,8 +460,241 @@ async fn get_or_create_session(
 }
 
 // Health check endpoint
-async fn health_check() -> &'static str {
-    "OK"
+async fn health_check(State(state): State<Arc<AppState>>) -> String {
+    match state.config.active_profile {
+        Some(ref profile) => format!("OK (profile: {})", profile),
+        None => "OK".to_string(),
+    }
+}
+
+// Liveness: the process is up and serving requests
+async fn health_live() -> StatusCode {
+    StatusCode::OK
+}
+
+#[derive(Clone, Serialize)]
+struct ReadinessCheck {
+    name: &'static str,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct ReadinessResponse {
+    ready: bool,
+    checks: Vec<ReadinessCheck>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failed: Vec<&'static str>,
+}
+
+// Readiness: memory index opens, the default model has a configured
+// provider, and the state dir is writable. Results are cached briefly so
+// orchestrator probes don't repeat the checks on every request.
+async fn health_ready(State(state): State<Arc<AppState>>) -> Response {
+    let cached = state
+        .readiness
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .filter(|(at, _)| at.elapsed() < READINESS_CACHE_TTL)
+        .map(|(_, report)| report.clone());
+
+    let report = match cached {
+        Some(report) => report,
+        None => {
+            let report = check_readiness(&state);
+            *state.readiness.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some((Instant::now(), report.clone()));
+            report
+        }
+    };
+
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report)).into_response()
+}
+
+fn check_readiness(state: &AppState) -> ReadinessResponse {
+    let check = |name: &'static str, result: Result<(), anyhow::Error>| ReadinessCheck {
+        name,
+        ok: result.is_ok(),
+        error: result.err().map(|e| e.to_string()),
+    };
+
+    let checks = vec![
+        check("memory", state.memory.chunk_count().map(|_| ())),
+        check(
+            "provider",
+            providers::create_provider(&state.config.agent.default_model, &state.config)
+                .map(|_| ()),
+        ),
+        check(
+            "state_dir",
+            check_dir_writable(&state.config.paths.state_dir),
+        ),
+    ];
+
+    let failed: Vec<&'static str> = checks.iter().filter(|c| !c.ok).map(|c| c.name).collect();
+    ReadinessResponse {
+        ready: failed.is_empty(),
+        checks,
+        failed,
+    }
+}
+
+fn check_dir_writable(dir: &std::path::Path) -> Result<(), anyhow::Error> {
+    let probe = dir.join(format!(".health-probe-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)?;
+    Ok(())
+}
+
+// Rate limiting: per-client token buckets
+//
+// Requests to /api/* and /health spend a token from the caller's bucket.
+// An empty bucket yields 429 with Retry-After in whole seconds; the UI
+// assets are served without limit.
+async fn rate_limit(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if !(path.starts_with("/api/") || path.starts_with("/health")) {
+        return next.run(request).await;
+    }
+
+    match state.rate_limiter.check(addr.ip()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            debug!(
+                "Rate limited {} on {} (retry in {}s)",
+                addr.ip(),
+                path,
+                secs
+            );
+            let mut response = AppError(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Rate limit exceeded".to_string(),
+            )
+            .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+            response
+        }
+    }
+}
+
+// Local-only routes: the caller must connect over loopback and address
+// the server by a loopback name, and a browser caller must be on a page
+// served from localhost, so other sites the user visits can't read them
+// even though the server has no auth. The Host check covers DNS
+// rebinding, where a same-origin GET carries no Origin at all.
+async fn require_local(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    let local_origin = request.headers().get(header::ORIGIN).is_none_or(|origin| {
+        origin
+            .to_str()
+            .ok()
+            .and_then(|o| o.parse::<axum::http::Uri>().ok())
+            .and_then(|uri| uri.host().map(is_loopback_host))
+            .unwrap_or(false)
+    });
+    if !addr.ip().is_loopback() || !addressed_to_loopback(&request) || !local_origin {
+        debug!(
+            "Refused {} from {} (not local)",
+            request.uri().path(),
+            addr.ip()
+        );
+        return AppError(
+            StatusCode::FORBIDDEN,
+            "This endpoint is only available to local clients".to_string(),
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+/// Whether the request's Host (or HTTP/2 authority) names a loopback host.
+fn addressed_to_loopback(request: &axum::extract::Request) -> bool {
+    request
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .or_else(|| request.uri().authority().map(|a| a.as_str()))
+        .and_then(|host| host.parse::<axum::http::uri::Authority>().ok())
+        .is_some_and(|authority| is_loopback_host(authority.host()))
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+// Conditional GET: weak ETags for read-only API responses
+//
+// Successful GET /api/* responses with a known, bounded length get an
+// ETag over their body. A matching If-None-Match turns them into a 304.
+// Streaming responses (SSE, WebSocket upgrades) pass through untouched.
+async fn conditional_get(request: axum::extract::Request, next: Next) -> Response {
+    let eligible = request.method() == Method::GET && request.uri().path().starts_with("/api/");
+    let if_none_match = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let response = next.run(request).await;
+    if !eligible || response.status() != StatusCode::OK {
+        return response;
+    }
+    match response.body().size_hint().exact() {
+        Some(len) if len <= ETAG_MAX_BODY_BYTES => {}
+        _ => return response,
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    };
+
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let etag = format!("W/\"{:016x}\"", hasher.finish());
+
+    if if_none_match.is_some_and(|tags| etag_matches(&tags, &etag)) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    if let Ok(value) = etag.parse() {
+        parts.headers.insert(header::ETAG, value);
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Weak comparison (RFC 9110 §13.1.2) against an If-None-Match list.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
 }
 
 // Serve UI index.html at root
@@ -388,6 +743,8 @@ struct StatusResponse {
     model: String,
     memory_chunks: usize,
     active_sessions: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
 }
 
 async fn status(State(state): State<Arc<AppState>>) -> Json<StatusResponse> {
@@ -398,6 +755,7 @@ async fn status(State(state): State<Arc<AppState>>) -> Json<StatusResponse> {
         model: state.config.agent.default_model.clone(),
         memory_chunks: state.memory.chunk_count().unwrap_or(0),
         active_sessions: sessions.len(),
+        profile: state.config.active_profile.clone(),
     })
 }
 
@@ -893,6 +1251,46 @@ fn memory_search_inner(
     })
 }
 
+// Tool listing endpoint
+#[derive(Serialize)]
+struct ToolInfo {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+    output_schema: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct ToolsResponse {
+    tools: Vec<ToolInfo>,
+}
+
+async fn list_tools(State(state): State<Arc<AppState>>) -> Response {
+    // Same tool set HTTP sessions get (see Agent::new)
+    let memory = Arc::new(state.memory.clone());
+    let tools = match tools::create_safe_tools(&state.config, Some(memory)) {
+        Ok(tools) => tools,
+        Err(e) => {
+            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+        }
+    };
+
+    let tools = tools
+        .iter()
+        .map(|tool| {
+            let schema = tool.schema();
+            ToolInfo {
+                name: schema.name,
+                description: schema.description,
+                parameters: schema.parameters,
+                output_schema: tool.output_schema(),
+            }
+        })
+        .collect();
+
+    Json(ToolsResponse { tools }).into_response()
+}
+
 // Memory stats endpoint
 #[derive(Serialize)]
 struct StatsResponse {
@@ -1245,19 +1643,17 @@ struct DaemonLogsResponse {
     file_size_bytes: u64,
 }
 
-async fn get_daemon_logs(Query(query): Query<LogsQuery>) -> Response {
-    use localgpt_core::agent::get_state_dir;
+async fn get_daemon_logs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LogsQuery>,
+) -> Response {
     use std::fs::File;
     use std::io::{BufRead, BufReader};
 
     let lines_requested = query.lines.unwrap_or(200).min(1000);
 
-    let state_dir = match get_state_dir() {
-        Ok(dir) => dir,
-        Err(e) => {
-            return AppError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
-        }
-    };
+    // Honor an explicit `state_dir` from config
+    let state_dir = state.config.paths.state_dir.clone();
 
     // Use date-based log file (matches daemon.rs)
     let date = chrono::Local::now().format("%Y-%m-%d");
@@ -1307,6 +1703,144 @@ async fn get_daemon_logs(Query(query): Query<LogsQuery>) -> Response {
     .into_response()
 }
 
+// Audit log endpoint - paged, filtered, or tailed over SSE
+#[derive(Deserialize)]
+struct AuditParams {
+    /// RFC 3339; entries at or after this time
+    since: Option<String>,
+    /// RFC 3339; entries before this time
+    until: Option<String>,
+    /// Comma-separated actions, e.g. `tool_mutation,path_denied`
+    action: Option<String>,
+    limit: Option<usize>,
+    /// `next_cursor` from a previous page
+    cursor: Option<u64>,
+    /// Stream entries as they are appended instead of returning a page
+    #[serde(default)]
+    follow: bool,
+}
+
+#[derive(Serialize)]
+struct AuditResponse {
+    entries: Vec<security::AuditEntry>,
+    next_cursor: u64,
+    has_more: bool,
+}
+
+async fn get_audit(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<AuditParams>,
+) -> Response {
+    let mut query = match audit_query(&params) {
+        Ok(query) => query,
+        Err(e) => return AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+    let state_dir = state.config.paths.state_dir.clone();
+
+    if !params.follow {
+        query.limit = params
+            .limit
+            .unwrap_or(AUDIT_DEFAULT_LIMIT)
+            .clamp(1, AUDIT_MAX_LIMIT);
+        return match security::read_audit_entries(&state_dir, &query) {
+            Ok(page) => Json(AuditResponse {
+                entries: page
+                    .entries
+                    .into_iter()
+                    .map(|entry| redact_audit_entry(&state.secret_scanner, entry))
+                    .collect(),
+                next_cursor: page.next_cursor,
+                has_more: page.has_more,
+            })
+            .into_response(),
+            Err(e) => AppError(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        };
+    }
+
+    // Without a cursor, tail from the current end of the log
+    if params.cursor.is_none() {
+        query.cursor = std::fs::metadata(security::audit_file_path(&state_dir))
+            .map(|m| m.len())
+            .unwrap_or(0);
+    }
+    query.limit = AUDIT_MAX_LIMIT;
+
+    let stream = async_stream::stream! {
+        loop {
+            match security::read_audit_entries(&state_dir, &query) {
+                Ok(page) => {
+                    query.cursor = page.next_cursor;
+                    let count = page.entries.len();
+                    for (i, entry) in page.entries.into_iter().enumerate() {
+                        let entry = redact_audit_entry(&state.secret_scanner, entry);
+                        let data = serde_json::to_string(&entry).unwrap_or_default();
+                        let mut event = Event::default().event("audit").data(data);
+                        // The id after a batch is the cursor to resume from
+                        if i + 1 == count {
+                            event = event.id(page.next_cursor.to_string());
+                        }
+                        yield Ok::<Event, Infallible>(event);
+                    }
+                    if page.has_more {
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    yield Ok(Event::default().data(json!({"error": e.to_string()}).to_string()));
+                    break;
+                }
+            }
+            tokio::time::sleep(AUDIT_POLL_INTERVAL).await;
+        }
+    };
+
+    Sse::new(stream)
+        .keep_alive(axum::response::sse::KeepAlive::default())
+        .into_response()
+}
+
+fn audit_query(params: &AuditParams) -> Result<security::AuditQuery> {
+    let parse_time = |value: &Option<String>| -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        value
+            .as_deref()
+            .map(|s| {
+                chrono::DateTime::parse_from_rfc3339(s)
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .map_err(|e| anyhow::anyhow!("Invalid timestamp '{}': {}", s, e))
+            })
+            .transpose()
+    };
+    let actions = params
+        .action
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(|a| {
+            serde_json::from_value(serde_json::Value::String(a.to_string()))
+                .map_err(|_| anyhow::anyhow!("Unknown audit action '{}'", a))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(security::AuditQuery {
+        since: parse_time(&params.since)?,
+        until: parse_time(&params.until)?,
+        actions,
+        cursor: params.cursor.unwrap_or(0),
+        limit: 0,
+    })
+}
+
+fn redact_audit_entry(
+    scanner: &security::SecretScanner,
+    mut entry: security::AuditEntry,
+) -> security::AuditEntry {
+    entry.source = scanner.redact_secrets(&entry.source);
+    entry.detail = entry.detail.map(|d| scanner.redact_secrets(&d));
+    entry
+}
+
 // WebSocket handler
 async fn websocket_handler(
     ws: WebSocketUpgrade,
@@ -1532,3 +2066,161 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
 
     debug!("WebSocket connection closed");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+    /// Pretend `secs` seconds have passed since `client`'s last request.
+    fn age_bucket(limiter: &RateLimiter, client: IpAddr, secs: f64) {
+        let mut buckets = limiter.buckets.lock().unwrap();
+        let bucket = buckets.get_mut(&client).unwrap();
+        bucket.last_refill -= Duration::from_secs_f64(secs);
+    }
+
+    #[test]
+    fn rate_limiter_refills_over_time() {
+        let limiter = RateLimiter::new(60);
+        for _ in 0..60 {
+            assert!(limiter.check(CLIENT).is_ok());
+        }
+        assert!(limiter.check(CLIENT).is_err());
+
+        // One token per second at 60/minute
+        age_bucket(&limiter, CLIENT, 1.0);
+        assert!(limiter.check(CLIENT).is_ok());
+        assert!(limiter.check(CLIENT).is_err());
+
+        // Refill is capped at one minute's worth
+        age_bucket(&limiter, CLIENT, 3600.0);
+        for _ in 0..60 {
+            assert!(limiter.check(CLIENT).is_ok());
+        }
+        assert!(limiter.check(CLIENT).is_err());
+    }
+
+    #[test]
+    fn rate_limiter_retry_after_is_time_to_next_token() {
+        let limiter = RateLimiter::new(6);
+        for _ in 0..6 {
+            assert!(limiter.check(CLIENT).is_ok());
+        }
+        // 6/minute refills a token every 10s
+        let wait = limiter.check(CLIENT).unwrap_err();
+        assert!(wait <= Duration::from_secs(10), "{:?}", wait);
+        assert!(wait > Duration::from_secs(9), "{:?}", wait);
+
+        age_bucket(&limiter, CLIENT, 4.0);
+        let wait = limiter.check(CLIENT).unwrap_err();
+        assert!(wait <= Duration::from_secs(6), "{:?}", wait);
+        assert!(wait > Duration::from_secs(5), "{:?}", wait);
+    }
+
+    #[test]
+    fn rate_limiter_tracks_clients_separately() {
+        let limiter = RateLimiter::new(1);
+        let other: IpAddr = "10.0.0.2".parse().unwrap();
+        assert!(limiter.check(CLIENT).is_ok());
+        assert!(limiter.check(CLIENT).is_err());
+        assert!(limiter.check(other).is_ok());
+    }
+
+    #[test]
+    fn rate_limiter_zero_per_minute_is_unlimited() {
+        let limiter = RateLimiter::new(0);
+        for _ in 0..1000 {
+            assert!(limiter.check(CLIENT).is_ok());
+        }
+        assert!(limiter.buckets.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn etag_matches_weakly() {
+        let etag = "W/\"00000000000000ab\"";
+        assert!(etag_matches(etag, etag));
+        assert!(etag_matches("\"00000000000000ab\"", etag));
+        assert!(etag_matches("\"other\", W/\"00000000000000ab\"", etag));
+        assert!(!etag_matches("W/\"00000000000000cd\"", etag));
+        assert!(!etag_matches("", etag));
+    }
+
+    #[test]
+    fn etag_matches_wildcard() {
+        assert!(etag_matches("*", "W/\"00000000000000ab\""));
+        assert!(etag_matches("\"other\", *", "W/\"00000000000000ab\""));
+    }
+
+    #[test]
+    fn loopback_hosts() {
+        assert!(is_loopback_host("localhost"));
+        assert!(is_loopback_host("LocalHost"));
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("[::1]"));
+        assert!(is_loopback_host("::1"));
+        assert!(!is_loopback_host("[::2]"));
+        assert!(!is_loopback_host("[2001:db8::1]"));
+        assert!(!is_loopback_host("192.168.1.10"));
+        assert!(!is_loopback_host("localhost.example.com"));
+    }
+
+    #[test]
+    fn host_header_must_name_loopback() {
+        let request = |host: &str| {
+            axum::http::Request::builder()
+                .uri("/api/audit")
+                .header(header::HOST, host)
+                .body(Body::empty())
+                .unwrap()
+        };
+        assert!(addressed_to_loopback(&request("localhost:31327")));
+        assert!(addressed_to_loopback(&request("127.0.0.1")));
+        assert!(addressed_to_loopback(&request("[::1]:31327")));
+        // A DNS-rebound page keeps its own hostname in Host
+        assert!(!addressed_to_loopback(&request("evil.example")));
+        assert!(!addressed_to_loopback(&request("evil.example:31327")));
+
+        let no_host = axum::http::Request::builder()
+            .uri("/api/audit")
+            .body(Body::empty())
+            .unwrap();
+        assert!(!addressed_to_loopback(&no_host));
+    }
+
+    fn audit_params(value: serde_json::Value) -> AuditParams {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn audit_query_parses_actions_and_times() {
+        let query = audit_query(&audit_params(json!({
+            "action": "tool_mutation, path_denied",
+            "since": "2026-01-01T00:00:00Z",
+            "cursor": 42,
+        })))
+        .unwrap();
+        assert_eq!(
+            query.actions,
+            vec![
+                security::AuditAction::ToolMutation,
+                security::AuditAction::PathDenied,
+            ]
+        );
+        assert!(query.since.is_some());
+        assert!(query.until.is_none());
+        assert_eq!(query.cursor, 42);
+    }
+
+    #[test]
+    fn audit_query_rejects_unknown_action() {
+        let err =
+            audit_query(&audit_params(json!({ "action": "tool_mutation,bogus" }))).unwrap_err();
+        assert!(err.to_string().contains("Unknown audit action 'bogus'"));
+    }
+
+    #[test]
+    fn audit_query_rejects_bad_timestamp() {
+        assert!(audit_query(&audit_params(json!({ "until": "yesterday" }))).is_err());
+    }
+}